@@ -0,0 +1,290 @@
+//! Locate `libpostal` and instruct cargo how to link against it.
+//!
+//! The library is searched in the following order:
+//!
+//! 1. `LIBPOSTAL_LIB_DIR`: directory containing `libpostal.so`/`libpostal.a`.
+//! 2. `LIBPOSTAL_DIR`: installation prefix, i.e. `$LIBPOSTAL_DIR/lib`.
+//! 3. `pkg-config`, through `libpostal.pc`.
+//!
+//! Set `LIBPOSTAL_STATIC=1` to link statically instead of dynamically.
+//!
+//! With the `vendored` feature, the library is instead compiled from the source
+//! snapshot in `vendor/libpostal` and linked statically. The default data directory
+//! is then taken from `LIBPOSTAL_DATA_DIR` at build time.
+//!
+//! With the `bindgen` feature, the FFI bindings are generated from `libpostal.h`,
+//! searched in `LIBPOSTAL_INCLUDE_DIR`, `$LIBPOSTAL_DIR/include` or the
+//! `pkg-config` include paths.
+
+#![cfg_attr(feature = "vendored", allow(dead_code))]
+
+use std::env;
+use std::path::{Path, PathBuf};
+
+const INSTALL_HELP: &str = "
+Could not find libpostal.
+
+rustpostal links against the libpostal C library, which has to be installed
+separately. To install it from source:
+
+    git clone https://github.com/openvenues/libpostal
+    cd libpostal
+    ./bootstrap.sh
+    ./configure --datadir=[...some dir with a few GB of space...]
+    make -j4
+    sudo make install
+
+See https://github.com/openvenues/libpostal#installation-maclinux for details.
+
+If libpostal is already installed, point the build to it with one of:
+
+    LIBPOSTAL_LIB_DIR  directory that contains libpostal.so or libpostal.a
+    LIBPOSTAL_DIR      installation prefix, e.g. /usr/local
+    PKG_CONFIG_PATH    directory that contains libpostal.pc
+
+Set LIBPOSTAL_STATIC=1 to link libpostal statically.
+";
+
+fn env_var(name: &str) -> Option<String> {
+    println!("cargo:rerun-if-env-changed={}", name);
+    env::var(name).ok().filter(|s| !s.is_empty())
+}
+
+fn is_static() -> bool {
+    match env_var("LIBPOSTAL_STATIC") {
+        Some(s) => s != "0",
+        None => false,
+    }
+}
+
+fn has_library(dir: &Path, statik: bool) -> bool {
+    let names: &[&str] = if statik {
+        &["libpostal.a"]
+    } else {
+        &["libpostal.so", "libpostal.dylib", "libpostal.a"]
+    };
+    names.iter().any(|name| dir.join(name).exists())
+}
+
+fn link_from_dir(dir: &Path, statik: bool) {
+    if !has_library(dir, statik) {
+        panic!(
+            "\n\nlibpostal was not found in `{}`.\n{}",
+            dir.display(),
+            INSTALL_HELP
+        );
+    }
+    println!("cargo:rustc-link-search=native={}", dir.display());
+    if statik {
+        println!("cargo:rustc-link-lib=static=postal");
+        println!("cargo:rustc-link-lib=m");
+    } else {
+        println!("cargo:rustc-link-lib=dylib=postal");
+        emit_rpath(dir);
+    }
+}
+
+fn link_from_pkg_config(statik: bool) -> Result<Vec<PathBuf>, pkg_config::Error> {
+    let library = pkg_config::Config::new()
+        .statik(statik)
+        .probe("libpostal")?;
+    if !statik {
+        for dir in &library.link_paths {
+            emit_rpath(dir);
+        }
+    }
+    Ok(library.include_paths)
+}
+
+/// Embed the library path in our binaries, so that `LD_LIBRARY_PATH` is not needed.
+fn emit_rpath(dir: &Path) {
+    let target_os = env::var("CARGO_CFG_TARGET_OS").unwrap_or_default();
+    if target_os != "windows" {
+        println!("cargo:rustc-link-arg=-Wl,-rpath,{}", dir.display());
+    }
+}
+
+/// Sources of the libpostal library, relative to `vendor/libpostal/src`.
+#[cfg(feature = "vendored")]
+const VENDORED_SOURCES: &[&str] = &[
+    "strndup.c",
+    "libpostal.c",
+    "expand.c",
+    "address_dictionary.c",
+    "transliterate.c",
+    "tokens.c",
+    "trie.c",
+    "trie_search.c",
+    "trie_utils.c",
+    "string_utils.c",
+    "file_utils.c",
+    "utf8proc/utf8proc.c",
+    "normalize.c",
+    "numex.c",
+    "features.c",
+    "unicode_scripts.c",
+    "address_parser.c",
+    "address_parser_io.c",
+    "averaged_perceptron.c",
+    "crf.c",
+    "crf_context.c",
+    "sparse_matrix.c",
+    "averaged_perceptron_tagger.c",
+    "graph.c",
+    "graph_builder.c",
+    "language_classifier.c",
+    "language_features.c",
+    "logistic_regression.c",
+    "logistic.c",
+    "minibatch.c",
+    "float_utils.c",
+    "ngrams.c",
+    "place.c",
+    "near_dupe.c",
+    "double_metaphone.c",
+    "geohash/geohash.c",
+    "dedupe.c",
+    "string_similarity.c",
+    "acronyms.c",
+    "soft_tfidf.c",
+    "jaccard.c",
+    "klib/drand48.c",
+];
+
+/// Default data directory of a `make install` with the default prefix.
+#[cfg(feature = "vendored")]
+const DEFAULT_DATA_DIR: &str = "/usr/local/share/libpostal";
+
+/// Write the `config.h` that autotools would otherwise generate.
+#[cfg(feature = "vendored")]
+fn write_config_header(dir: &Path) {
+    let data_dir = env_var("LIBPOSTAL_DATA_DIR").unwrap_or_else(|| DEFAULT_DATA_DIR.to_string());
+    let target_family = env::var("CARGO_CFG_TARGET_FAMILY").unwrap_or_default();
+
+    let mut header = String::from("#define PACKAGE \"libpostal\"\n");
+    header.push_str(&format!("#define LIBPOSTAL_DATA_DIR {:?}\n", data_dir));
+    if target_family == "unix" {
+        header.push_str("#define HAVE_DIRENT_H 1\n");
+        header.push_str("#define HAVE_DRAND48 1\n");
+        header.push_str("#define HAVE_STRNDUP 1\n");
+    }
+    std::fs::create_dir_all(dir).expect("failed to create include directory");
+    std::fs::write(dir.join("config.h"), header).expect("failed to write config.h");
+}
+
+#[cfg(feature = "vendored")]
+fn vendored_build(include: &Path) -> cc::Build {
+    let target_arch = env::var("CARGO_CFG_TARGET_ARCH").unwrap_or_default();
+
+    let mut build = cc::Build::new();
+    build
+        .warnings(false)
+        .include(include)
+        .flag_if_supported("-std=gnu99")
+        .define("HAVE_CONFIG_H", None)
+        .define("LIBPOSTAL_EXPORTS", None);
+    if target_arch == "x86_64" {
+        build.define("USE_SSE", None).flag_if_supported("-msse2");
+    }
+    build
+}
+
+/// Compile the vendored libpostal sources into a static library.
+#[cfg(feature = "vendored")]
+fn link_vendored() {
+    let src = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("vendor/libpostal/src");
+    let include = PathBuf::from(env::var("OUT_DIR").unwrap()).join("include");
+    println!("cargo:rerun-if-changed={}", src.display());
+    write_config_header(&include);
+
+    // The generated scanner takes unreasonably long to compile with optimizations,
+    // and is fast enough without them.
+    vendored_build(&include)
+        .file(src.join("scanner.c"))
+        .opt_level(0)
+        .compile("postal_scanner");
+
+    let mut build = vendored_build(&include);
+    for source in VENDORED_SOURCES {
+        build.file(src.join(source));
+    }
+    build.compile("postal");
+
+    println!("cargo:rustc-link-lib=m");
+    println!("cargo:root={}", env::var("OUT_DIR").unwrap());
+    println!("cargo:include={}", src.display());
+}
+
+/// Link against a system installation, returning the include directories
+/// that were found along with it.
+#[cfg(not(feature = "vendored"))]
+fn link() -> Vec<PathBuf> {
+    let statik = is_static();
+    let mut include_paths: Vec<PathBuf> = env_var("LIBPOSTAL_INCLUDE_DIR")
+        .map(PathBuf::from)
+        .into_iter()
+        .collect();
+
+    if let Some(lib_dir) = env_var("LIBPOSTAL_LIB_DIR") {
+        link_from_dir(&PathBuf::from(lib_dir), statik);
+    } else if let Some(prefix) = env_var("LIBPOSTAL_DIR") {
+        let prefix = PathBuf::from(prefix);
+        link_from_dir(&prefix.join("lib"), statik);
+        include_paths.push(prefix.join("include"));
+    } else {
+        match link_from_pkg_config(statik) {
+            Ok(paths) => include_paths.extend(paths),
+            Err(err) => panic!(
+                "\n\npkg-config failed to find libpostal: {}\n{}",
+                err, INSTALL_HELP
+            ),
+        }
+    }
+    include_paths
+}
+
+#[cfg(feature = "vendored")]
+fn link() -> Vec<PathBuf> {
+    link_vendored();
+    Vec::new()
+}
+
+/// Generate bindings from the `libpostal.h` found in `include_paths`, or the
+/// vendored header if there is none.
+#[cfg(feature = "bindgen")]
+fn generate_bindings(include_paths: &[PathBuf]) {
+    let header = include_paths
+        .iter()
+        .map(|dir| dir.join("libpostal").join("libpostal.h"))
+        .find(|header| header.exists())
+        .unwrap_or_else(|| {
+            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("vendor/libpostal/src/libpostal.h")
+        });
+    println!("cargo:rerun-if-changed={}", header.display());
+
+    let bindings = bindgen::Builder::default()
+        .header(header.to_string_lossy())
+        .allowlist_function("libpostal_.*")
+        .allowlist_type("libpostal_.*")
+        .allowlist_var("LIBPOSTAL_.*")
+        .layout_tests(true)
+        .generate()
+        .expect("failed to generate libpostal bindings");
+    let out = PathBuf::from(env::var("OUT_DIR").unwrap()).join("bindings.rs");
+    bindings
+        .write_to_file(out)
+        .expect("failed to write libpostal bindings");
+}
+
+fn main() {
+    // docs.rs builds documentation without libpostal installed.
+    if env::var("DOCS_RS").is_ok() {
+        return;
+    }
+
+    #[allow(unused_variables)]
+    let include_paths = link();
+
+    #[cfg(feature = "bindgen")]
+    generate_bindings(&include_paths);
+}
@@ -0,0 +1,46 @@
+//! SQLite loadable extension registering the `rustpostal::sqlite` functions.
+//!
+//! ```sh
+//! cargo build --release --example rustpostal_sqlite --features sqlite-extension
+//! sqlite3
+//! sqlite> .load target/release/examples/librustpostal_sqlite
+//! sqlite> SELECT postal_component('10 Downing St, London', 'postcode');
+//! sqlite> SELECT value FROM postal_expand('10 Downing St, London', 'en');
+//! ```
+//!
+//! The models are loaded from the default data directory, or from
+//! `LIBPOSTAL_DATA_DIR` if set at runtime, and kept until the process exits.
+use std::env;
+use std::os::raw::{c_char, c_int};
+
+use rusqlite::{ffi, Connection, Error, Result};
+use rustpostal::models::{self, Models};
+
+/// Entry point for SQLite to load the extension.
+///
+/// # Safety
+///
+/// It is called by SQLite with a valid connection and API routines.
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub unsafe extern "C" fn sqlite3_extension_init(
+    db: *mut ffi::sqlite3,
+    pz_err_msg: *mut *mut c_char,
+    p_api: *mut ffi::sqlite3_api_routines,
+) -> c_int {
+    Connection::extension_init2(db, pz_err_msg, p_api, extension_init)
+}
+
+fn extension_init(db: Connection) -> Result<bool> {
+    let all = Models::BASE | Models::PARSER | Models::CLASSIFIER;
+    if !models::loaded().contains(all) {
+        let loaded = match env::var_os("LIBPOSTAL_DATA_DIR") {
+            Some(datadir) => models::load_with_datadir(all, datadir),
+            None => models::load(all),
+        };
+        loaded.map_err(|err| Error::ModuleError(err.to_string()))?;
+    }
+    rustpostal::sqlite::register_functions(&db)?;
+    // Keep the extension loaded for the lifetime of the process.
+    Ok(true)
+}
@@ -22,13 +22,147 @@
 //! ```
 use std::collections::HashMap;
 use std::ffi::{CStr, CString, NulError};
+use std::fmt;
 use std::slice::Iter;
+use std::str::FromStr;
 use std::vec::IntoIter;
 
+use libc::c_char;
+
+#[cfg(feature = "expand")]
+use crate::country::{self, Country, Subdivision};
+use crate::error::ParseLabelError;
+#[cfg(feature = "expand")]
+use crate::expand::AddressComponents;
 use crate::ffi;
+use crate::house_number::HouseNumber;
+#[cfg(feature = "expand")]
+use crate::road::RoadParts;
+#[cfg(feature = "expand")]
+use crate::subpremise::Subpremise;
+
+/// Labels of the address components returned by the parser.
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum AddressLabel {
+    /// Venue name, e.g. "Brooklyn Academy of Music".
+    House,
+    /// Category query, e.g. "restaurants".
+    Category,
+    /// Phrases like "in", "near", etc. used after a category phrase.
+    Near,
+    HouseNumber,
+    PoBox,
+    Building,
+    Entrance,
+    Staircase,
+    Level,
+    Unit,
+    Road,
+    MetroStation,
+    Suburb,
+    CityDistrict,
+    City,
+    StateDistrict,
+    Island,
+    State,
+    /// Postal code, labeled `postcode`.
+    Postcode,
+    CountryRegion,
+    Country,
+    WorldRegion,
+    Website,
+    /// Telephone number, labeled `phone`.
+    #[cfg_attr(feature = "serde", serde(rename = "phone"))]
+    Telephone,
+}
+
+impl AddressLabel {
+    /// Every label, in the order of the fields of an address.
+    pub const ALL: &'static [AddressLabel] = &[
+        AddressLabel::House,
+        AddressLabel::Category,
+        AddressLabel::Near,
+        AddressLabel::HouseNumber,
+        AddressLabel::PoBox,
+        AddressLabel::Building,
+        AddressLabel::Entrance,
+        AddressLabel::Staircase,
+        AddressLabel::Level,
+        AddressLabel::Unit,
+        AddressLabel::Road,
+        AddressLabel::MetroStation,
+        AddressLabel::Suburb,
+        AddressLabel::CityDistrict,
+        AddressLabel::City,
+        AddressLabel::StateDistrict,
+        AddressLabel::Island,
+        AddressLabel::State,
+        AddressLabel::Postcode,
+        AddressLabel::CountryRegion,
+        AddressLabel::Country,
+        AddressLabel::WorldRegion,
+        AddressLabel::Website,
+        AddressLabel::Telephone,
+    ];
+
+    /// Return the label as used by `libpostal`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AddressLabel::House => "house",
+            AddressLabel::Category => "category",
+            AddressLabel::Near => "near",
+            AddressLabel::HouseNumber => "house_number",
+            AddressLabel::PoBox => "po_box",
+            AddressLabel::Building => "building",
+            AddressLabel::Entrance => "entrance",
+            AddressLabel::Staircase => "staircase",
+            AddressLabel::Level => "level",
+            AddressLabel::Unit => "unit",
+            AddressLabel::Road => "road",
+            AddressLabel::MetroStation => "metro_station",
+            AddressLabel::Suburb => "suburb",
+            AddressLabel::CityDistrict => "city_district",
+            AddressLabel::City => "city",
+            AddressLabel::StateDistrict => "state_district",
+            AddressLabel::Island => "island",
+            AddressLabel::State => "state",
+            AddressLabel::Postcode => "postcode",
+            AddressLabel::CountryRegion => "country_region",
+            AddressLabel::Country => "country",
+            AddressLabel::WorldRegion => "world_region",
+            AddressLabel::Website => "website",
+            AddressLabel::Telephone => "phone",
+        }
+    }
+}
+
+impl fmt::Display for AddressLabel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for AddressLabel {
+    type Err = ParseLabelError;
+
+    /// Parse a label as used by `libpostal`, e.g. `house_number`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        AddressLabel::ALL
+            .iter()
+            .find(|label| label.as_str() == s)
+            .copied()
+            .ok_or_else(|| ParseLabelError::new(s))
+    }
+}
 
 /// Represents the parsing result.
 #[derive(Clone, Default, Debug, Hash, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AddressParserResponse {
     tokens: Vec<String>,
     labels: Vec<String>,
@@ -39,6 +173,31 @@ impl AddressParserResponse {
     pub fn new() -> AddressParserResponse {
         Default::default()
     }
+
+    /// Return the tokens labeled `label`, joined by spaces if there are several.
+    pub fn get(&self, label: AddressLabel) -> Option<String> {
+        let tokens: Vec<&str> = self
+            .into_iter()
+            .filter(|(l, _)| l.as_str() == label.as_str())
+            .map(|(_, token)| token.as_str())
+            .collect();
+        if tokens.is_empty() {
+            None
+        } else {
+            Some(tokens.join(" "))
+        }
+    }
+}
+
+impl std::iter::FromIterator<(AddressLabel, String)> for AddressParserResponse {
+    /// Collect `(label, token)` pairs, e.g. to build a response from other data.
+    fn from_iter<I: IntoIterator<Item = (AddressLabel, String)>>(iter: I) -> Self {
+        let (labels, tokens) = iter
+            .into_iter()
+            .map(|(label, token)| (String::from(label.as_str()), token))
+            .unzip();
+        AddressParserResponse { tokens, labels }
+    }
 }
 
 impl IntoIterator for AddressParserResponse {
@@ -115,13 +274,13 @@ impl AddressParserOptions {
 
     fn update_ffi_language(&self, options: &mut ffi::libpostal_address_parser_options) {
         if let Some(language) = &self.language {
-            options.language = language.as_ptr();
+            options.language = language.as_ptr() as *mut c_char;
         };
     }
 
     fn update_ffi_country(&self, options: &mut ffi::libpostal_address_parser_options) {
         if let Some(country) = &self.country {
-            options.country = country.as_ptr();
+            options.country = country.as_ptr() as *mut c_char;
         };
     }
 
@@ -153,30 +312,42 @@ impl AddressParserOptions {
     ///
     /// It will return an error if the address contains an internal null byte.
     pub fn parse<'b>(&self, address: &'b str) -> Result<AddressParserResponse, NulError> {
-        let c_address = CString::new(address)?;
         let mut response = AddressParserResponse::new();
-        let ptr = c_address.into_raw();
+        self.parse_with(address, &mut Vec::new(), |label, token| {
+            response.labels.push(String::from(label));
+            response.tokens.push(String::from(token));
+        })?;
+        Ok(response)
+    }
+
+    /// Parse `address`, passing each `(label, token)` pair to `visit` as
+    /// borrowed from `libpostal`, and reusing `buffer` for the C string of
+    /// the address.
+    pub(crate) fn parse_with(
+        &self,
+        address: &str,
+        buffer: &mut Vec<u8>,
+        mut visit: impl FnMut(&str, &str),
+    ) -> Result<(), NulError> {
+        let c_address = crate::c_str(buffer, address)?;
 
         let mut ffi_options = unsafe { ffi::libpostal_get_address_parser_default_options() };
         self.update_ffi_language(&mut ffi_options);
         self.update_ffi_country(&mut ffi_options);
 
-        let raw = unsafe { ffi::libpostal_parse_address(ptr, ffi_options) };
+        let raw =
+            unsafe { ffi::libpostal_parse_address(c_address.as_ptr() as *mut c_char, ffi_options) };
         if let Some(parsed) = unsafe { raw.as_ref() } {
             for i in 0..parsed.num_components {
                 let component = unsafe { CStr::from_ptr(*parsed.components.add(i)) };
                 let label = unsafe { CStr::from_ptr(*parsed.labels.add(i)) };
-                response
-                    .tokens
-                    .push(String::from(component.to_str().unwrap()));
-                response.labels.push(String::from(label.to_str().unwrap()));
+                visit(label.to_str().unwrap(), component.to_str().unwrap());
             }
         };
         unsafe {
             ffi::libpostal_address_parser_response_destroy(raw);
         }
-        let _c_address = unsafe { CString::from_raw(ptr) };
-        Ok(response)
+        Ok(())
     }
 }
 
@@ -200,7 +371,14 @@ pub fn parse_address(
 /// The only way to make one is from an `AddressParserResponse`.
 /// It implements a getter method for each label that might
 /// be included in the `AddressParserResponse`.
+///
+/// With the `serde` feature it is serialized as a map from label to token.
 #[derive(Clone, Default, Debug, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
 pub struct ParsedAddress {
     label_to_token: HashMap<String, String>,
 }
@@ -214,6 +392,13 @@ impl ParsedAddress {
         self.label_to_token.get("house_number").cloned()
     }
 
+    /// Parse the house number, see [`HouseNumber`].
+    ///
+    /// Returns `None` if there is no house number, or if it is not valid.
+    pub fn house_number_parts(&self) -> Option<HouseNumber> {
+        self.label_to_token.get("house_number")?.parse().ok()
+    }
+
     pub fn po_box(&self) -> Option<String> {
         self.label_to_token.get("po_box").cloned()
     }
@@ -297,6 +482,55 @@ impl ParsedAddress {
     }
 }
 
+#[cfg(feature = "expand")]
+impl ParsedAddress {
+    /// Resolve the country to its ISO 3166-1 codes, see [`country::find`].
+    pub fn country_code(&self) -> Option<&'static Country> {
+        country::find(self.label_to_token.get("country")?)
+    }
+
+    /// Resolve the state to its ISO 3166-2 code, within the country if it
+    /// is resolved, see [`country::find_subdivision`].
+    pub fn subdivision_code(&self) -> Option<&'static Subdivision> {
+        let state = self.label_to_token.get("state")?;
+        country::find_subdivision(self.country_code(), state)
+    }
+
+    /// Structure the unit, see [`Subpremise::parse`].
+    pub fn unit_parts(&self) -> Option<Subpremise> {
+        let unit = self.label_to_token.get("unit")?;
+        Subpremise::parse(unit, AddressComponents::UNIT)
+            .ok()
+            .flatten()
+    }
+
+    /// Structure the level, see [`Subpremise::parse`].
+    pub fn level_parts(&self) -> Option<Subpremise> {
+        let level = self.label_to_token.get("level")?;
+        Subpremise::parse(level, AddressComponents::LEVEL)
+            .ok()
+            .flatten()
+    }
+
+    /// Structure the staircase, see [`Subpremise::parse`].
+    pub fn staircase_parts(&self) -> Option<Subpremise> {
+        let staircase = self.label_to_token.get("staircase")?;
+        Subpremise::parse(staircase, AddressComponents::STAIRCASE)
+            .ok()
+            .flatten()
+    }
+
+    /// Decompose the road into street name, types and directionals, see
+    /// [`RoadParts::parse`].
+    ///
+    /// Returns `None` if there is no road or it cannot be decomposed.
+    pub fn road_parts(&self) -> Option<RoadParts> {
+        RoadParts::parse(self.label_to_token.get("road")?)
+            .ok()
+            .flatten()
+    }
+}
+
 impl From<AddressParserResponse> for ParsedAddress {
     /// Create a new `ParsedAddress` from an `AddressParserResponse`.
     fn from(response: AddressParserResponse) -> Self {
@@ -314,6 +548,86 @@ mod tests {
     use crate::error::RuntimeError;
     use crate::LibModules;
 
+    #[test]
+    fn address_label_from_str() {
+        for label in AddressLabel::ALL {
+            assert_eq!(label.as_str().parse(), Ok(*label));
+        }
+        assert_eq!("phone".parse(), Ok(AddressLabel::Telephone));
+        assert!("zip".parse::<AddressLabel>().is_err());
+    }
+
+    #[test]
+    fn address_parser_response_get() {
+        let response = AddressParserResponse {
+            tokens: vec!["flat 2".into(), "10".into(), "2nd floor".into()],
+            labels: vec!["unit".into(), "house_number".into(), "unit".into()],
+        };
+        assert_eq!(
+            response.get(AddressLabel::Unit).as_deref(),
+            Some("flat 2 2nd floor")
+        );
+        assert_eq!(response.get(AddressLabel::Road), None);
+    }
+
+    #[test]
+    fn address_parser_response_from_iter() {
+        let response: AddressParserResponse =
+            vec![(AddressLabel::Telephone, String::from("555 1234"))]
+                .into_iter()
+                .collect();
+        let pairs: Vec<(&String, &String)> = (&response).into_iter().collect();
+        assert_eq!(pairs, [(&String::from("phone"), &String::from("555 1234"))]);
+    }
+
+    #[test]
+    #[cfg(feature = "expand")]
+    fn parsed_address_iso_codes() {
+        let address = ParsedAddress::from(
+            vec![
+                (AddressLabel::State, String::from("n.y.")),
+                (AddressLabel::Country, String::from("usa")),
+            ]
+            .into_iter()
+            .collect::<AddressParserResponse>(),
+        );
+        assert_eq!(address.country_code().map(|c| c.alpha3), Some("USA"));
+        assert_eq!(address.subdivision_code().map(|s| s.code), Some("US-NY"));
+        assert!(ParsedAddress::default().country_code().is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "expand")]
+    fn parsed_address_subpremises() {
+        let address = ParsedAddress::from(
+            vec![
+                (AddressLabel::Unit, String::from("apt 2f")),
+                (AddressLabel::Level, String::from("3rd floor")),
+            ]
+            .into_iter()
+            .collect::<AddressParserResponse>(),
+        );
+        let unit = address.unit_parts().unwrap();
+        assert_eq!(unit.identifier.as_deref(), Some("2f"));
+        assert_eq!(
+            address.level_parts().unwrap().identifier.as_deref(),
+            Some("3")
+        );
+        assert!(address.staircase_parts().is_none());
+    }
+
+    #[test]
+    fn parsed_address_house_number() {
+        let address = ParsedAddress::from(
+            vec![(AddressLabel::HouseNumber, String::from("12-14"))]
+                .into_iter()
+                .collect::<AddressParserResponse>(),
+        );
+        let house_number = address.house_number_parts().unwrap();
+        assert!(house_number.contains(&"12".parse().unwrap()));
+        assert!(ParsedAddress::default().house_number_parts().is_none());
+    }
+
     #[test]
     fn default_address_parser_options() -> Result<(), NulError> {
         let options = AddressParserOptions::new(None, None)?;
@@ -0,0 +1,341 @@
+//! Columnar parsing and expansion of Apache Arrow arrays.
+//!
+//! Tokens and variations are written straight from `libpostal` into the
+//! Arrow buffers, without allocating for each address. Large arrays are
+//! processed on separate threads by [`parse_addresses_parallel`] and
+//! [`expand_addresses_parallel`], which split them into slices without
+//! copying.
+//!
+//! # Examples
+//!
+//! ```
+//! use arrow_array::{Array, StringArray};
+//! use rustpostal::address::AddressParserOptions;
+//! use rustpostal::error::RuntimeError;
+//! use rustpostal::{arrow, LibModules};
+//!
+//! fn main() -> Result<(), RuntimeError> {
+//!     let postal_module = LibModules::Address;
+//!     postal_module.setup()?;
+//!
+//!     let addresses = StringArray::from(vec![Some("10 Downing St, London"), None]);
+//!     let options = AddressParserOptions::new(None, None)?;
+//!     let parsed = arrow::parse_addresses(&options, &addresses)?;
+//!
+//!     assert_eq!(parsed.len(), 2);
+//!     assert!(parsed.is_null(1));
+//!     Ok(())
+//! }
+//! ```
+use std::ffi::NulError;
+use std::fmt::Write;
+use std::sync::Arc;
+use std::thread;
+
+use arrow_array::builder::{ListBuilder, StringBuilder};
+use arrow_array::cast::AsArray;
+use arrow_array::{Array, ArrayRef, GenericStringArray, ListArray, OffsetSizeTrait, StructArray};
+use arrow_buffer::NullBufferBuilder;
+use arrow_schema::{DataType, Field, Fields};
+use arrow_select::concat::concat;
+
+use crate::address::{AddressLabel, AddressParserOptions, AddressParserResponse};
+use crate::expand::NormalizeOptions;
+
+/// The fields of the arrays returned by [`parse_addresses`], one nullable
+/// string per [`AddressLabel`].
+pub fn address_fields() -> Fields {
+    AddressLabel::ALL
+        .iter()
+        .map(|label| Field::new(label.as_str(), DataType::Utf8, true))
+        .collect()
+}
+
+/// Builder of struct arrays of parsed addresses, one row at a time.
+///
+/// The array has the [`address_fields`], with the tokens of each label
+/// joined by spaces and missing labels null.
+#[derive(Debug)]
+pub struct AddressArrayBuilder {
+    builders: Vec<StringBuilder>,
+    nulls: NullBufferBuilder,
+    /// Whether each label has a token in the current address.
+    labeled: Vec<bool>,
+    /// The C string of the address being parsed.
+    buffer: Vec<u8>,
+}
+
+impl AddressArrayBuilder {
+    /// Create a builder with room for `capacity` addresses.
+    pub fn with_capacity(capacity: usize) -> AddressArrayBuilder {
+        AddressArrayBuilder {
+            builders: AddressLabel::ALL
+                .iter()
+                .map(|_| StringBuilder::with_capacity(capacity, 0))
+                .collect(),
+            nulls: NullBufferBuilder::new(capacity),
+            labeled: vec![false; AddressLabel::ALL.len()],
+            buffer: Vec::new(),
+        }
+    }
+
+    /// Append a parsed address.
+    pub fn append(&mut self, response: &AddressParserResponse) {
+        for (label, token) in response {
+            self.append_token(label, token);
+        }
+        self.end_address();
+    }
+
+    /// Parse `address` with `options` and append it, writing the tokens
+    /// from `libpostal` straight into the columns.
+    ///
+    /// # Errors
+    ///
+    /// It will return an error if the address contains an internal null
+    /// byte, and then appends nothing.
+    pub fn append_parsed(
+        &mut self,
+        options: &AddressParserOptions,
+        address: &str,
+    ) -> Result<(), NulError> {
+        let mut buffer = std::mem::take(&mut self.buffer);
+        let parsed = options.parse_with(address, &mut buffer, |label, token| {
+            self.append_token(label, token)
+        });
+        self.buffer = buffer;
+        parsed?;
+        self.end_address();
+        Ok(())
+    }
+
+    /// Write `token` into the column of `label`, after a space if the label
+    /// already has a token.
+    fn append_token(&mut self, label: &str, token: &str) {
+        let index = match AddressLabel::ALL.iter().position(|l| l.as_str() == label) {
+            Some(index) => index,
+            None => return,
+        };
+        let builder = &mut self.builders[index];
+        if self.labeled[index] {
+            builder.write_char(' ').unwrap();
+        }
+        builder.write_str(token).unwrap();
+        self.labeled[index] = true;
+    }
+
+    /// End the address of the tokens written so far.
+    fn end_address(&mut self) {
+        for (builder, labeled) in self.builders.iter_mut().zip(self.labeled.iter_mut()) {
+            if *labeled {
+                builder.append_value("");
+            } else {
+                builder.append_null();
+            }
+            *labeled = false;
+        }
+        self.nulls.append_non_null();
+    }
+
+    /// Append a null address.
+    pub fn append_null(&mut self) {
+        self.builders
+            .iter_mut()
+            .for_each(|builder| builder.append_null());
+        self.nulls.append_null();
+    }
+
+    /// Build the array and reset the builder.
+    pub fn finish(&mut self) -> StructArray {
+        let columns: Vec<ArrayRef> = self
+            .builders
+            .iter_mut()
+            .map(|builder| Arc::new(builder.finish()) as ArrayRef)
+            .collect();
+        StructArray::new(address_fields(), columns, self.nulls.finish())
+    }
+}
+
+/// Parse each address of `addresses` into a struct with one field per label.
+///
+/// Labels missing from an address are null, and so are the structs of null
+/// addresses. Tokens sharing a label are joined by spaces.
+///
+/// # Errors
+///
+/// It will return an error if an address contains an internal null byte.
+pub fn parse_addresses<O: OffsetSizeTrait>(
+    options: &AddressParserOptions,
+    addresses: &GenericStringArray<O>,
+) -> Result<StructArray, NulError> {
+    let mut builder = AddressArrayBuilder::with_capacity(addresses.len());
+    for address in addresses.iter() {
+        match address {
+            Some(address) => builder.append_parsed(options, address)?,
+            None => builder.append_null(),
+        }
+    }
+    Ok(builder.finish())
+}
+
+/// Expand each address of `addresses` into a list of its normalized variations.
+///
+/// The lists of null addresses are null.
+///
+/// # Errors
+///
+/// It will return an error if an address contains an internal null byte.
+pub fn expand_addresses<O: OffsetSizeTrait>(
+    options: &mut NormalizeOptions,
+    addresses: &GenericStringArray<O>,
+) -> Result<ListArray, NulError> {
+    let mut expander = options.expander();
+    let mut builder = ListBuilder::with_capacity(StringBuilder::new(), addresses.len());
+    for address in addresses.iter() {
+        match address {
+            Some(address) => {
+                let values = builder.values();
+                expander.expand(address, |variation| values.append_value(variation))?;
+                builder.append(true);
+            }
+            None => builder.append_null(),
+        }
+    }
+    Ok(builder.finish())
+}
+
+/// Apply `f` to slices of `addresses` on up to `threads` threads, and
+/// concatenate the arrays in order.
+fn in_parallel<O, T, F>(
+    addresses: &GenericStringArray<O>,
+    threads: usize,
+    f: F,
+) -> Result<ArrayRef, NulError>
+where
+    O: OffsetSizeTrait,
+    T: Array + Send,
+    F: Fn(&GenericStringArray<O>) -> Result<T, NulError> + Sync,
+{
+    let len = addresses.len();
+    let chunk = len.div_ceil(threads.max(1)).max(1);
+    let chunks = thread::scope(|scope| {
+        let workers: Vec<_> = (0..len)
+            .step_by(chunk)
+            .map(|offset| {
+                let slice = addresses.slice(offset, chunk.min(len - offset));
+                let f = &f;
+                scope.spawn(move || f(&slice))
+            })
+            .collect();
+        workers
+            .into_iter()
+            .map(|worker| worker.join().expect("worker panicked"))
+            .collect::<Result<Vec<T>, NulError>>()
+    })?;
+    let chunks: Vec<&dyn Array> = chunks.iter().map(|chunk| chunk as &dyn Array).collect();
+    Ok(concat(&chunks).expect("chunks of the same type"))
+}
+
+/// Parse `addresses` like [`parse_addresses`], on up to `threads` threads.
+///
+/// # Errors
+///
+/// It will return an error if an address contains an internal null byte.
+pub fn parse_addresses_parallel<O: OffsetSizeTrait>(
+    options: &AddressParserOptions,
+    addresses: &GenericStringArray<O>,
+    threads: usize,
+) -> Result<StructArray, NulError> {
+    if addresses.is_empty() {
+        return parse_addresses(options, addresses);
+    }
+    let parsed = in_parallel(addresses, threads, |slice| parse_addresses(options, slice))?;
+    Ok(parsed.as_struct().clone())
+}
+
+/// Expand `addresses` like [`expand_addresses`], on up to `threads` threads.
+///
+/// # Errors
+///
+/// It will return an error if an address contains an internal null byte.
+pub fn expand_addresses_parallel<O: OffsetSizeTrait>(
+    options: &NormalizeOptions,
+    addresses: &GenericStringArray<O>,
+    threads: usize,
+) -> Result<ListArray, NulError> {
+    if addresses.is_empty() {
+        return expand_addresses(&mut options.clone(), addresses);
+    }
+    let expanded = in_parallel(addresses, threads, |slice| {
+        expand_addresses(&mut options.clone(), slice)
+    })?;
+    Ok(expanded.as_list::<i32>().clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::StringArray;
+
+    #[test]
+    fn null_addresses() -> Result<(), NulError> {
+        let addresses = StringArray::from(vec![None::<&str>, None]);
+        let parsed = parse_addresses(&AddressParserOptions::new(None, None)?, &addresses)?;
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed.num_columns(), AddressLabel::ALL.len());
+        assert_eq!(parsed.null_count(), 2);
+
+        let expanded = expand_addresses(&mut NormalizeOptions::default(), &addresses)?;
+        assert_eq!(expanded.len(), 2);
+        assert_eq!(expanded.null_count(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn append_tokens_once() {
+        let response: AddressParserResponse = vec![
+            (AddressLabel::HouseNumber, String::from("10")),
+            (AddressLabel::Road, String::from("downing")),
+            (AddressLabel::Road, String::from("st")),
+        ]
+        .into_iter()
+        .collect();
+        let mut builder = AddressArrayBuilder::with_capacity(2);
+        builder.append(&response);
+        builder.append_null();
+        builder.append(&AddressParserResponse::new());
+        let parsed = builder.finish();
+
+        let road = parsed.column_by_name("road").unwrap().as_string::<i32>();
+        assert_eq!(road.value(0), "downing st");
+        assert!(road.is_null(1) && road.is_null(2));
+        let house_number = parsed.column_by_name("house_number").unwrap();
+        assert_eq!(house_number.as_string::<i32>().value(0), "10");
+        assert!(parsed.is_valid(2));
+    }
+
+    #[test]
+    fn null_addresses_in_parallel() -> Result<(), NulError> {
+        let addresses = StringArray::from(vec![None::<&str>; 5]);
+        let parsed =
+            parse_addresses_parallel(&AddressParserOptions::new(None, None)?, &addresses, 2)?;
+        assert_eq!((parsed.len(), parsed.null_count()), (5, 5));
+
+        let expanded = expand_addresses_parallel(&NormalizeOptions::default(), &addresses, 8)?;
+        assert_eq!((expanded.len(), expanded.null_count()), (5, 5));
+
+        let empty = StringArray::from(Vec::<&str>::new());
+        assert!(
+            parse_addresses_parallel(&AddressParserOptions::new(None, None)?, &empty, 4)?
+                .is_empty()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn address_fields_labels() {
+        let fields = address_fields();
+        assert_eq!(fields[0].name(), "house");
+        assert!(fields.iter().all(|field| field.is_nullable()));
+    }
+}
@@ -0,0 +1,301 @@
+//! Endpoint handlers, independent of the HTTP transport.
+//!
+//! Every `POST` endpoint takes a JSON object, or an array of objects for a
+//! batch, and answers with a single result or an array of results in the
+//! same order.
+use std::collections::BTreeMap;
+use std::error::Error;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use rustpostal::address::{AddressParserOptions, AddressParserResponse};
+use rustpostal::dedupe::{HashComponents, HashKeys, NearDupeHashOptions};
+use rustpostal::expand::{AddressComponents, NormalizeOptions, NormalizedAddress, StringOptions};
+use rustpostal::language::{self, LanguageClassifierResponse};
+use rustpostal::models::{self, Models};
+
+type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+/// A response status and JSON body.
+pub type Response = (u16, Value);
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ParseRequest {
+    address: String,
+    language: Option<String>,
+    country: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ExpandRequest {
+    address: String,
+    #[serde(default)]
+    languages: Vec<String>,
+    #[serde(default)]
+    string_options: Vec<String>,
+    #[serde(default)]
+    components: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ClassifyRequest {
+    address: String,
+}
+
+/// Components are either given as a label to value map, as a serialized
+/// `ParsedAddress`, or parsed from `address`.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NearDupeHashRequest {
+    address: Option<String>,
+    components: Option<BTreeMap<String, String>>,
+    #[serde(default)]
+    languages: Vec<String>,
+    #[serde(default)]
+    with: Vec<String>,
+    #[serde(default)]
+    keys: Vec<String>,
+    latlon: Option<(f64, f64)>,
+    geohash_precision: Option<u32>,
+}
+
+#[derive(Serialize)]
+struct Health {
+    status: &'static str,
+    modules: Vec<&'static str>,
+    models: Vec<&'static str>,
+    memory_bytes: u64,
+}
+
+/// A single request or a batch of them.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Batch<T> {
+    Many(Vec<T>),
+    One(T),
+}
+
+fn languages_option(languages: &[String]) -> Option<Vec<&str>> {
+    if languages.is_empty() {
+        None
+    } else {
+        Some(languages.iter().map(String::as_str).collect())
+    }
+}
+
+fn parse_flags<F>(names: &[String]) -> Result<Option<F>>
+where
+    F: std::str::FromStr + std::ops::BitOr<Output = F>,
+    F::Err: Error + 'static,
+{
+    let mut flags: Option<F> = None;
+    for name in names {
+        let flag = name.parse::<F>()?;
+        flags = Some(match flags {
+            Some(flags) => flags | flag,
+            None => flag,
+        });
+    }
+    Ok(flags)
+}
+
+fn parse(request: ParseRequest) -> Result<AddressParserResponse> {
+    let options =
+        AddressParserOptions::new(request.language.as_deref(), request.country.as_deref())?;
+    Ok(options.parse(&request.address)?)
+}
+
+fn expand(request: ExpandRequest) -> Result<NormalizedAddress> {
+    let languages = languages_option(&request.languages);
+    if languages.is_none() {
+        require(Models::CLASSIFIER)?;
+    }
+    let mut options = NormalizeOptions::new(languages.as_ref().map(|l| l.iter()))?;
+    if let Some(string_options) = parse_flags::<StringOptions>(&request.string_options)? {
+        options.add_string_option(string_options);
+    }
+    if let Some(components) = parse_flags::<AddressComponents>(&request.components)? {
+        options.add_address_component(components);
+    }
+    Ok(options.expand(&request.address)?)
+}
+
+fn classify(request: ClassifyRequest) -> Result<LanguageClassifierResponse> {
+    Ok(language::classify_language(&request.address)?)
+}
+
+fn near_dupe_hashes(request: NearDupeHashRequest) -> Result<Vec<String>> {
+    let languages = languages_option(&request.languages);
+    if languages.is_none() {
+        require(Models::CLASSIFIER)?;
+    }
+    let mut options = NearDupeHashOptions::new(languages.as_ref().map(|l| l.iter()))?;
+    if let Some(components) = parse_flags::<HashComponents>(&request.with)? {
+        options.set_components(components);
+    }
+    if let Some(keys) = parse_flags::<HashKeys>(&request.keys)? {
+        options.set_keys(keys);
+    }
+    if let Some((latitude, longitude)) = request.latlon {
+        options.set_latlon(latitude, longitude, request.geohash_precision);
+    }
+    match (request.components, request.address) {
+        (Some(components), None) => Ok(options.hashes(&components)?),
+        (None, Some(address)) => {
+            require(Models::PARSER)?;
+            let parsed = AddressParserOptions::new(None, None)?.parse(&address)?;
+            Ok(options.hashes(&parsed)?)
+        }
+        _ => Err("expected exactly one of `address` or `components`".into()),
+    }
+}
+
+/// Fail unless `models` are loaded.
+fn require(models: Models) -> Result<()> {
+    if models::loaded().contains(models) {
+        Ok(())
+    } else {
+        Err(format!(
+            "required models are not loaded: {}",
+            model_names(models).join(", ")
+        )
+        .into())
+    }
+}
+
+fn model_names(models: Models) -> Vec<&'static str> {
+    [
+        (Models::BASE, "base"),
+        (Models::PARSER, "parser"),
+        (Models::CLASSIFIER, "classifier"),
+    ]
+    .iter()
+    .filter(|(model, _)| models.contains(*model))
+    .map(|&(_, name)| name)
+    .collect()
+}
+
+/// The modules whose models are all loaded.
+fn loaded_modules() -> Vec<&'static str> {
+    let loaded = models::loaded();
+    [
+        (Models::BASE | Models::PARSER, "address"),
+        (Models::BASE | Models::CLASSIFIER, "expand"),
+        (Models::BASE | Models::CLASSIFIER, "dedupe"),
+        (Models::BASE | Models::CLASSIFIER, "language"),
+    ]
+    .iter()
+    .filter(|(models, _)| loaded.contains(*models))
+    .map(|&(_, name)| name)
+    .collect()
+}
+
+fn health() -> Response {
+    let health = Health {
+        status: "ok",
+        modules: loaded_modules(),
+        models: model_names(models::loaded()),
+        memory_bytes: models::memory_usage().total(),
+    };
+    (200, json!(health))
+}
+
+fn error(status: u16, message: impl ToString) -> Response {
+    (status, json!({ "error": message.to_string() }))
+}
+
+/// Run `f` on a single request or on each request of a batch.
+fn batch<T, R, F>(body: &[u8], required: Models, f: F) -> Response
+where
+    T: DeserializeOwned,
+    R: Serialize,
+    F: Fn(T) -> Result<R>,
+{
+    if let Err(err) = require(required) {
+        return error(503, err);
+    }
+    let batch = match serde_json::from_slice::<Batch<T>>(body) {
+        Ok(batch) => batch,
+        Err(err) => return error(400, format!("invalid request body: {}", err)),
+    };
+    let result = match batch {
+        Batch::One(request) => f(request).map(|response| json!(response)),
+        Batch::Many(requests) => requests
+            .into_iter()
+            .map(|request| f(request).map(|response| json!(response)))
+            .collect::<Result<Vec<Value>>>()
+            .map(Value::Array),
+    };
+    match result {
+        Ok(value) => (200, value),
+        Err(err) => error(422, err),
+    }
+}
+
+/// Route a request to its handler.
+pub fn handle(method: &str, path: &str, body: &[u8]) -> Response {
+    match (method, path) {
+        ("GET", "/health") => health(),
+        ("POST", "/parse") => batch(body, Models::PARSER, parse),
+        ("POST", "/expand") => batch(body, Models::BASE, expand),
+        ("POST", "/classify-language") => batch(body, Models::CLASSIFIER, classify),
+        ("POST", "/near-dupe-hashes") => batch(body, Models::BASE, near_dupe_hashes),
+        (_, "/health")
+        | (_, "/parse")
+        | (_, "/expand")
+        | (_, "/classify-language")
+        | (_, "/near-dupe-hashes") => error(405, "method not allowed"),
+        _ => error(404, "not found"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn handle_routes() {
+        assert_eq!(handle("GET", "/nowhere", b"").0, 404);
+        assert_eq!(handle("GET", "/parse", b"").0, 405);
+        assert_eq!(handle("GET", "/health", b"").0, 200);
+    }
+
+    #[test]
+    fn health_keeps_models_loaded() {
+        let _models = rustpostal::LibModules::All.setup_guarded().unwrap();
+        for _ in 0..2 {
+            let (status, body) = handle("GET", "/health", b"");
+            assert_eq!(status, 200);
+            assert!(body["modules"]
+                .as_array()
+                .unwrap()
+                .contains(&json!("address")));
+        }
+        let (status, body) = handle("POST", "/parse", br#"{"address": "10 Downing St, London"}"#);
+        assert_eq!(status, 200, "{}", body);
+    }
+
+    #[test]
+    fn batch_requests() {
+        let one = serde_json::from_str::<Batch<ClassifyRequest>>(r#"{"address": "a"}"#);
+        assert!(matches!(one, Ok(Batch::One(_))));
+        let many = serde_json::from_str::<Batch<ClassifyRequest>>(r#"[{"address": "a"}]"#);
+        assert!(matches!(many, Ok(Batch::Many(requests)) if requests.len() == 1));
+    }
+
+    #[test]
+    fn parse_flag_names() {
+        let names = vec![String::from("name"), String::from("postal_code")];
+        let flags = parse_flags::<HashComponents>(&names).unwrap();
+        assert_eq!(
+            flags,
+            Some(HashComponents::NAME | HashComponents::POSTAL_CODE)
+        );
+        assert!(parse_flags::<HashComponents>(&[String::from("zip")]).is_err());
+    }
+}
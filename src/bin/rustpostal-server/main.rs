@@ -0,0 +1,165 @@
+//! Local HTTP server exposing `libpostal` as JSON endpoints.
+//!
+//! The models are loaded once at startup and shared by all workers.
+//!
+//! * `GET /health`: the loaded modules and models.
+//! * `POST /parse`: `{"address", "language"?, "country"?}`.
+//! * `POST /expand`: `{"address", "languages"?, "string_options"?, "components"?}`.
+//! * `POST /classify-language`: `{"address"}`.
+//! * `POST /near-dupe-hashes`: `{"address" | "components", "languages"?, "with"?, "keys"?,
+//!   "latlon"?, "geohash_precision"?}`.
+//!
+//! `POST` bodies may also be arrays of requests, answered in order.
+use std::io::Read;
+use std::net::{IpAddr, Ipv4Addr};
+use std::path::PathBuf;
+use std::process;
+use std::sync::Arc;
+use std::thread;
+
+use clap::{Parser, ValueEnum};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use rustpostal::LibModules;
+
+mod handlers;
+
+/// Requests with larger bodies are rejected.
+const MAX_BODY_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Serve libpostal over HTTP.
+#[derive(Parser, Debug)]
+#[command(name = "rustpostal-server", version)]
+struct Args {
+    /// Address to listen on.
+    #[arg(long, default_value_t = IpAddr::V4(Ipv4Addr::LOCALHOST))]
+    host: IpAddr,
+    /// Port to listen on.
+    #[arg(long, default_value_t = 8080)]
+    port: u16,
+    /// Number of worker threads handling requests.
+    #[arg(long, default_value_t = default_workers())]
+    workers: usize,
+    /// Modules to load; all of them if not given.
+    #[arg(
+        long = "module",
+        value_name = "MODULE",
+        value_enum,
+        value_delimiter = ','
+    )]
+    modules: Vec<Module>,
+    /// Load the libpostal models from DIR instead of the default data directory.
+    #[arg(long, value_name = "DIR")]
+    datadir: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Module {
+    Address,
+    Expand,
+    Dedupe,
+    Language,
+}
+
+impl From<Module> for LibModules {
+    fn from(module: Module) -> Self {
+        match module {
+            Module::Address => LibModules::Address,
+            Module::Expand => LibModules::Expand,
+            Module::Dedupe => LibModules::Dedupe,
+            Module::Language => LibModules::Language,
+        }
+    }
+}
+
+fn default_workers() -> usize {
+    thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+fn respond(mut request: Request) {
+    let mut body = Vec::new();
+    let read = request
+        .as_reader()
+        .take(MAX_BODY_BYTES + 1)
+        .read_to_end(&mut body);
+    let (status, value) = match read {
+        Ok(_) if body.len() as u64 > MAX_BODY_BYTES => (
+            413,
+            serde_json::json!({ "error": "request body too large" }),
+        ),
+        Ok(_) => {
+            let method = match request.method() {
+                Method::Get => "GET",
+                Method::Post => "POST",
+                _ => "",
+            };
+            let path = request.url().split('?').next().unwrap_or_default();
+            handlers::handle(method, path, &body)
+        }
+        Err(err) => (400, serde_json::json!({ "error": err.to_string() })),
+    };
+    let content_type = Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]).unwrap();
+    let response = Response::from_string(value.to_string())
+        .with_status_code(status)
+        .with_header(content_type);
+    if let Err(err) = request.respond(response) {
+        eprintln!("rustpostal-server: failed to respond: {}", err);
+    }
+}
+
+fn main() {
+    let args = Args::parse();
+    let modules: Vec<LibModules> = if args.modules.is_empty() {
+        vec![LibModules::All]
+    } else {
+        args.modules.iter().map(|&m| LibModules::from(m)).collect()
+    };
+    for module in &modules {
+        let loaded = match &args.datadir {
+            Some(datadir) => module.setup_with_datadir(datadir),
+            None => module.setup(),
+        };
+        if let Err(err) = loaded {
+            eprintln!("rustpostal-server: {:?}: {}", module, err);
+            process::exit(1);
+        }
+    }
+
+    let server = match Server::http((args.host, args.port)) {
+        Ok(server) => Arc::new(server),
+        Err(err) => {
+            eprintln!("rustpostal-server: {}", err);
+            process::exit(1);
+        }
+    };
+    eprintln!(
+        "rustpostal-server: listening on http://{}:{} with {} workers",
+        args.host, args.port, args.workers
+    );
+
+    let workers: Vec<_> = (0..args.workers.max(1))
+        .map(|_| {
+            let server = Arc::clone(&server);
+            thread::spawn(move || {
+                for request in server.incoming_requests() {
+                    respond(request);
+                }
+            })
+        })
+        .collect();
+    for worker in workers {
+        let _ = worker.join();
+    }
+    drop(modules);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn args_debug_assert() {
+        Args::command().debug_assert();
+    }
+}
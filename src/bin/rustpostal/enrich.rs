@@ -0,0 +1,251 @@
+//! Column-wise parsing of CSV files.
+//!
+//! Every record is copied with one column appended per address label, and
+//! optionally with the expansions and near-dupe hashes of the address.
+use std::convert::TryFrom;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+
+use clap::Args;
+use csv::{ReaderBuilder, StringRecord, WriterBuilder};
+
+use rustpostal::address::{AddressLabel, AddressParserOptions, AddressParserResponse};
+use rustpostal::dedupe::NearDupeHashOptions;
+use rustpostal::expand::NormalizeOptions;
+use rustpostal::models::Models;
+
+use crate::Result;
+
+/// Separator of the values in the expansion and hash columns.
+const LIST_SEPARATOR: &str = "|";
+
+#[derive(Args, Debug)]
+pub struct CsvArgs {
+    /// CSV file to read; stdin if not given.
+    input: Option<PathBuf>,
+    /// Write the CSV to FILE instead of stdout.
+    #[arg(short, long, value_name = "FILE")]
+    output: Option<PathBuf>,
+    /// Column with the address, by header name or 0-based index.
+    #[arg(short, long)]
+    column: String,
+    /// The input has no header row.
+    #[arg(long)]
+    no_headers: bool,
+    /// Field delimiter.
+    #[arg(short, long, default_value_t = ',')]
+    delimiter: char,
+    /// Labels to output columns for; all of them if not given.
+    #[arg(long = "label", value_name = "LABEL", value_delimiter = ',')]
+    labels: Vec<AddressLabel>,
+    /// Prefix of the added column names.
+    #[arg(long, default_value = "")]
+    prefix: String,
+    /// Language code of every address.
+    #[arg(long)]
+    lang: Option<String>,
+    /// Country code of every address.
+    #[arg(long)]
+    country: Option<String>,
+    /// Column with the language code of each address; overrides `--lang`.
+    #[arg(long, value_name = "COLUMN")]
+    lang_column: Option<String>,
+    /// Column with the country code of each address; overrides `--country`.
+    #[arg(long, value_name = "COLUMN")]
+    country_column: Option<String>,
+    /// Add a column with the normalized expansions of the address.
+    #[arg(long)]
+    expand: bool,
+    /// Add a column with the near-dupe hashes of the address.
+    #[arg(long)]
+    hash: bool,
+}
+
+impl CsvArgs {
+    /// The models the options need.
+    pub fn models(&self) -> Models {
+        let mut models = Models::PARSER;
+        // Rows with an empty language cell fall back to `--lang`, or to the
+        // classifier without it.
+        if (self.expand || self.hash) && self.lang.is_none() {
+            models |= Models::CLASSIFIER;
+        }
+        models
+    }
+
+    fn labels(&self) -> &[AddressLabel] {
+        if self.labels.is_empty() {
+            AddressLabel::ALL
+        } else {
+            &self.labels
+        }
+    }
+}
+
+/// Find a column by header name, or else by 0-based index.
+fn column_index(headers: Option<&StringRecord>, column: &str) -> Result<usize> {
+    if let Some(index) = headers.and_then(|headers| headers.iter().position(|h| h == column)) {
+        return Ok(index);
+    }
+    column
+        .parse()
+        .map_err(|_| format!("no column `{}` in the input", column).into())
+}
+
+/// Resolve a per-row option: the row column if set and not empty, else the default.
+fn row_value<'a>(
+    record: &'a StringRecord,
+    column: Option<usize>,
+    default: Option<&'a str>,
+) -> Option<&'a str> {
+    column
+        .and_then(|index| record.get(index))
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .or(default)
+}
+
+pub fn run(args: &CsvArgs) -> Result<()> {
+    let delimiter = u8::try_from(args.delimiter).map_err(|_| "the delimiter must be ASCII")?;
+    let input: Box<dyn Read> = match &args.input {
+        Some(path) => Box::new(File::open(path).map_err(|e| format!("{}: {}", path.display(), e))?),
+        None => Box::new(io::stdin()),
+    };
+    let output: Box<dyn Write> = match &args.output {
+        Some(path) => {
+            Box::new(File::create(path).map_err(|e| format!("{}: {}", path.display(), e))?)
+        }
+        None => Box::new(io::stdout()),
+    };
+    let mut reader = ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(!args.no_headers)
+        .flexible(true)
+        .from_reader(input);
+    let mut writer = WriterBuilder::new()
+        .delimiter(delimiter)
+        .from_writer(output);
+
+    let headers = if args.no_headers {
+        None
+    } else {
+        Some(reader.headers()?.clone())
+    };
+    let address_column = column_index(headers.as_ref(), &args.column)?;
+    let lang_column = args
+        .lang_column
+        .as_deref()
+        .map(|column| column_index(headers.as_ref(), column))
+        .transpose()?;
+    let country_column = args
+        .country_column
+        .as_deref()
+        .map(|column| column_index(headers.as_ref(), column))
+        .transpose()?;
+
+    let labels = args.labels();
+    if let Some(headers) = &headers {
+        let mut output_headers = headers.clone();
+        for label in labels {
+            output_headers.push_field(&format!("{}{}", args.prefix, label));
+        }
+        if args.expand {
+            output_headers.push_field(&format!("{}expansions", args.prefix));
+        }
+        if args.hash {
+            output_headers.push_field(&format!("{}near_dupe_hashes", args.prefix));
+        }
+        writer.write_record(&output_headers)?;
+    }
+
+    for record in reader.records() {
+        let mut record = record?;
+        let address = record.get(address_column).unwrap_or_default().to_string();
+        let language = row_value(&record, lang_column, args.lang.as_deref()).map(String::from);
+        let country = row_value(&record, country_column, args.country.as_deref()).map(String::from);
+        let languages = language.as_deref().map(|language| [language]);
+        let languages = languages.as_ref().map(|l| l.iter());
+
+        let response = if address.trim().is_empty() {
+            AddressParserResponse::new()
+        } else {
+            AddressParserOptions::new(language.as_deref(), country.as_deref())?.parse(&address)?
+        };
+        for &label in labels {
+            record.push_field(&response.get(label).unwrap_or_default());
+        }
+        if args.expand {
+            let expansions = if address.trim().is_empty() {
+                Vec::new()
+            } else {
+                let mut options = NormalizeOptions::new(languages.clone())?;
+                Vec::from(options.expand(&address)?)
+            };
+            record.push_field(&expansions.join(LIST_SEPARATOR));
+        }
+        if args.hash {
+            let hashes = NearDupeHashOptions::new(languages)?.hashes(&response)?;
+            record.push_field(&hashes.join(LIST_SEPARATOR));
+        }
+        writer.write_record(&record)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn column_index_by_name_or_position() {
+        let headers = StringRecord::from(vec!["id", "address"]);
+        assert_eq!(column_index(Some(&headers), "address").unwrap(), 1);
+        assert_eq!(column_index(Some(&headers), "0").unwrap(), 0);
+        assert_eq!(column_index(None, "1").unwrap(), 1);
+        assert!(column_index(Some(&headers), "street").is_err());
+    }
+
+    #[test]
+    fn bom_and_quoting() {
+        let data = "\u{feff}id,address\n1,\"10 Downing St, London\"\n";
+        let mut reader = ReaderBuilder::new().from_reader(data.as_bytes());
+        let headers = reader.headers().unwrap().clone();
+        assert_eq!(column_index(Some(&headers), "id").unwrap(), 0);
+        let record = reader.records().next().unwrap().unwrap();
+        assert_eq!(record.get(1), Some("10 Downing St, London"));
+    }
+
+    #[test]
+    fn row_value_fallback() {
+        let record = StringRecord::from(vec!["a", " ", "fr"]);
+        assert_eq!(row_value(&record, Some(2), Some("en")), Some("fr"));
+        assert_eq!(row_value(&record, Some(1), Some("en")), Some("en"));
+        assert_eq!(row_value(&record, None, None), None);
+    }
+
+    #[test]
+    fn models_with_language_column() {
+        let mut args = CsvArgs {
+            input: None,
+            output: None,
+            column: String::from("address"),
+            no_headers: false,
+            delimiter: ',',
+            labels: Vec::new(),
+            prefix: String::new(),
+            lang: None,
+            country: None,
+            lang_column: Some(String::from("lang")),
+            country_column: None,
+            expand: true,
+            hash: false,
+        };
+        assert!(args.models().contains(Models::CLASSIFIER));
+        args.lang = Some(String::from("en"));
+        assert!(!args.models().contains(Models::CLASSIFIER));
+        args.expand = false;
+        assert_eq!(args.models(), Models::PARSER);
+    }
+}
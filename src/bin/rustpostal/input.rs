@@ -0,0 +1,58 @@
+//! Addresses given on the command line, in files or on stdin.
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::PathBuf;
+
+use clap::Args;
+
+#[derive(Args, Debug)]
+pub struct Input {
+    /// Addresses to process. If none are given, they are read one per line
+    /// from the input files or stdin.
+    addresses: Vec<String>,
+    /// Read addresses from FILE, one per line; `-` reads stdin.
+    #[arg(short, long = "input", value_name = "FILE")]
+    files: Vec<PathBuf>,
+}
+
+impl Input {
+    /// Whether the addresses were given as arguments.
+    pub fn is_args(&self) -> bool {
+        !self.addresses.is_empty()
+    }
+
+    /// Iterate over the non-empty input records.
+    pub fn records(&self) -> Box<dyn Iterator<Item = io::Result<String>> + '_> {
+        if self.is_args() {
+            return Box::new(self.addresses.iter().cloned().map(Ok));
+        }
+        if self.files.is_empty() {
+            return Box::new(non_empty(stdin_lines()));
+        }
+        Box::new(self.files.iter().flat_map(|path| {
+            let lines: Box<dyn Iterator<Item = io::Result<String>>> = if path.as_os_str() == "-" {
+                stdin_lines()
+            } else {
+                match File::open(path) {
+                    Ok(file) => Box::new(BufReader::new(file).lines()),
+                    Err(err) => Box::new(std::iter::once(Err(io::Error::new(
+                        err.kind(),
+                        format!("{}: {}", path.display(), err),
+                    )))),
+                }
+            };
+            non_empty(lines)
+        }))
+    }
+}
+
+fn stdin_lines() -> Box<dyn Iterator<Item = io::Result<String>>> {
+    Box::new(io::stdin().lock().lines())
+}
+
+fn non_empty<I>(lines: I) -> impl Iterator<Item = io::Result<String>>
+where
+    I: Iterator<Item = io::Result<String>>,
+{
+    lines.filter(|line| !matches!(line, Ok(line) if line.trim().is_empty()))
+}
@@ -0,0 +1,597 @@
+//! Command-line interface to `libpostal`.
+//!
+//! Every subcommand takes addresses as arguments, or reads them one per
+//! line from files or stdin, and prints the results as text or JSON lines.
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::ffi::NulError;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+use std::process;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+
+use rustpostal::address::{AddressParserOptions, AddressParserResponse, ParsedAddress};
+use rustpostal::dedupe::{
+    DuplicateOptions, DuplicateStatus, HashComponents, HashKeys, NearDupeHashOptions,
+};
+use rustpostal::error::SetupError;
+use rustpostal::expand::{AddressComponents, NormalizeOptions, NormalizedAddress, StringOptions};
+use rustpostal::language::{self, LanguageClassifierResponse};
+use rustpostal::models::{self, Models};
+
+#[cfg(feature = "csv")]
+mod enrich;
+mod input;
+mod repl;
+
+use input::Input;
+
+type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+/// Parse, normalize and deduplicate postal addresses with libpostal.
+#[derive(Parser, Debug)]
+#[command(name = "rustpostal", version)]
+struct Cli {
+    /// Load the libpostal models from DIR instead of the default data directory.
+    #[arg(long, global = true, value_name = "DIR")]
+    datadir: Option<PathBuf>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Parse addresses into labeled components.
+    Parse {
+        #[command(flatten)]
+        input: Input,
+        /// Language code hint for the parser.
+        #[arg(long)]
+        lang: Option<String>,
+        /// Country code hint for the parser.
+        #[arg(long)]
+        country: Option<String>,
+        #[arg(long, value_enum, default_value_t = Format::Table)]
+        format: Format,
+    },
+    /// Expand addresses into their normalized variations.
+    Expand {
+        #[command(flatten)]
+        input: Input,
+        /// Language codes to normalize for; classified per address if not given.
+        #[arg(long = "lang", value_name = "LANG", value_delimiter = ',')]
+        languages: Vec<String>,
+        /// String normalization options, e.g. `latin_ascii`.
+        #[arg(long = "string-option", value_name = "OPTION", value_delimiter = ',')]
+        string_options: Vec<StringOptions>,
+        /// Address components to expand, e.g. `street`.
+        #[arg(long = "component", value_name = "COMPONENT", value_delimiter = ',')]
+        components: Vec<AddressComponents>,
+        #[arg(long, value_enum, default_value_t = Format::Table)]
+        format: Format,
+    },
+    /// Classify the language of addresses.
+    Classify {
+        #[command(flatten)]
+        input: Input,
+        #[arg(long, value_enum, default_value_t = Format::Table)]
+        format: Format,
+    },
+    /// Generate near-dupe hashes of addresses for blocking.
+    Hash {
+        #[command(flatten)]
+        input: Input,
+        /// Language codes to normalize for; classified per address if not given.
+        #[arg(long = "lang", value_name = "LANG", value_delimiter = ',')]
+        languages: Vec<String>,
+        /// Components to include in the hashes, e.g. `postal_code`.
+        #[arg(long = "with", value_name = "COMPONENT", value_delimiter = ',')]
+        components: Vec<HashComponents>,
+        /// Key types to generate, e.g. `address_only`.
+        #[arg(long = "key", value_name = "KEY", value_delimiter = ',')]
+        keys: Vec<HashKeys>,
+        #[arg(long, value_enum, default_value_t = Format::Table)]
+        format: Format,
+    },
+    /// Compare pairs of addresses component by component.
+    ///
+    /// Pairs are given as two arguments, or as tab-separated lines.
+    Dedupe {
+        #[command(flatten)]
+        input: Input,
+        /// Language codes to normalize for; classified per address if not given.
+        #[arg(long = "lang", value_name = "LANG", value_delimiter = ',')]
+        languages: Vec<String>,
+        #[arg(long, value_enum, default_value_t = Format::Table)]
+        format: Format,
+    },
+    /// Parse an address column of a CSV file into one column per label.
+    #[cfg(feature = "csv")]
+    Csv(enrich::CsvArgs),
+    /// Parse or expand addresses interactively.
+    ///
+    /// Type `.help` at the prompt for the available commands.
+    Repl {
+        /// Language code hint to start with.
+        #[arg(long)]
+        lang: Option<String>,
+        /// Country code hint to start with.
+        #[arg(long)]
+        country: Option<String>,
+    },
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Format {
+    /// Human-readable text.
+    Table,
+    /// One JSON object per address.
+    Json,
+}
+
+#[derive(Serialize)]
+struct ParseRecord<'a> {
+    address: &'a str,
+    #[serde(flatten)]
+    response: AddressParserResponse,
+}
+
+#[derive(Serialize)]
+struct ExpandRecord<'a> {
+    address: &'a str,
+    expansions: NormalizedAddress,
+}
+
+#[derive(Serialize)]
+struct ClassifyRecord<'a> {
+    address: &'a str,
+    #[serde(flatten)]
+    response: LanguageClassifierResponse,
+}
+
+#[derive(Serialize)]
+struct HashRecord<'a> {
+    address: &'a str,
+    hashes: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct DedupeRecord<'a> {
+    address1: &'a str,
+    address2: &'a str,
+    components: BTreeMap<&'static str, DuplicateStatus>,
+}
+
+impl Command {
+    /// The models the subcommand needs.
+    fn models(&self) -> Models {
+        let classifier_unless = |languages: &[String]| {
+            if languages.is_empty() {
+                Models::CLASSIFIER
+            } else {
+                Models::empty()
+            }
+        };
+        match self {
+            Command::Parse { .. } => Models::PARSER,
+            Command::Expand { languages, .. } => Models::BASE | classifier_unless(languages),
+            Command::Classify { .. } => Models::CLASSIFIER,
+            #[cfg(feature = "csv")]
+            Command::Csv(args) => args.models(),
+            Command::Repl { .. } => Models::PARSER | Models::CLASSIFIER,
+            Command::Hash { languages, .. } | Command::Dedupe { languages, .. } => {
+                Models::PARSER | classifier_unless(languages)
+            }
+        }
+    }
+}
+
+fn main() {
+    let cli = Cli::parse();
+    if let Err(err) = run(cli) {
+        if let Some(err) = err.downcast_ref::<io::Error>() {
+            if err.kind() == io::ErrorKind::BrokenPipe {
+                return;
+            }
+        }
+        eprintln!("rustpostal: {}", err);
+        process::exit(1);
+    }
+}
+
+fn run(cli: Cli) -> Result<()> {
+    let required = cli.command.models();
+    match &cli.datadir {
+        Some(datadir) => models::load_with_datadir(required, datadir),
+        None => models::load(required),
+    }
+    .map_err(|_: SetupError| {
+        format!(
+            "failed to load the libpostal models from {}",
+            cli.datadir
+                .as_deref()
+                .map(|datadir| datadir.display().to_string())
+                .unwrap_or_else(|| models::DEFAULT_DATA_DIR.to_string())
+        )
+    })?;
+
+    let stdout = io::stdout();
+    let mut out = BufWriter::new(stdout.lock());
+    let result = match cli.command {
+        Command::Parse {
+            input,
+            lang,
+            country,
+            format,
+        } => parse(&mut out, &input, lang, country, format),
+        Command::Expand {
+            input,
+            languages,
+            string_options,
+            components,
+            format,
+        } => expand(
+            &mut out,
+            &input,
+            &languages,
+            &string_options,
+            &components,
+            format,
+        ),
+        Command::Classify { input, format } => classify(&mut out, &input, format),
+        Command::Hash {
+            input,
+            languages,
+            components,
+            keys,
+            format,
+        } => hash(&mut out, &input, &languages, &components, &keys, format),
+        Command::Dedupe {
+            input,
+            languages,
+            format,
+        } => dedupe(&mut out, &input, &languages, format),
+        #[cfg(feature = "csv")]
+        Command::Csv(args) => enrich::run(&args),
+        Command::Repl { lang, country } => repl::run(&mut out, lang, country),
+    };
+    out.flush()?;
+    models::unload(required);
+    result
+}
+
+fn languages_option(languages: &[String]) -> Option<Vec<&str>> {
+    if languages.is_empty() {
+        None
+    } else {
+        Some(languages.iter().map(String::as_str).collect())
+    }
+}
+
+/// Write a separator between text records.
+fn separate<W: Write>(out: &mut W, first: &mut bool) -> io::Result<()> {
+    if !std::mem::replace(first, false) {
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+fn write_json<W: Write, T: Serialize>(out: &mut W, record: &T) -> Result<()> {
+    serde_json::to_writer(&mut *out, record)?;
+    writeln!(out)?;
+    Ok(())
+}
+
+/// Write `(label, token)` pairs as aligned columns.
+fn write_components<W: Write>(out: &mut W, response: &AddressParserResponse) -> io::Result<()> {
+    let width = response
+        .into_iter()
+        .map(|(label, _)| label.len())
+        .max()
+        .unwrap_or(0);
+    for (label, token) in response {
+        writeln!(out, "{:width$}  {}", label, token, width = width)?;
+    }
+    Ok(())
+}
+
+fn parse<W: Write>(
+    out: &mut W,
+    input: &Input,
+    lang: Option<String>,
+    country: Option<String>,
+    format: Format,
+) -> Result<()> {
+    let options = AddressParserOptions::new(lang.as_deref(), country.as_deref())?;
+    let mut first = true;
+    for address in input.records() {
+        let address = address?;
+        let response = options.parse(&address)?;
+        match format {
+            Format::Table => {
+                separate(out, &mut first)?;
+                write_components(out, &response)?;
+            }
+            Format::Json => write_json(
+                out,
+                &ParseRecord {
+                    address: &address,
+                    response,
+                },
+            )?,
+        }
+    }
+    Ok(())
+}
+
+fn expand<W: Write>(
+    out: &mut W,
+    input: &Input,
+    languages: &[String],
+    string_options: &[StringOptions],
+    components: &[AddressComponents],
+    format: Format,
+) -> Result<()> {
+    let languages = languages_option(languages);
+    let mut options = NormalizeOptions::new(languages.as_ref().map(|l| l.iter()))?;
+    for &option in string_options {
+        options.add_string_option(option);
+    }
+    for &component in components {
+        options.add_address_component(component);
+    }
+    let mut first = true;
+    for address in input.records() {
+        let address = address?;
+        let expansions = options.expand(&address)?;
+        match format {
+            Format::Table => {
+                separate(out, &mut first)?;
+                for expansion in &expansions {
+                    writeln!(out, "{}", expansion)?;
+                }
+            }
+            Format::Json => write_json(
+                out,
+                &ExpandRecord {
+                    address: &address,
+                    expansions,
+                },
+            )?,
+        }
+    }
+    Ok(())
+}
+
+fn classify<W: Write>(out: &mut W, input: &Input, format: Format) -> Result<()> {
+    let mut first = true;
+    for address in input.records() {
+        let address = address?;
+        let response = language::classify_language(&address)?;
+        match format {
+            Format::Table => {
+                separate(out, &mut first)?;
+                for (language, probability) in &response {
+                    writeln!(out, "{}  {:.4}", language, probability)?;
+                }
+            }
+            Format::Json => write_json(
+                out,
+                &ClassifyRecord {
+                    address: &address,
+                    response,
+                },
+            )?,
+        }
+    }
+    Ok(())
+}
+
+fn hash<W: Write>(
+    out: &mut W,
+    input: &Input,
+    languages: &[String],
+    components: &[HashComponents],
+    keys: &[HashKeys],
+    format: Format,
+) -> Result<()> {
+    let parser = AddressParserOptions::new(None, None)?;
+    let languages = languages_option(languages);
+    let mut options = NearDupeHashOptions::new(languages.as_ref().map(|l| l.iter()))?;
+    if !components.is_empty() {
+        options.set_components(
+            components
+                .iter()
+                .fold(HashComponents::empty(), |a, &c| a | c),
+        );
+    }
+    if !keys.is_empty() {
+        options.set_keys(keys.iter().fold(HashKeys::empty(), |a, &k| a | k));
+    }
+    let mut first = true;
+    for address in input.records() {
+        let address = address?;
+        let response = parser.parse(&address)?;
+        let hashes = options.hashes(&response)?;
+        match format {
+            Format::Table => {
+                separate(out, &mut first)?;
+                for hash in &hashes {
+                    writeln!(out, "{}", hash)?;
+                }
+            }
+            Format::Json => write_json(
+                out,
+                &HashRecord {
+                    address: &address,
+                    hashes,
+                },
+            )?,
+        }
+    }
+    Ok(())
+}
+
+/// Labels compared with `DuplicateOptions::is_toponym_duplicate`.
+const TOPONYM_LABELS: &[&str] = &[
+    "suburb",
+    "city_district",
+    "city",
+    "state_district",
+    "island",
+    "state",
+    "country_region",
+    "country",
+    "world_region",
+];
+
+fn toponyms(response: &AddressParserResponse) -> Vec<(&String, &String)> {
+    response
+        .into_iter()
+        .filter(|(label, _)| TOPONYM_LABELS.contains(&label.as_str()))
+        .collect()
+}
+
+/// Compare the components present in both addresses.
+fn compare(
+    options: &DuplicateOptions,
+    response1: &AddressParserResponse,
+    response2: &AddressParserResponse,
+) -> std::result::Result<BTreeMap<&'static str, DuplicateStatus>, NulError> {
+    type Check =
+        fn(&DuplicateOptions, &str, &str) -> std::result::Result<DuplicateStatus, NulError>;
+    type Getter = fn(&ParsedAddress) -> Option<String>;
+    let checks: [(&str, Getter, Check); 7] = [
+        (
+            "house",
+            ParsedAddress::house,
+            DuplicateOptions::is_name_duplicate,
+        ),
+        (
+            "house_number",
+            ParsedAddress::house_number,
+            DuplicateOptions::is_house_number_duplicate,
+        ),
+        (
+            "road",
+            ParsedAddress::road,
+            DuplicateOptions::is_street_duplicate,
+        ),
+        (
+            "unit",
+            ParsedAddress::unit,
+            DuplicateOptions::is_unit_duplicate,
+        ),
+        (
+            "level",
+            ParsedAddress::level,
+            DuplicateOptions::is_floor_duplicate,
+        ),
+        (
+            "po_box",
+            ParsedAddress::po_box,
+            DuplicateOptions::is_po_box_duplicate,
+        ),
+        (
+            "postcode",
+            ParsedAddress::postcode,
+            DuplicateOptions::is_postal_code_duplicate,
+        ),
+    ];
+
+    let parsed1 = ParsedAddress::from(response1.clone());
+    let parsed2 = ParsedAddress::from(response2.clone());
+    let mut statuses = BTreeMap::new();
+    for (label, get, check) in checks.iter() {
+        if let (Some(value1), Some(value2)) = (get(&parsed1), get(&parsed2)) {
+            statuses.insert(*label, check(options, &value1, &value2)?);
+        }
+    }
+    let (toponyms1, toponyms2) = (toponyms(response1), toponyms(response2));
+    if !toponyms1.is_empty() && !toponyms2.is_empty() {
+        statuses.insert(
+            "toponym",
+            options.is_toponym_duplicate(toponyms1, toponyms2)?,
+        );
+    }
+    Ok(statuses)
+}
+
+fn dedupe<W: Write>(
+    out: &mut W,
+    input: &Input,
+    languages: &[String],
+    format: Format,
+) -> Result<()> {
+    let parser = AddressParserOptions::new(None, None)?;
+    let languages = languages_option(languages);
+    let options = DuplicateOptions::new(languages.as_ref().map(|l| l.iter()))?;
+
+    let records = input.records().collect::<io::Result<Vec<String>>>()?;
+    let pairs: Vec<(String, String)> = if input.is_args() {
+        if records.len() % 2 != 0 {
+            return Err("dedupe expects addresses in pairs".into());
+        }
+        records
+            .chunks(2)
+            .map(|pair| (pair[0].clone(), pair[1].clone()))
+            .collect()
+    } else {
+        records
+            .iter()
+            .map(|line| match line.split_once('\t') {
+                Some((address1, address2)) => Ok((address1.to_string(), address2.to_string())),
+                None => Err(format!("expected two tab-separated addresses: {}", line)),
+            })
+            .collect::<std::result::Result<_, _>>()?
+    };
+
+    let mut first = true;
+    for (address1, address2) in &pairs {
+        let components = compare(&options, &parser.parse(address1)?, &parser.parse(address2)?)?;
+        match format {
+            Format::Table => {
+                separate(out, &mut first)?;
+                let width = components
+                    .keys()
+                    .map(|label| label.len())
+                    .max()
+                    .unwrap_or(0);
+                for (label, status) in &components {
+                    writeln!(out, "{:width$}  {:?}", label, status, width = width)?;
+                }
+            }
+            Format::Json => write_json(
+                out,
+                &DedupeRecord {
+                    address1,
+                    address2,
+                    components,
+                },
+            )?,
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn cli_debug_assert() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn command_models() {
+        let cli =
+            Cli::try_parse_from(["rustpostal", "expand", "--lang", "en", "1 main st"]).unwrap();
+        assert_eq!(cli.command.models(), Models::BASE);
+        let cli = Cli::try_parse_from(["rustpostal", "hash", "1 main st"]).unwrap();
+        assert_eq!(cli.command.models(), Models::PARSER | Models::CLASSIFIER);
+    }
+}
@@ -0,0 +1,176 @@
+//! Interactive shell, after the `address_parser` client of `libpostal`.
+use std::io::Write;
+
+use rustyline::error::ReadlineError;
+use rustyline::DefaultEditor;
+
+use rustpostal::address::AddressParserOptions;
+use rustpostal::expand::NormalizeOptions;
+
+use crate::{write_components, Result};
+
+const HELP: &str = "\
+Enter an address to parse or expand it, or one of the commands:
+  .lang [CODE]     set the language hint, or clear it without CODE
+  .country [CODE]  set the country hint, or clear it without CODE
+  .expand          switch to expanding addresses
+  .parse           switch to parsing addresses
+  .options         show the current mode and hints
+  .help            show this message
+  .exit            leave the shell";
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Mode {
+    Parse,
+    Expand,
+}
+
+/// What to do after a line was handled.
+#[derive(Debug, Eq, PartialEq)]
+enum Action {
+    /// Process the line as an address.
+    Address,
+    /// Print a message.
+    Message(String),
+    Exit,
+}
+
+#[derive(Debug)]
+struct Session {
+    mode: Mode,
+    language: Option<String>,
+    country: Option<String>,
+}
+
+impl Session {
+    fn new(language: Option<String>, country: Option<String>) -> Session {
+        Session {
+            mode: Mode::Parse,
+            language,
+            country,
+        }
+    }
+
+    fn options(&self) -> String {
+        let mode = match self.mode {
+            Mode::Parse => "parse",
+            Mode::Expand => "expand",
+        };
+        format!(
+            "mode: {}, language: {}, country: {}",
+            mode,
+            self.language.as_deref().unwrap_or("-"),
+            self.country.as_deref().unwrap_or("-")
+        )
+    }
+
+    /// Interpret a line, updating the session for commands.
+    fn handle(&mut self, line: &str) -> Action {
+        let line = line.trim();
+        if !line.starts_with('.') {
+            return Action::Address;
+        }
+        let mut words = line.split_whitespace();
+        let command = words.next().unwrap_or_default();
+        let argument = words.next().map(String::from);
+        match command {
+            ".lang" | ".language" => {
+                self.language = argument;
+                Action::Message(self.options())
+            }
+            ".country" => {
+                self.country = argument;
+                Action::Message(self.options())
+            }
+            ".expand" => {
+                self.mode = Mode::Expand;
+                Action::Message(self.options())
+            }
+            ".parse" => {
+                self.mode = Mode::Parse;
+                Action::Message(self.options())
+            }
+            ".options" => Action::Message(self.options()),
+            ".help" => Action::Message(String::from(HELP)),
+            ".exit" | ".quit" => Action::Exit,
+            _ => Action::Message(format!("unknown command `{}`, try .help", command)),
+        }
+    }
+
+    fn process<W: Write>(&self, out: &mut W, address: &str) -> Result<()> {
+        match self.mode {
+            Mode::Parse => {
+                let options =
+                    AddressParserOptions::new(self.language.as_deref(), self.country.as_deref())?;
+                write_components(out, &options.parse(address)?)?;
+            }
+            Mode::Expand => {
+                let languages = self.language.as_deref().map(|language| [language]);
+                let mut options = NormalizeOptions::new(languages.as_ref().map(|l| l.iter()))?;
+                for expansion in &options.expand(address)? {
+                    writeln!(out, "{}", expansion)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Run the shell until `.exit` or end of input.
+pub fn run<W: Write>(out: &mut W, language: Option<String>, country: Option<String>) -> Result<()> {
+    let mut session = Session::new(language, country);
+    let mut editor = DefaultEditor::new()?;
+    writeln!(
+        out,
+        "rustpostal {}, type .help for help",
+        env!("CARGO_PKG_VERSION")
+    )?;
+    out.flush()?;
+    loop {
+        let line = match editor.readline("> ") {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(err) => return Err(err.into()),
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        editor.add_history_entry(line.as_str())?;
+        match session.handle(&line) {
+            Action::Address => {
+                if let Err(err) = session.process(out, line.trim()) {
+                    writeln!(out, "error: {}", err)?;
+                }
+            }
+            Action::Message(message) => writeln!(out, "{}", message)?,
+            Action::Exit => break,
+        }
+        writeln!(out)?;
+        out.flush()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn session_handle() {
+        let mut session = Session::new(None, None);
+        assert_eq!(session.handle("10 Downing St"), Action::Address);
+        session.handle(".lang en");
+        session.handle(".country gb");
+        assert_eq!(session.language.as_deref(), Some("en"));
+        assert_eq!(session.country.as_deref(), Some("gb"));
+        session.handle(".lang");
+        assert_eq!(session.language, None);
+        session.handle(".expand");
+        assert_eq!(session.mode, Mode::Expand);
+        session.handle(".parse");
+        assert_eq!(session.mode, Mode::Parse);
+        assert_eq!(session.handle(".exit"), Action::Exit);
+        assert!(matches!(session.handle(".bogus"), Action::Message(_)));
+    }
+}
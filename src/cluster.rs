@@ -0,0 +1,191 @@
+//! Clustering of duplicate addresses within a single dataset.
+//!
+//! Records sharing a near-dupe hash are compared pairwise like in
+//! [`linkage`](crate::linkage), and matching pairs are merged transitively,
+//! so that a cluster holds every record linked to another by a chain of
+//! matches.
+//!
+//! Candidate pairs are generated block by block and never collected, and
+//! pairs already in the same cluster are not compared again. Memory thus
+//! grows with the number of records and blocking keys, not with the number
+//! of candidate pairs.
+//!
+//! # Examples
+//!
+//! ```
+//! use rustpostal::cluster;
+//! use rustpostal::error::RuntimeError;
+//! use rustpostal::linkage::LinkageOptions;
+//! use rustpostal::LibModules;
+//!
+//! fn main() -> Result<(), RuntimeError> {
+//!     let postal_module = LibModules::All;
+//!     postal_module.setup()?;
+//!
+//!     let records = vec![
+//!         (1, "660 Nostrand Ave, Brooklyn, NY 11216"),
+//!         (2, "660 Nostrand Avenue, Brooklyn, New York 11216"),
+//!         (3, "1 Main St, Springfield"),
+//!     ];
+//!     let options = LinkageOptions::new(Some(["en"].iter()))?;
+//!     for cluster in cluster::cluster(&options, records)? {
+//!         println!("{}: {:?} ({})", cluster.id, cluster.members, cluster.representative);
+//!     }
+//!     Ok(())
+//! }
+//! ```
+use std::collections::HashMap;
+use std::ffi::NulError;
+
+use crate::linkage::{LinkageOptions, Record};
+
+/// A cluster of records referring to the same place.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Cluster<K> {
+    /// The cluster index, in order of the first record of each cluster.
+    pub id: usize,
+    /// The id of the most complete record of the cluster.
+    pub representative: K,
+    /// The ids of the records of the cluster, in input order.
+    pub members: Vec<K>,
+}
+
+/// Disjoint sets of record indices, merged by union by rank.
+struct UnionFind {
+    parents: Vec<usize>,
+    ranks: Vec<u8>,
+}
+
+impl UnionFind {
+    fn new(len: usize) -> UnionFind {
+        UnionFind {
+            parents: (0..len).collect(),
+            ranks: vec![0; len],
+        }
+    }
+
+    fn find(&mut self, mut index: usize) -> usize {
+        while self.parents[index] != index {
+            // Path halving.
+            self.parents[index] = self.parents[self.parents[index]];
+            index = self.parents[index];
+        }
+        index
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return;
+        }
+        match self.ranks[a].cmp(&self.ranks[b]) {
+            std::cmp::Ordering::Less => self.parents[a] = b,
+            std::cmp::Ordering::Greater => self.parents[b] = a,
+            std::cmp::Ordering::Equal => {
+                self.parents[b] = a;
+                self.ranks[a] += 1;
+            }
+        }
+    }
+}
+
+/// The number of distinct labels of a record, ranking representatives.
+fn completeness<K>(record: &Record<K>) -> usize {
+    let mut labels: Vec<&String> = (&record.response)
+        .into_iter()
+        .map(|(label, _)| label)
+        .collect();
+    labels.sort();
+    labels.dedup();
+    labels.len()
+}
+
+/// Cluster the `(id, address)` records referring to the same place.
+///
+/// Every record belongs to exactly one cluster, possibly on its own. The
+/// representative of a cluster is the record with the most labeled
+/// components, the first one in input order on ties.
+///
+/// # Errors
+///
+/// It will return an error if an address contains an internal null byte.
+pub fn cluster<K, A, I>(options: &LinkageOptions, records: I) -> Result<Vec<Cluster<K>>, NulError>
+where
+    K: Clone,
+    A: AsRef<str>,
+    I: IntoIterator<Item = (K, A)>,
+{
+    let records = options.parse(records)?;
+    let mut blocks: HashMap<String, Vec<usize>> = HashMap::new();
+    for (index, record) in records.iter().enumerate() {
+        for hash in options.blocking_keys(record)? {
+            blocks.entry(hash).or_default().push(index);
+        }
+    }
+
+    let mut sets = UnionFind::new(records.len());
+    for block in blocks.values() {
+        for (i, &a) in block.iter().enumerate() {
+            for &b in &block[i + 1..] {
+                if sets.find(a) == sets.find(b) {
+                    continue;
+                }
+                if options.matches(&records[a], &records[b])?.is_some() {
+                    sets.union(a, b);
+                }
+            }
+        }
+    }
+    drop(blocks);
+
+    let mut cluster_ids: HashMap<usize, usize> = HashMap::new();
+    let mut clusters: Vec<(Vec<usize>, usize)> = Vec::new();
+    for index in 0..records.len() {
+        let root = sets.find(index);
+        let id = *cluster_ids.entry(root).or_insert_with(|| {
+            clusters.push((Vec::new(), index));
+            clusters.len() - 1
+        });
+        let (members, representative) = &mut clusters[id];
+        members.push(index);
+        if completeness(&records[index]) > completeness(&records[*representative]) {
+            *representative = index;
+        }
+    }
+    Ok(clusters
+        .into_iter()
+        .enumerate()
+        .map(|(id, (members, representative))| Cluster {
+            id,
+            representative: records[representative].id.clone(),
+            members: members
+                .into_iter()
+                .map(|index| records[index].id.clone())
+                .collect(),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn union_find_merges_transitively() {
+        let mut sets = UnionFind::new(5);
+        sets.union(0, 1);
+        sets.union(3, 4);
+        sets.union(1, 4);
+        assert_eq!(sets.find(0), sets.find(3));
+        assert_ne!(sets.find(0), sets.find(2));
+    }
+
+    #[test]
+    fn cluster_without_records() -> Result<(), NulError> {
+        let options = LinkageOptions::new(Some(["en"].iter()))?;
+        let records: Vec<(u32, &str)> = Vec::new();
+        assert!(cluster(&options, records)?.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,995 @@
+//! Resolution of country and subdivision names to ISO 3166 codes.
+//!
+//! The parser returns countries and states as free text in any language,
+//! e.g. "españa", "deutschland" or "n.y.". They are resolved against a
+//! bundled multilingual table of the ISO 3166-1 countries and of the
+//! ISO 3166-2 subdivisions of the main countries.
+//!
+//! Names are first compared ignoring case, punctuation and the diacritics
+//! of Latin letters. If that fails and the models for expansion are loaded,
+//! the normalized variations of the name, see [`NormalizeOptions::expand`],
+//! are compared too, so that scripts and abbreviations are handled by
+//! `libpostal`.
+//!
+//! # Examples
+//!
+//! ```
+//! use rustpostal::country;
+//!
+//! let germany = country::find("Deutschland").unwrap();
+//! assert_eq!((germany.alpha2, germany.alpha3), ("DE", "DEU"));
+//! assert_eq!(germany.name(), "germany");
+//!
+//! let us = country::find("U.S.A.").unwrap();
+//! let new_york = country::find_subdivision(Some(us), "n.y.").unwrap();
+//! assert_eq!(new_york.code, "US-NY");
+//! ```
+use crate::expand::NormalizeOptions;
+use crate::models::{self, Models};
+
+/// A country of ISO 3166-1.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Country {
+    /// The alpha-2 code, e.g. "GB".
+    pub alpha2: &'static str,
+    /// The alpha-3 code, e.g. "GBR".
+    pub alpha3: &'static str,
+    /// The lowercase names of the country, the English one first.
+    pub names: &'static [&'static str],
+}
+
+impl Country {
+    /// Return the English name of the country.
+    pub fn name(&self) -> &'static str {
+        self.names[0]
+    }
+
+    /// Return the bundled subdivisions of the country.
+    pub fn subdivisions(&self) -> impl Iterator<Item = &'static Subdivision> {
+        let alpha2 = self.alpha2;
+        SUBDIVISIONS
+            .iter()
+            .filter(move |subdivision| subdivision.country_code() == alpha2)
+    }
+}
+
+/// A subdivision of ISO 3166-2, e.g. a state or province.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Subdivision {
+    /// The code, e.g. "US-NY".
+    pub code: &'static str,
+    /// The lowercase names of the subdivision, the English one first.
+    pub names: &'static [&'static str],
+}
+
+impl Subdivision {
+    /// Return the English name of the subdivision.
+    pub fn name(&self) -> &'static str {
+        self.names[0]
+    }
+
+    /// Return the alpha-2 code of the country of the subdivision.
+    pub fn country_code(&self) -> &'static str {
+        &self.code[..2]
+    }
+
+    /// Return the part of the code following the country, e.g. "NY".
+    pub fn local_code(&self) -> &'static str {
+        &self.code[3..]
+    }
+}
+
+macro_rules! countries {
+    ($(($alpha2:expr, $alpha3:expr, [$($name:expr),+ $(,)?])),+ $(,)?) => {
+        &[$(Country {
+            alpha2: $alpha2,
+            alpha3: $alpha3,
+            names: &[$($name),+],
+        }),+]
+    };
+}
+
+macro_rules! subdivisions {
+    ($(($code:expr, [$($name:expr),+ $(,)?])),+ $(,)?) => {
+        &[$(Subdivision {
+            code: $code,
+            names: &[$($name),+],
+        }),+]
+    };
+}
+
+/// The countries of ISO 3166-1, by alpha-2 code.
+///
+/// Names are simplified, see [`simplify`], so Latin ones are without
+/// accents.
+pub const COUNTRIES: &[Country] = countries![
+    ("AD", "AND", ["andorra"]),
+    (
+        "AE",
+        "ARE",
+        ["united arab emirates", "uae", "emirats arabes unis"]
+    ),
+    ("AF", "AFG", ["afghanistan"]),
+    ("AG", "ATG", ["antigua and barbuda"]),
+    ("AI", "AIA", ["anguilla"]),
+    ("AL", "ALB", ["albania", "shqiperia"]),
+    ("AM", "ARM", ["armenia", "hayastan"]),
+    ("AO", "AGO", ["angola"]),
+    ("AQ", "ATA", ["antarctica"]),
+    ("AR", "ARG", ["argentina", "argentine", "argentinien"]),
+    ("AS", "ASM", ["american samoa"]),
+    (
+        "AT",
+        "AUT",
+        ["austria", "osterreich", "oesterreich", "autriche"]
+    ),
+    ("AU", "AUS", ["australia", "australie", "australien"]),
+    ("AW", "ABW", ["aruba"]),
+    ("AX", "ALA", ["aland islands", "aland"]),
+    ("AZ", "AZE", ["azerbaijan", "azerbaycan"]),
+    (
+        "BA",
+        "BIH",
+        ["bosnia and herzegovina", "bosna i hercegovina"]
+    ),
+    ("BB", "BRB", ["barbados"]),
+    ("BD", "BGD", ["bangladesh"]),
+    (
+        "BE",
+        "BEL",
+        ["belgium", "belgique", "belgie", "belgien", "belgio", "belgica"]
+    ),
+    ("BF", "BFA", ["burkina faso"]),
+    ("BG", "BGR", ["bulgaria", "bulgariya", "българия"]),
+    ("BH", "BHR", ["bahrain"]),
+    ("BI", "BDI", ["burundi"]),
+    ("BJ", "BEN", ["benin"]),
+    ("BL", "BLM", ["saint barthelemy"]),
+    ("BM", "BMU", ["bermuda"]),
+    ("BN", "BRN", ["brunei", "brunei darussalam"]),
+    ("BO", "BOL", ["bolivia"]),
+    (
+        "BQ",
+        "BES",
+        ["bonaire sint eustatius and saba", "caribbean netherlands"]
+    ),
+    ("BR", "BRA", ["brazil", "brasil", "bresil", "brasilien"]),
+    ("BS", "BHS", ["bahamas", "the bahamas"]),
+    ("BT", "BTN", ["bhutan"]),
+    ("BV", "BVT", ["bouvet island"]),
+    ("BW", "BWA", ["botswana"]),
+    ("BY", "BLR", ["belarus", "беларусь"]),
+    ("BZ", "BLZ", ["belize"]),
+    ("CA", "CAN", ["canada"]),
+    ("CC", "CCK", ["cocos keeling islands", "cocos islands"]),
+    (
+        "CD",
+        "COD",
+        [
+            "democratic republic of the congo",
+            "dr congo",
+            "drc",
+            "congo kinshasa",
+            "republique democratique du congo",
+        ]
+    ),
+    ("CF", "CAF", ["central african republic", "centrafrique"]),
+    (
+        "CG",
+        "COG",
+        ["republic of the congo", "congo", "congo brazzaville"]
+    ),
+    (
+        "CH",
+        "CHE",
+        ["switzerland", "schweiz", "suisse", "svizzera", "svizra"]
+    ),
+    ("CI", "CIV", ["cote divoire", "ivory coast"]),
+    ("CK", "COK", ["cook islands"]),
+    ("CL", "CHL", ["chile", "chili"]),
+    ("CM", "CMR", ["cameroon", "cameroun"]),
+    ("CN", "CHN", ["china", "zhongguo", "chine", "中国"]),
+    ("CO", "COL", ["colombia", "colombie"]),
+    ("CR", "CRI", ["costa rica"]),
+    ("CU", "CUB", ["cuba"]),
+    ("CV", "CPV", ["cabo verde", "cape verde"]),
+    ("CW", "CUW", ["curacao"]),
+    ("CX", "CXR", ["christmas island"]),
+    ("CY", "CYP", ["cyprus", "kypros", "chypre", "κυπρος"]),
+    (
+        "CZ",
+        "CZE",
+        [
+            "czechia",
+            "czech republic",
+            "cesko",
+            "ceska republika",
+            "tschechien",
+        ]
+    ),
+    (
+        "DE",
+        "DEU",
+        [
+            "germany",
+            "deutschland",
+            "allemagne",
+            "alemania",
+            "germania",
+            "duitsland",
+            "niemcy",
+        ]
+    ),
+    ("DJ", "DJI", ["djibouti"]),
+    (
+        "DK",
+        "DNK",
+        ["denmark", "danmark", "danemark", "dinamarca", "danimarca"]
+    ),
+    ("DM", "DMA", ["dominica"]),
+    ("DO", "DOM", ["dominican republic", "republica dominicana"]),
+    ("DZ", "DZA", ["algeria", "algerie"]),
+    ("EC", "ECU", ["ecuador", "equateur"]),
+    ("EE", "EST", ["estonia", "eesti"]),
+    ("EG", "EGY", ["egypt", "misr", "egypte"]),
+    ("EH", "ESH", ["western sahara"]),
+    ("ER", "ERI", ["eritrea"]),
+    (
+        "ES",
+        "ESP",
+        ["spain", "espana", "espagne", "spanien", "spagna", "espanya"]
+    ),
+    ("ET", "ETH", ["ethiopia"]),
+    ("FI", "FIN", ["finland", "suomi", "finnland", "finlande"]),
+    ("FJ", "FJI", ["fiji"]),
+    ("FK", "FLK", ["falkland islands", "malvinas"]),
+    (
+        "FM",
+        "FSM",
+        ["micronesia", "federated states of micronesia"]
+    ),
+    ("FO", "FRO", ["faroe islands", "foroyar"]),
+    (
+        "FR",
+        "FRA",
+        ["france", "frankreich", "francia", "frankrijk", "francja"]
+    ),
+    ("GA", "GAB", ["gabon"]),
+    (
+        "GB",
+        "GBR",
+        [
+            "united kingdom",
+            "uk",
+            "great britain",
+            "britain",
+            "united kingdom of great britain and northern ireland",
+            "royaume uni",
+            "reino unido",
+            "vereinigtes konigreich",
+            "regno unito",
+            "england",
+            "scotland",
+            "wales",
+            "northern ireland",
+        ]
+    ),
+    ("GD", "GRD", ["grenada"]),
+    ("GE", "GEO", ["georgia", "sakartvelo"]),
+    ("GF", "GUF", ["french guiana", "guyane"]),
+    ("GG", "GGY", ["guernsey"]),
+    ("GH", "GHA", ["ghana"]),
+    ("GI", "GIB", ["gibraltar"]),
+    ("GL", "GRL", ["greenland", "kalaallit nunaat", "gronland"]),
+    ("GM", "GMB", ["gambia", "the gambia"]),
+    ("GN", "GIN", ["guinea", "guinee"]),
+    ("GP", "GLP", ["guadeloupe"]),
+    ("GQ", "GNQ", ["equatorial guinea"]),
+    (
+        "GR",
+        "GRC",
+        [
+            "greece",
+            "ellada",
+            "hellas",
+            "grece",
+            "griechenland",
+            "ελλαδα",
+            "ελλάδα"
+        ]
+    ),
+    (
+        "GS",
+        "SGS",
+        ["south georgia and the south sandwich islands"]
+    ),
+    ("GT", "GTM", ["guatemala"]),
+    ("GU", "GUM", ["guam"]),
+    ("GW", "GNB", ["guinea bissau"]),
+    ("GY", "GUY", ["guyana"]),
+    ("HK", "HKG", ["hong kong", "香港"]),
+    ("HM", "HMD", ["heard island and mcdonald islands"]),
+    ("HN", "HND", ["honduras"]),
+    ("HR", "HRV", ["croatia", "hrvatska", "kroatien", "croatie"]),
+    ("HT", "HTI", ["haiti"]),
+    (
+        "HU",
+        "HUN",
+        ["hungary", "magyarorszag", "ungarn", "hongrie"]
+    ),
+    ("ID", "IDN", ["indonesia", "indonesie"]),
+    (
+        "IE",
+        "IRL",
+        ["ireland", "eire", "republic of ireland", "irlande"]
+    ),
+    ("IL", "ISR", ["israel", "ישראל"]),
+    ("IM", "IMN", ["isle of man"]),
+    ("IN", "IND", ["india", "bharat", "inde", "indien"]),
+    ("IO", "IOT", ["british indian ocean territory"]),
+    ("IQ", "IRQ", ["iraq"]),
+    ("IR", "IRN", ["iran"]),
+    ("IS", "ISL", ["iceland", "islande"]),
+    ("IT", "ITA", ["italy", "italia", "italie", "italien"]),
+    ("JE", "JEY", ["jersey"]),
+    ("JM", "JAM", ["jamaica"]),
+    ("JO", "JOR", ["jordan"]),
+    ("JP", "JPN", ["japan", "nihon", "nippon", "japon", "日本"]),
+    ("KE", "KEN", ["kenya"]),
+    ("KG", "KGZ", ["kyrgyzstan"]),
+    ("KH", "KHM", ["cambodia", "cambodge"]),
+    ("KI", "KIR", ["kiribati"]),
+    ("KM", "COM", ["comoros", "comores"]),
+    ("KN", "KNA", ["saint kitts and nevis"]),
+    ("KP", "PRK", ["north korea"]),
+    (
+        "KR",
+        "KOR",
+        [
+            "south korea",
+            "korea",
+            "republic of korea",
+            "대한민국",
+            "한국"
+        ]
+    ),
+    ("KW", "KWT", ["kuwait"]),
+    ("KY", "CYM", ["cayman islands"]),
+    ("KZ", "KAZ", ["kazakhstan", "казахстан"]),
+    ("LA", "LAO", ["laos"]),
+    ("LB", "LBN", ["lebanon", "liban"]),
+    ("LC", "LCA", ["saint lucia"]),
+    ("LI", "LIE", ["liechtenstein"]),
+    ("LK", "LKA", ["sri lanka"]),
+    ("LR", "LBR", ["liberia"]),
+    ("LS", "LSO", ["lesotho"]),
+    ("LT", "LTU", ["lithuania", "lietuva"]),
+    (
+        "LU",
+        "LUX",
+        ["luxembourg", "luxemburg", "letzebuerg", "lussemburgo"]
+    ),
+    ("LV", "LVA", ["latvia", "latvija"]),
+    ("LY", "LBY", ["libya", "libye"]),
+    ("MA", "MAR", ["morocco", "maroc", "marokko", "marruecos"]),
+    ("MC", "MCO", ["monaco"]),
+    ("MD", "MDA", ["moldova"]),
+    ("ME", "MNE", ["montenegro", "crna gora"]),
+    ("MF", "MAF", ["saint martin"]),
+    ("MG", "MDG", ["madagascar"]),
+    ("MH", "MHL", ["marshall islands"]),
+    (
+        "MK",
+        "MKD",
+        ["north macedonia", "macedonia", "северна македонија"]
+    ),
+    ("ML", "MLI", ["mali"]),
+    ("MM", "MMR", ["myanmar", "burma"]),
+    ("MN", "MNG", ["mongolia"]),
+    ("MO", "MAC", ["macao", "macau", "澳門"]),
+    ("MP", "MNP", ["northern mariana islands"]),
+    ("MQ", "MTQ", ["martinique"]),
+    ("MR", "MRT", ["mauritania", "mauritanie"]),
+    ("MS", "MSR", ["montserrat"]),
+    ("MT", "MLT", ["malta", "malte"]),
+    ("MU", "MUS", ["mauritius", "maurice"]),
+    ("MV", "MDV", ["maldives"]),
+    ("MW", "MWI", ["malawi"]),
+    ("MX", "MEX", ["mexico", "mexique", "mexiko", "messico"]),
+    ("MY", "MYS", ["malaysia"]),
+    ("MZ", "MOZ", ["mozambique", "mocambique"]),
+    ("NA", "NAM", ["namibia"]),
+    ("NC", "NCL", ["new caledonia", "nouvelle caledonie"]),
+    ("NE", "NER", ["niger"]),
+    ("NF", "NFK", ["norfolk island"]),
+    ("NG", "NGA", ["nigeria"]),
+    ("NI", "NIC", ["nicaragua"]),
+    (
+        "NL",
+        "NLD",
+        [
+            "netherlands",
+            "the netherlands",
+            "nederland",
+            "holland",
+            "pays bas",
+            "niederlande",
+            "paises bajos",
+            "paesi bassi",
+        ]
+    ),
+    (
+        "NO",
+        "NOR",
+        ["norway", "norge", "noreg", "norwegen", "norvege"]
+    ),
+    ("NP", "NPL", ["nepal"]),
+    ("NR", "NRU", ["nauru"]),
+    ("NU", "NIU", ["niue"]),
+    ("NZ", "NZL", ["new zealand", "aotearoa"]),
+    ("OM", "OMN", ["oman"]),
+    ("PA", "PAN", ["panama"]),
+    ("PE", "PER", ["peru", "perou"]),
+    ("PF", "PYF", ["french polynesia", "polynesie francaise"]),
+    ("PG", "PNG", ["papua new guinea"]),
+    ("PH", "PHL", ["philippines", "pilipinas"]),
+    ("PK", "PAK", ["pakistan"]),
+    (
+        "PL",
+        "POL",
+        ["poland", "polska", "pologne", "polen", "polonia"]
+    ),
+    ("PM", "SPM", ["saint pierre and miquelon"]),
+    ("PN", "PCN", ["pitcairn", "pitcairn islands"]),
+    ("PR", "PRI", ["puerto rico"]),
+    ("PS", "PSE", ["palestine", "state of palestine"]),
+    ("PT", "PRT", ["portugal"]),
+    ("PW", "PLW", ["palau"]),
+    ("PY", "PRY", ["paraguay"]),
+    ("QA", "QAT", ["qatar"]),
+    ("RE", "REU", ["reunion"]),
+    ("RO", "ROU", ["romania", "roumanie", "rumanien"]),
+    ("RS", "SRB", ["serbia", "srbija", "србија"]),
+    (
+        "RU",
+        "RUS",
+        [
+            "russia",
+            "russian federation",
+            "rossiya",
+            "russland",
+            "russie",
+            "россия"
+        ]
+    ),
+    ("RW", "RWA", ["rwanda"]),
+    ("SA", "SAU", ["saudi arabia"]),
+    ("SB", "SLB", ["solomon islands"]),
+    ("SC", "SYC", ["seychelles"]),
+    ("SD", "SDN", ["sudan"]),
+    (
+        "SE",
+        "SWE",
+        ["sweden", "sverige", "suede", "schweden", "svezia"]
+    ),
+    ("SG", "SGP", ["singapore", "singapur", "新加坡"]),
+    ("SH", "SHN", ["saint helena"]),
+    ("SI", "SVN", ["slovenia", "slovenija"]),
+    ("SJ", "SJM", ["svalbard and jan mayen"]),
+    ("SK", "SVK", ["slovakia", "slovensko"]),
+    ("SL", "SLE", ["sierra leone"]),
+    ("SM", "SMR", ["san marino"]),
+    ("SN", "SEN", ["senegal"]),
+    ("SO", "SOM", ["somalia"]),
+    ("SR", "SUR", ["suriname"]),
+    ("SS", "SSD", ["south sudan"]),
+    ("ST", "STP", ["sao tome and principe"]),
+    ("SV", "SLV", ["el salvador"]),
+    ("SX", "SXM", ["sint maarten"]),
+    ("SY", "SYR", ["syria"]),
+    ("SZ", "SWZ", ["eswatini", "swaziland"]),
+    ("TC", "TCA", ["turks and caicos islands"]),
+    ("TD", "TCD", ["chad", "tchad"]),
+    ("TF", "ATF", ["french southern territories"]),
+    ("TG", "TGO", ["togo"]),
+    ("TH", "THA", ["thailand", "thailande"]),
+    ("TJ", "TJK", ["tajikistan"]),
+    ("TK", "TKL", ["tokelau"]),
+    ("TL", "TLS", ["timor leste", "east timor"]),
+    ("TM", "TKM", ["turkmenistan"]),
+    ("TN", "TUN", ["tunisia", "tunisie"]),
+    ("TO", "TON", ["tonga"]),
+    ("TR", "TUR", ["turkey", "turkiye", "turquie", "turkei"]),
+    ("TT", "TTO", ["trinidad and tobago"]),
+    ("TV", "TUV", ["tuvalu"]),
+    ("TW", "TWN", ["taiwan", "台灣"]),
+    ("TZ", "TZA", ["tanzania"]),
+    ("UA", "UKR", ["ukraine", "ukraina", "україна"]),
+    ("UG", "UGA", ["uganda"]),
+    ("UM", "UMI", ["united states minor outlying islands"]),
+    (
+        "US",
+        "USA",
+        [
+            "united states",
+            "united states of america",
+            "america",
+            "etats unis",
+            "estados unidos",
+            "vereinigte staaten",
+            "stati uniti",
+        ]
+    ),
+    ("UY", "URY", ["uruguay"]),
+    ("UZ", "UZB", ["uzbekistan"]),
+    ("VA", "VAT", ["vatican city", "holy see", "vatican"]),
+    ("VC", "VCT", ["saint vincent and the grenadines"]),
+    ("VE", "VEN", ["venezuela"]),
+    ("VG", "VGB", ["british virgin islands"]),
+    (
+        "VI",
+        "VIR",
+        ["united states virgin islands", "us virgin islands"]
+    ),
+    ("VN", "VNM", ["vietnam", "viet nam"]),
+    ("VU", "VUT", ["vanuatu"]),
+    ("WF", "WLF", ["wallis and futuna"]),
+    ("WS", "WSM", ["samoa"]),
+    ("YE", "YEM", ["yemen"]),
+    ("YT", "MYT", ["mayotte"]),
+    (
+        "ZA",
+        "ZAF",
+        ["south africa", "suid afrika", "afrique du sud"]
+    ),
+    ("ZM", "ZMB", ["zambia"]),
+    ("ZW", "ZWE", ["zimbabwe"]),
+];
+
+/// The subdivisions of ISO 3166-2 of the main countries, by code.
+///
+/// Within a known country, the part of the code following the country is
+/// matched too, e.g. "ny" for "US-NY".
+pub const SUBDIVISIONS: &[Subdivision] = subdivisions![
+    ("AU-ACT", ["australian capital territory"]),
+    ("AU-NSW", ["new south wales"]),
+    ("AU-NT", ["northern territory"]),
+    ("AU-QLD", ["queensland"]),
+    ("AU-SA", ["south australia"]),
+    ("AU-TAS", ["tasmania"]),
+    ("AU-VIC", ["victoria"]),
+    ("AU-WA", ["western australia"]),
+    ("BR-AC", ["acre"]),
+    ("BR-AL", ["alagoas"]),
+    ("BR-AM", ["amazonas"]),
+    ("BR-AP", ["amapa"]),
+    ("BR-BA", ["bahia"]),
+    ("BR-CE", ["ceara"]),
+    ("BR-DF", ["distrito federal"]),
+    ("BR-ES", ["espirito santo"]),
+    ("BR-GO", ["goias"]),
+    ("BR-MA", ["maranhao"]),
+    ("BR-MG", ["minas gerais"]),
+    ("BR-MS", ["mato grosso do sul"]),
+    ("BR-MT", ["mato grosso"]),
+    ("BR-PA", ["para"]),
+    ("BR-PB", ["paraiba"]),
+    ("BR-PE", ["pernambuco"]),
+    ("BR-PI", ["piaui"]),
+    ("BR-PR", ["parana"]),
+    ("BR-RJ", ["rio de janeiro"]),
+    ("BR-RN", ["rio grande do norte"]),
+    ("BR-RO", ["rondonia"]),
+    ("BR-RR", ["roraima"]),
+    ("BR-RS", ["rio grande do sul"]),
+    ("BR-SC", ["santa catarina"]),
+    ("BR-SE", ["sergipe"]),
+    ("BR-SP", ["sao paulo"]),
+    ("BR-TO", ["tocantins"]),
+    ("CA-AB", ["alberta", "alta"]),
+    ("CA-BC", ["british columbia", "colombie britannique"]),
+    ("CA-MB", ["manitoba", "man"]),
+    ("CA-NB", ["new brunswick", "nouveau brunswick"]),
+    (
+        "CA-NL",
+        [
+            "newfoundland and labrador",
+            "nfld",
+            "terre neuve et labrador"
+        ]
+    ),
+    ("CA-NS", ["nova scotia", "nouvelle ecosse"]),
+    (
+        "CA-NT",
+        ["northwest territories", "territoires du nord ouest"]
+    ),
+    ("CA-NU", ["nunavut"]),
+    ("CA-ON", ["ontario", "ont"]),
+    (
+        "CA-PE",
+        ["prince edward island", "pei", "ile du prince edouard"]
+    ),
+    ("CA-QC", ["quebec", "que", "pq"]),
+    ("CA-SK", ["saskatchewan", "sask"]),
+    ("CA-YT", ["yukon"]),
+    ("DE-BB", ["brandenburg"]),
+    ("DE-BE", ["berlin"]),
+    ("DE-BW", ["baden wurttemberg", "baden wuerttemberg"]),
+    ("DE-BY", ["bavaria", "bayern"]),
+    ("DE-HB", ["bremen"]),
+    ("DE-HE", ["hesse", "hessen"]),
+    ("DE-HH", ["hamburg"]),
+    (
+        "DE-MV",
+        ["mecklenburg western pomerania", "mecklenburg vorpommern"]
+    ),
+    ("DE-NI", ["lower saxony", "niedersachsen"]),
+    (
+        "DE-NW",
+        ["north rhine westphalia", "nordrhein westfalen", "nrw"]
+    ),
+    ("DE-RP", ["rhineland palatinate", "rheinland pfalz"]),
+    ("DE-SH", ["schleswig holstein"]),
+    ("DE-SL", ["saarland"]),
+    ("DE-SN", ["saxony", "sachsen"]),
+    ("DE-ST", ["saxony anhalt", "sachsen anhalt"]),
+    ("DE-TH", ["thuringia", "thuringen", "thueringen"]),
+    ("ES-AN", ["andalusia", "andalucia"]),
+    ("ES-AR", ["aragon"]),
+    ("ES-AS", ["asturias"]),
+    ("ES-CB", ["cantabria"]),
+    ("ES-CE", ["ceuta"]),
+    ("ES-CL", ["castile and leon", "castilla y leon"]),
+    ("ES-CM", ["castilla la mancha"]),
+    ("ES-CN", ["canary islands", "canarias"]),
+    ("ES-CT", ["catalonia", "cataluna", "catalunya"]),
+    ("ES-EX", ["extremadura"]),
+    ("ES-GA", ["galicia"]),
+    (
+        "ES-IB",
+        ["balearic islands", "illes balears", "islas baleares"]
+    ),
+    ("ES-MC", ["murcia", "region de murcia"]),
+    ("ES-MD", ["madrid", "comunidad de madrid"]),
+    ("ES-ML", ["melilla"]),
+    ("ES-NC", ["navarre", "navarra"]),
+    ("ES-PV", ["basque country", "pais vasco", "euskadi"]),
+    ("ES-RI", ["la rioja"]),
+    (
+        "ES-VC",
+        [
+            "valencian community",
+            "comunidad valenciana",
+            "comunitat valenciana"
+        ]
+    ),
+    ("FR-20R", ["corsica", "corse"]),
+    ("FR-ARA", ["auvergne rhone alpes"]),
+    ("FR-BFC", ["bourgogne franche comte"]),
+    ("FR-BRE", ["brittany", "bretagne"]),
+    ("FR-CVL", ["centre val de loire"]),
+    ("FR-GES", ["grand est"]),
+    ("FR-HDF", ["hauts de france"]),
+    ("FR-IDF", ["ile de france"]),
+    ("FR-NAQ", ["nouvelle aquitaine"]),
+    ("FR-NOR", ["normandy", "normandie"]),
+    ("FR-OCC", ["occitanie"]),
+    ("FR-PAC", ["provence alpes cote dazur", "paca"]),
+    ("FR-PDL", ["pays de la loire"]),
+    ("GB-ENG", ["england"]),
+    ("GB-NIR", ["northern ireland"]),
+    ("GB-SCT", ["scotland"]),
+    ("GB-WLS", ["wales", "cymru"]),
+    ("IT-21", ["piedmont", "piemonte"]),
+    ("IT-23", ["aosta valley", "valle daosta"]),
+    ("IT-25", ["lombardy", "lombardia"]),
+    ("IT-32", ["trentino alto adige", "trentino south tyrol"]),
+    ("IT-34", ["veneto"]),
+    ("IT-36", ["friuli venezia giulia"]),
+    ("IT-42", ["liguria"]),
+    ("IT-45", ["emilia romagna"]),
+    ("IT-52", ["tuscany", "toscana"]),
+    ("IT-55", ["umbria"]),
+    ("IT-57", ["marche"]),
+    ("IT-62", ["lazio"]),
+    ("IT-65", ["abruzzo"]),
+    ("IT-67", ["molise"]),
+    ("IT-72", ["campania"]),
+    ("IT-75", ["apulia", "puglia"]),
+    ("IT-77", ["basilicata"]),
+    ("IT-78", ["calabria"]),
+    ("IT-82", ["sicily", "sicilia"]),
+    ("IT-88", ["sardinia", "sardegna"]),
+    ("MX-AGU", ["aguascalientes", "ags"]),
+    ("MX-BCN", ["baja california", "bc"]),
+    ("MX-BCS", ["baja california sur"]),
+    ("MX-CAM", ["campeche"]),
+    ("MX-CHH", ["chihuahua", "chih"]),
+    ("MX-CHP", ["chiapas", "chis"]),
+    (
+        "MX-CMX",
+        [
+            "mexico city",
+            "ciudad de mexico",
+            "cdmx",
+            "distrito federal"
+        ]
+    ),
+    ("MX-COA", ["coahuila", "coah"]),
+    ("MX-COL", ["colima"]),
+    ("MX-DUR", ["durango", "dgo"]),
+    ("MX-GRO", ["guerrero"]),
+    ("MX-GUA", ["guanajuato", "gto"]),
+    ("MX-HID", ["hidalgo"]),
+    ("MX-JAL", ["jalisco"]),
+    ("MX-MEX", ["state of mexico", "estado de mexico", "edomex"]),
+    ("MX-MIC", ["michoacan"]),
+    ("MX-MOR", ["morelos"]),
+    ("MX-NAY", ["nayarit"]),
+    ("MX-NLE", ["nuevo leon", "nl"]),
+    ("MX-OAX", ["oaxaca"]),
+    ("MX-PUE", ["puebla"]),
+    ("MX-QUE", ["queretaro", "qro"]),
+    ("MX-ROO", ["quintana roo", "qroo"]),
+    ("MX-SIN", ["sinaloa"]),
+    ("MX-SLP", ["san luis potosi"]),
+    ("MX-SON", ["sonora"]),
+    ("MX-TAB", ["tabasco"]),
+    ("MX-TAM", ["tamaulipas", "tamps"]),
+    ("MX-TLA", ["tlaxcala"]),
+    ("MX-VER", ["veracruz"]),
+    ("MX-YUC", ["yucatan"]),
+    ("MX-ZAC", ["zacatecas"]),
+    ("NL-DR", ["drenthe"]),
+    ("NL-FL", ["flevoland"]),
+    ("NL-FR", ["friesland", "fryslan"]),
+    ("NL-GE", ["gelderland"]),
+    ("NL-GR", ["groningen"]),
+    ("NL-LI", ["limburg"]),
+    ("NL-NB", ["north brabant", "noord brabant"]),
+    ("NL-NH", ["north holland", "noord holland"]),
+    ("NL-OV", ["overijssel"]),
+    ("NL-UT", ["utrecht"]),
+    ("NL-ZE", ["zeeland"]),
+    ("NL-ZH", ["south holland", "zuid holland"]),
+    ("US-AK", ["alaska"]),
+    ("US-AL", ["alabama"]),
+    ("US-AR", ["arkansas"]),
+    ("US-AZ", ["arizona"]),
+    ("US-CA", ["california", "calif"]),
+    ("US-CO", ["colorado"]),
+    ("US-CT", ["connecticut", "conn"]),
+    ("US-DC", ["district of columbia", "washington dc"]),
+    ("US-DE", ["delaware"]),
+    ("US-FL", ["florida", "fla"]),
+    ("US-GA", ["georgia"]),
+    ("US-HI", ["hawaii"]),
+    ("US-IA", ["iowa"]),
+    ("US-ID", ["idaho"]),
+    ("US-IL", ["illinois", "ill"]),
+    ("US-IN", ["indiana", "ind"]),
+    ("US-KS", ["kansas", "kan"]),
+    ("US-KY", ["kentucky"]),
+    ("US-LA", ["louisiana"]),
+    ("US-MA", ["massachusetts", "mass"]),
+    ("US-MD", ["maryland"]),
+    ("US-ME", ["maine"]),
+    ("US-MI", ["michigan", "mich"]),
+    ("US-MN", ["minnesota", "minn"]),
+    ("US-MO", ["missouri"]),
+    ("US-MS", ["mississippi", "miss"]),
+    ("US-MT", ["montana", "mont"]),
+    ("US-NC", ["north carolina"]),
+    ("US-ND", ["north dakota"]),
+    ("US-NE", ["nebraska", "neb"]),
+    ("US-NH", ["new hampshire"]),
+    ("US-NJ", ["new jersey"]),
+    ("US-NM", ["new mexico"]),
+    ("US-NV", ["nevada", "nev"]),
+    ("US-NY", ["new york"]),
+    ("US-OH", ["ohio"]),
+    ("US-OK", ["oklahoma", "okla"]),
+    ("US-OR", ["oregon", "ore"]),
+    ("US-PA", ["pennsylvania", "penn"]),
+    ("US-PR", ["puerto rico"]),
+    ("US-RI", ["rhode island"]),
+    ("US-SC", ["south carolina"]),
+    ("US-SD", ["south dakota"]),
+    ("US-TN", ["tennessee", "tenn"]),
+    ("US-TX", ["texas", "tex"]),
+    ("US-UT", ["utah"]),
+    ("US-VA", ["virginia"]),
+    ("US-VT", ["vermont"]),
+    ("US-WA", ["washington", "wash"]),
+    ("US-WI", ["wisconsin", "wis"]),
+    ("US-WV", ["west virginia"]),
+    ("US-WY", ["wyoming", "wyo"]),
+];
+
+/// Fold a lowercase Latin letter with a diacritic to its base letters.
+fn fold_latin(c: char) -> Option<&'static str> {
+    let folded = match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => "a",
+        'æ' => "ae",
+        'ç' | 'ć' | 'č' => "c",
+        'ď' | 'đ' | 'ð' => "d",
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ė' | 'ę' | 'ě' => "e",
+        'ğ' => "g",
+        'ì' | 'í' | 'î' | 'ï' | 'ī' | 'į' | 'ı' => "i",
+        'ł' => "l",
+        'ñ' | 'ń' | 'ň' => "n",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ő' => "o",
+        'œ' => "oe",
+        'ř' => "r",
+        'ś' | 'š' | 'ş' | 'ș' => "s",
+        'ß' => "ss",
+        'ť' | 'ţ' | 'ț' => "t",
+        'þ' => "th",
+        'ù' | 'ú' | 'û' | 'ü' | 'ū' | 'ů' | 'ű' | 'ų' => "u",
+        'ý' | 'ÿ' => "y",
+        'ź' | 'ż' | 'ž' => "z",
+        _ => return None,
+    };
+    Some(folded)
+}
+
+/// Lowercase a name, fold Latin diacritics, drop periods and apostrophes
+/// and replace any other punctuation by a space, e.g. "N.Y." by "ny" and
+/// "Côte d'Ivoire" by "cote divoire".
+fn simplify(name: &str) -> String {
+    let mut simplified = String::with_capacity(name.len());
+    for c in name.chars().flat_map(char::to_lowercase) {
+        if let Some(folded) = fold_latin(c) {
+            simplified.push_str(folded);
+        } else if c.is_alphanumeric() {
+            simplified.push(c);
+        } else if matches!(c, '.' | '\'' | '\u{2019}') {
+            continue;
+        } else if !simplified.is_empty() && !simplified.ends_with(' ') {
+            simplified.push(' ');
+        }
+    }
+    let len = simplified.trim_end().len();
+    simplified.truncate(len);
+    simplified
+}
+
+/// Find the single item matching `name` or, failing that, one of its
+/// normalized variations, if the models for expansion are loaded.
+fn resolve<T, F>(items: &'static [T], name: &str, matches: F) -> Option<&'static T>
+where
+    F: Fn(&T, &str) -> bool,
+{
+    let find = |key: &str| {
+        let mut found = items.iter().filter(|item| matches(item, key));
+        match (found.next(), found.next()) {
+            (Some(item), None) => Some(item),
+            _ => None,
+        }
+    };
+    let key = simplify(name);
+    if key.is_empty() {
+        return None;
+    }
+    if let Some(item) = find(&key) {
+        return Some(item);
+    }
+    if !models::loaded().contains(Models::BASE | Models::CLASSIFIER) {
+        return None;
+    }
+    let variations = NormalizeOptions::default().expand(name).ok()?;
+    variations
+        .iter()
+        .map(|variation| simplify(variation))
+        .filter(|variation| *variation != key)
+        .find_map(|variation| find(&variation))
+}
+
+/// Resolve the name or ISO 3166-1 code of a country.
+///
+/// Returns `None` if the name is unknown.
+pub fn find(name: &str) -> Option<&'static Country> {
+    resolve(COUNTRIES, name, |country, key| {
+        country.alpha2.eq_ignore_ascii_case(key)
+            || country.alpha3.eq_ignore_ascii_case(key)
+            || country.names.contains(&key)
+    })
+}
+
+/// Resolve the name or ISO 3166-2 code of a subdivision, e.g. a state.
+///
+/// Within a `country`, the part of the code following the country is
+/// matched too. Without one, the subdivision is only resolved if its name
+/// is unambiguous among the bundled subdivisions.
+///
+/// Returns `None` if the name is unknown or ambiguous.
+pub fn find_subdivision(country: Option<&Country>, name: &str) -> Option<&'static Subdivision> {
+    resolve(SUBDIVISIONS, name, |subdivision, key| {
+        let code = key.len() == subdivision.code.len()
+            && key.replace(' ', "-").eq_ignore_ascii_case(subdivision.code);
+        match country {
+            Some(country) => {
+                subdivision.country_code() == country.alpha2
+                    && (code
+                        || subdivision.local_code().eq_ignore_ascii_case(key)
+                        || subdivision.names.contains(&key))
+            }
+            None => code || subdivision.names.contains(&key),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn simplify_names() {
+        assert_eq!(simplify("N.Y."), "ny");
+        assert_eq!(simplify(" Côte d'Ivoire "), "cote divoire");
+        assert_eq!(simplify("Baden-Württemberg"), "baden wurttemberg");
+        assert_eq!(simplify("Antigua & Barbuda"), "antigua barbuda");
+        assert_eq!(simplify("..."), "");
+    }
+
+    #[test]
+    fn tables_are_simplified_and_unique() {
+        for (i, country) in COUNTRIES.iter().enumerate() {
+            assert_eq!(country.alpha2.len(), 2);
+            assert_eq!(country.alpha3.len(), 3);
+            assert!(COUNTRIES[i + 1..]
+                .iter()
+                .all(|other| other.alpha2 != country.alpha2 && other.alpha3 != country.alpha3));
+            for name in country.names {
+                assert_eq!(simplify(name), *name);
+            }
+        }
+        for (i, subdivision) in SUBDIVISIONS.iter().enumerate() {
+            assert!(find(subdivision.country_code()).is_some());
+            assert!(SUBDIVISIONS[i + 1..]
+                .iter()
+                .all(|other| other.code != subdivision.code));
+            for name in subdivision.names {
+                assert_eq!(simplify(name), *name);
+            }
+        }
+    }
+
+    #[test]
+    fn find_countries() {
+        let cases = [
+            ("united kingdom", "GB"),
+            ("UK", "GB"),
+            ("deutschland", "DE"),
+            ("España", "ES"),
+            ("Österreich", "AT"),
+            ("Côte d'Ivoire", "CI"),
+            ("DEU", "DE"),
+            ("u.s.a.", "US"),
+            ("Россия", "RU"),
+            ("日本", "JP"),
+        ];
+        for (name, alpha2) in cases.iter() {
+            assert_eq!(find(name).map(|country| country.alpha2), Some(*alpha2));
+        }
+        assert!(find("atlantis").is_none());
+        assert!(find("").is_none());
+    }
+
+    #[test]
+    fn find_subdivisions() {
+        let us = find("us");
+        let code = |country, name| find_subdivision(country, name).map(|s| s.code);
+        assert_eq!(code(us, "n.y."), Some("US-NY"));
+        assert_eq!(code(us, "California"), Some("US-CA"));
+        assert_eq!(code(None, "california"), Some("US-CA"));
+        assert_eq!(code(None, "us-tx"), Some("US-TX"));
+        assert_eq!(code(find("de"), "bayern"), Some("DE-BY"));
+        assert_eq!(code(find("de"), "ny"), None);
+        // "ny" is only a code within a country.
+        assert_eq!(code(None, "ny"), None);
+        // Both in Brazil and, formerly, in Mexico.
+        assert_eq!(code(None, "distrito federal"), None);
+        assert_eq!(code(find("br"), "distrito federal"), Some("BR-DF"));
+    }
+}
@@ -0,0 +1,410 @@
+//! Scalar UDFs for [DataFusion](https://datafusion.apache.org).
+//!
+//! * `postal_parse(address [, language [, country]])`: a struct with one
+//!   string field per [`AddressLabel`], as built by [`AddressArrayBuilder`].
+//! * `postal_component(address, label [, language [, country]])`: the tokens
+//!   of a single label, e.g. `postal_component(address, 'city')`.
+//! * `postal_expand(address [, languages])`: a list of the normalized variations.
+//! * `postal_near_dupe_hashes(address [, languages])`: a list of the near-dupe
+//!   hashes of the parsed address.
+//!
+//! `languages` are comma-separated language codes, e.g. `'en,fr'`. Every
+//! argument may be a column or a literal, and null or empty hints are
+//! ignored. The UDFs do not load any models; set up the modules they use
+//! before running queries.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use datafusion::prelude::SessionContext;
+//! use rustpostal::LibModules;
+//!
+//! # async fn run() -> datafusion::error::Result<()> {
+//! let postal_module = LibModules::All;
+//! postal_module.setup().unwrap();
+//!
+//! let mut ctx = SessionContext::new();
+//! rustpostal::datafusion::register_udfs(&mut ctx)?;
+//! let df = ctx
+//!     .sql("SELECT postal_component('10 Downing St, London', 'city') AS city")
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+use std::any::Any;
+use std::ffi::NulError;
+use std::sync::Arc;
+
+use ::datafusion_common::cast::as_string_array;
+use ::datafusion_common::{exec_err, DataFusionError, Result};
+use ::datafusion_expr::registry::FunctionRegistry;
+use ::datafusion_expr::{
+    ColumnarValue, ScalarFunctionArgs, ScalarUDF, ScalarUDFImpl, Signature, TypeSignature,
+    Volatility,
+};
+use arrow_array::builder::{ListBuilder, StringBuilder};
+use arrow_array::{Array, ArrayRef, StringArray};
+use arrow_schema::{DataType, Field};
+
+use crate::address::{AddressLabel, AddressParserOptions, AddressParserResponse};
+use crate::arrow::{address_fields, AddressArrayBuilder};
+use crate::dedupe::NearDupeHashOptions;
+use crate::expand::NormalizeOptions;
+
+/// Return every UDF of the module.
+pub fn udfs() -> Vec<ScalarUDF> {
+    vec![
+        ScalarUDF::new_from_impl(PostalParse::new()),
+        ScalarUDF::new_from_impl(PostalComponent::new()),
+        ScalarUDF::new_from_impl(PostalExpand::new()),
+        ScalarUDF::new_from_impl(PostalNearDupeHashes::new()),
+    ]
+}
+
+/// Register every UDF of the module, e.g. with a `SessionContext`.
+pub fn register_udfs(registry: &mut dyn FunctionRegistry) -> Result<()> {
+    for udf in udfs() {
+        registry.register_udf(Arc::new(udf))?;
+    }
+    Ok(())
+}
+
+fn nul_error(err: NulError) -> DataFusionError {
+    DataFusionError::Execution(format!("invalid address: {}", err))
+}
+
+/// A signature of string arguments, from `min` to `max` of them.
+fn string_signature(min: usize, max: usize) -> Signature {
+    let signatures = (min..=max)
+        .map(|n| TypeSignature::Uniform(n, vec![DataType::Utf8]))
+        .collect();
+    Signature::one_of(signatures, Volatility::Immutable)
+}
+
+fn list_of_strings() -> DataType {
+    DataType::List(Arc::new(Field::new("item", DataType::Utf8, true)))
+}
+
+/// Evaluate the arguments to string arrays of the batch size.
+fn string_arrays(args: &ScalarFunctionArgs) -> Result<Vec<StringArray>> {
+    args.args
+        .iter()
+        .map(|arg| {
+            let array = arg.to_array(args.number_rows)?;
+            Ok(as_string_array(&array)?.clone())
+        })
+        .collect()
+}
+
+/// The non-empty value of an optional argument at `row`.
+fn hint(arrays: &[StringArray], index: usize, row: usize) -> Option<&str> {
+    arrays
+        .get(index)
+        .filter(|array| array.is_valid(row))
+        .map(|array| array.value(row).trim())
+        .filter(|value| !value.is_empty())
+}
+
+/// Split comma-separated language codes.
+fn languages(hint: Option<&str>) -> Option<Vec<&str>> {
+    hint.map(|languages| {
+        languages
+            .split(',')
+            .map(str::trim)
+            .filter(|language| !language.is_empty())
+            .collect()
+    })
+}
+
+fn parse(
+    address: &str,
+    language: Option<&str>,
+    country: Option<&str>,
+) -> Result<AddressParserResponse> {
+    AddressParserOptions::new(language, country)
+        .and_then(|options| options.parse(address))
+        .map_err(nul_error)
+}
+
+fn finish(array: ArrayRef) -> Result<ColumnarValue> {
+    Ok(ColumnarValue::Array(array))
+}
+
+/// `postal_parse(address [, language [, country]])`.
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct PostalParse {
+    signature: Signature,
+}
+
+impl PostalParse {
+    fn new() -> Self {
+        PostalParse {
+            signature: string_signature(1, 3),
+        }
+    }
+}
+
+impl ScalarUDFImpl for PostalParse {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "postal_parse"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Struct(address_fields()))
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        let arrays = string_arrays(&args)?;
+        let addresses = &arrays[0];
+        let mut builder = AddressArrayBuilder::with_capacity(addresses.len());
+        for row in 0..addresses.len() {
+            if addresses.is_null(row) {
+                builder.append_null();
+                continue;
+            }
+            let response = parse(
+                addresses.value(row),
+                hint(&arrays, 1, row),
+                hint(&arrays, 2, row),
+            )?;
+            builder.append(&response);
+        }
+        finish(Arc::new(builder.finish()))
+    }
+}
+
+/// `postal_component(address, label [, language [, country]])`.
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct PostalComponent {
+    signature: Signature,
+}
+
+impl PostalComponent {
+    fn new() -> Self {
+        PostalComponent {
+            signature: string_signature(2, 4),
+        }
+    }
+}
+
+impl ScalarUDFImpl for PostalComponent {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "postal_component"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Utf8)
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        let arrays = string_arrays(&args)?;
+        let (addresses, labels) = (&arrays[0], &arrays[1]);
+        let mut builder = StringBuilder::with_capacity(addresses.len(), 0);
+        for row in 0..addresses.len() {
+            if addresses.is_null(row) || labels.is_null(row) {
+                builder.append_null();
+                continue;
+            }
+            let label: AddressLabel = match labels.value(row).parse() {
+                Ok(label) => label,
+                Err(err) => return exec_err!("postal_component: {}", err),
+            };
+            let response = parse(
+                addresses.value(row),
+                hint(&arrays, 2, row),
+                hint(&arrays, 3, row),
+            )?;
+            builder.append_option(response.get(label));
+        }
+        finish(Arc::new(builder.finish()))
+    }
+}
+
+/// `postal_expand(address [, languages])`.
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct PostalExpand {
+    signature: Signature,
+}
+
+impl PostalExpand {
+    fn new() -> Self {
+        PostalExpand {
+            signature: string_signature(1, 2),
+        }
+    }
+}
+
+impl ScalarUDFImpl for PostalExpand {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "postal_expand"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(list_of_strings())
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        let arrays = string_arrays(&args)?;
+        let addresses = &arrays[0];
+        let mut builder = ListBuilder::with_capacity(StringBuilder::new(), addresses.len());
+        for row in 0..addresses.len() {
+            if addresses.is_null(row) {
+                builder.append_null();
+                continue;
+            }
+            let languages = languages(hint(&arrays, 1, row));
+            let mut options =
+                NormalizeOptions::new(languages.as_ref().map(|l| l.iter())).map_err(nul_error)?;
+            let expanded = options.expand(addresses.value(row)).map_err(nul_error)?;
+            for variation in &expanded {
+                builder.values().append_value(variation);
+            }
+            builder.append(true);
+        }
+        finish(Arc::new(builder.finish()))
+    }
+}
+
+/// `postal_near_dupe_hashes(address [, languages])`.
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct PostalNearDupeHashes {
+    signature: Signature,
+}
+
+impl PostalNearDupeHashes {
+    fn new() -> Self {
+        PostalNearDupeHashes {
+            signature: string_signature(1, 2),
+        }
+    }
+}
+
+impl ScalarUDFImpl for PostalNearDupeHashes {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "postal_near_dupe_hashes"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(list_of_strings())
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        let arrays = string_arrays(&args)?;
+        let addresses = &arrays[0];
+        let mut builder = ListBuilder::with_capacity(StringBuilder::new(), addresses.len());
+        for row in 0..addresses.len() {
+            if addresses.is_null(row) {
+                builder.append_null();
+                continue;
+            }
+            let languages = languages(hint(&arrays, 1, row));
+            let response = parse(addresses.value(row), None, None)?;
+            let hashes = NearDupeHashOptions::new(languages.as_ref().map(|l| l.iter()))
+                .and_then(|options| options.hashes(&response))
+                .map_err(nul_error)?;
+            for hash in &hashes {
+                builder.values().append_value(hash);
+            }
+            builder.append(true);
+        }
+        finish(Arc::new(builder.finish()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::datafusion_common::config::ConfigOptions;
+    use ::datafusion_common::ScalarValue;
+    use arrow_array::{ListArray, StructArray};
+
+    fn invoke(udf: &dyn ScalarUDFImpl, args: Vec<ColumnarValue>, rows: usize) -> ArrayRef {
+        let arg_types: Vec<DataType> = args.iter().map(|arg| arg.data_type()).collect();
+        let return_type = udf.return_type(&arg_types).unwrap();
+        let args = ScalarFunctionArgs {
+            arg_fields: arg_types
+                .iter()
+                .map(|t| Arc::new(Field::new("arg", t.clone(), true)))
+                .collect(),
+            args,
+            number_rows: rows,
+            return_field: Arc::new(Field::new("result", return_type, true)),
+            config_options: Arc::new(ConfigOptions::default()),
+        };
+        match udf.invoke_with_args(args).unwrap() {
+            ColumnarValue::Array(array) => array,
+            ColumnarValue::Scalar(_) => panic!("expected an array"),
+        }
+    }
+
+    fn null_addresses() -> ColumnarValue {
+        ColumnarValue::Array(Arc::new(StringArray::from(vec![None::<&str>, None])))
+    }
+
+    #[test]
+    fn udf_names() {
+        let names: Vec<String> = udfs().iter().map(|udf| udf.name().to_string()).collect();
+        assert_eq!(
+            names,
+            [
+                "postal_parse",
+                "postal_component",
+                "postal_expand",
+                "postal_near_dupe_hashes"
+            ]
+        );
+    }
+
+    #[test]
+    fn null_addresses_are_null() {
+        let parsed = invoke(&PostalParse::new(), vec![null_addresses()], 2);
+        let parsed = parsed.as_any().downcast_ref::<StructArray>().unwrap();
+        assert_eq!(parsed.null_count(), 2);
+
+        let label = ColumnarValue::Scalar(ScalarValue::Utf8(Some(String::from("city"))));
+        let city = invoke(&PostalComponent::new(), vec![null_addresses(), label], 2);
+        assert_eq!(city.null_count(), 2);
+
+        let expanded = invoke(&PostalExpand::new(), vec![null_addresses()], 2);
+        let expanded = expanded.as_any().downcast_ref::<ListArray>().unwrap();
+        assert_eq!(expanded.null_count(), 2);
+    }
+
+    #[test]
+    fn split_languages() {
+        assert_eq!(languages(Some("en, fr,")), Some(vec!["en", "fr"]));
+        assert_eq!(languages(None), None);
+    }
+}
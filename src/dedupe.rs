@@ -0,0 +1,645 @@
+//! Deduplication utilities for postal addresses.
+//!
+//! Near-dupe hashes are used as blocking keys to find candidate duplicates,
+//! while pairwise methods decide whether two address components refer to the
+//! same thing.
+//!
+//! # Examples
+//!
+//! ```
+//! use rustpostal::{dedupe, LibModules};
+//! use rustpostal::dedupe::{DuplicateOptions, DuplicateStatus, NearDupeHashOptions};
+//! use rustpostal::error::RuntimeError;
+//!
+//! fn main() -> Result<(), RuntimeError> {
+//!     let postal_module = LibModules::Dedupe;
+//!     postal_module.setup()?;
+//!
+//!     let components = [("house_number", "660"), ("road", "nostrand ave"), ("postcode", "11216")];
+//!     let options = NearDupeHashOptions::new(Some(["en"].iter()))?;
+//!     for hash in options.hashes(components.iter().copied())? {
+//!         println!("{}", hash);
+//!     }
+//!
+//!     let options = DuplicateOptions::new(Some(["en"].iter()))?;
+//!     let status = options.is_street_duplicate("nostrand ave", "nostrand avenue")?;
+//!     assert!(status >= DuplicateStatus::LikelyDuplicate);
+//!     Ok(())
+//! }
+//! ```
+use std::ffi::{CStr, CString, NulError};
+use std::str::FromStr;
+
+use bitflags::bitflags;
+use libc::c_char;
+
+use crate::error::ParseOptionError;
+use crate::ffi;
+
+/// Outcome of a pairwise duplicate check, ordered by confidence.
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum DuplicateStatus {
+    /// The check could not be performed, e.g. one of the values was empty.
+    Null,
+    NonDuplicate,
+    PossibleDuplicateNeedsReview,
+    LikelyDuplicate,
+    ExactDuplicate,
+}
+
+impl From<ffi::libpostal_duplicate_status_t> for DuplicateStatus {
+    fn from(status: ffi::libpostal_duplicate_status_t) -> Self {
+        match status {
+            ffi::libpostal_duplicate_status_t_LIBPOSTAL_NON_DUPLICATE => {
+                DuplicateStatus::NonDuplicate
+            }
+            ffi::libpostal_duplicate_status_t_LIBPOSTAL_POSSIBLE_DUPLICATE_NEEDS_REVIEW => {
+                DuplicateStatus::PossibleDuplicateNeedsReview
+            }
+            ffi::libpostal_duplicate_status_t_LIBPOSTAL_LIKELY_DUPLICATE => {
+                DuplicateStatus::LikelyDuplicate
+            }
+            ffi::libpostal_duplicate_status_t_LIBPOSTAL_EXACT_DUPLICATE => {
+                DuplicateStatus::ExactDuplicate
+            }
+            _ => DuplicateStatus::Null,
+        }
+    }
+}
+
+impl DuplicateStatus {
+    /// Whether the status indicates at least a likely duplicate.
+    pub fn is_duplicate(&self) -> bool {
+        *self >= DuplicateStatus::LikelyDuplicate
+    }
+}
+
+bitflags! {
+    /// Bit set of address components to include in near-dupe hashes.
+    pub struct HashComponents: u8 {
+        const NAME = 1 << 0;
+        const ADDRESS = 1 << 1;
+        const UNIT = 1 << 2;
+        const CITY_OR_EQUIVALENT = 1 << 3;
+        const SMALL_CONTAINING_BOUNDARIES = 1 << 4;
+        const POSTAL_CODE = 1 << 5;
+        const LATLON = 1 << 6;
+    }
+}
+
+impl Default for HashComponents {
+    /// The components hashed by default in `libpostal`.
+    fn default() -> Self {
+        HashComponents::NAME
+            | HashComponents::ADDRESS
+            | HashComponents::CITY_OR_EQUIVALENT
+            | HashComponents::SMALL_CONTAINING_BOUNDARIES
+            | HashComponents::POSTAL_CODE
+    }
+}
+
+bitflags! {
+    /// Bit set of key types generated as near-dupe hashes.
+    pub struct HashKeys: u8 {
+        const NAME_AND_ADDRESS = 1 << 0;
+        const NAME_ONLY = 1 << 1;
+        const ADDRESS_ONLY = 1 << 2;
+    }
+}
+
+impl Default for HashKeys {
+    /// The keys generated by default in `libpostal`.
+    fn default() -> Self {
+        HashKeys::NAME_AND_ADDRESS
+    }
+}
+
+const HASH_COMPONENT_NAMES: &[(&str, HashComponents)] = &[
+    ("name", HashComponents::NAME),
+    ("address", HashComponents::ADDRESS),
+    ("unit", HashComponents::UNIT),
+    ("city_or_equivalent", HashComponents::CITY_OR_EQUIVALENT),
+    (
+        "small_containing_boundaries",
+        HashComponents::SMALL_CONTAINING_BOUNDARIES,
+    ),
+    ("postal_code", HashComponents::POSTAL_CODE),
+    ("latlon", HashComponents::LATLON),
+];
+
+impl FromStr for HashComponents {
+    type Err = ParseOptionError;
+
+    /// Parse a single component from its lowercase name, e.g. `postal_code`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        crate::flag_from_name(HASH_COMPONENT_NAMES, s)
+    }
+}
+
+const HASH_KEY_NAMES: &[(&str, HashKeys)] = &[
+    ("name_and_address", HashKeys::NAME_AND_ADDRESS),
+    ("name_only", HashKeys::NAME_ONLY),
+    ("address_only", HashKeys::ADDRESS_ONLY),
+];
+
+impl FromStr for HashKeys {
+    type Err = ParseOptionError;
+
+    /// Parse a single key type from its lowercase name, e.g. `name_only`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        crate::flag_from_name(HASH_KEY_NAMES, s)
+    }
+}
+
+/// Owned C strings along with the pointer array passed to `libpostal`.
+struct CStringArray {
+    _strings: Vec<CString>,
+    ptrs: Vec<*mut c_char>,
+}
+
+impl CStringArray {
+    fn new<I, S>(strings: I) -> Result<CStringArray, NulError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let strings = strings
+            .into_iter()
+            .map(|s| CString::new(s.as_ref()))
+            .collect::<Result<Vec<CString>, NulError>>()?;
+        Ok(CStringArray::from_cstrings(strings))
+    }
+
+    fn from_cstrings(strings: Vec<CString>) -> CStringArray {
+        let ptrs = strings.iter().map(|s| s.as_ptr() as *mut c_char).collect();
+        CStringArray {
+            _strings: strings,
+            ptrs,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.ptrs.len()
+    }
+
+    fn as_mut_ptr(&mut self) -> *mut *mut c_char {
+        self.ptrs.as_mut_ptr()
+    }
+}
+
+/// Split `(label, value)` pairs into label and value arrays.
+fn components_arrays<I, L, V>(components: I) -> Result<(CStringArray, CStringArray), NulError>
+where
+    I: IntoIterator<Item = (L, V)>,
+    L: AsRef<str>,
+    V: AsRef<str>,
+{
+    let (labels, values): (Vec<L>, Vec<V>) = components.into_iter().unzip();
+    Ok((CStringArray::new(labels)?, CStringArray::new(values)?))
+}
+
+/// Convert an array of strings allocated by `libpostal`, and free it.
+unsafe fn take_string_array(raw: *mut *mut c_char, n: usize) -> Vec<String> {
+    if raw.is_null() {
+        return Vec::new();
+    }
+    let mut strings = Vec::with_capacity(n);
+    for i in 0..n {
+        let s = CStr::from_ptr(*raw.add(i));
+        strings.push(String::from(s.to_str().unwrap()));
+    }
+    ffi::libpostal_expansion_array_destroy(raw, n);
+    strings
+}
+
+fn c_languages<'a, 'b, T>(languages: Option<T>) -> Result<Option<Vec<CString>>, NulError>
+where
+    'a: 'b,
+    T: Iterator<Item = &'b &'a str>,
+{
+    languages
+        .map(|languages| languages.map(|lang| CString::new(*lang)).collect())
+        .transpose()
+}
+
+/// Options for generating near-dupe hashes.
+///
+/// Near-dupe hashes are keys that similar addresses share, and are meant
+/// to be used for blocking, i.e. to limit the number of pairwise comparisons.
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct NearDupeHashOptions {
+    languages: Option<Vec<CString>>,
+    components: HashComponents,
+    keys: HashKeys,
+    latlon: Option<(f64, f64)>,
+    geohash_precision: Option<u32>,
+}
+
+impl NearDupeHashOptions {
+    /// Create new instance with default options.
+    ///
+    /// `languages` are used for normalization; if not given, they are
+    /// classified from the components.
+    pub fn new<'a, 'b, T>(languages: Option<T>) -> Result<NearDupeHashOptions, NulError>
+    where
+        'a: 'b,
+        T: Iterator<Item = &'b &'a str>,
+    {
+        Ok(NearDupeHashOptions {
+            languages: c_languages(languages)?,
+            ..Default::default()
+        })
+    }
+
+    /// Set the components to include in the hashes.
+    pub fn set_components(&mut self, components: HashComponents) {
+        self.components = components;
+    }
+
+    /// Set the key types to generate.
+    pub fn set_keys(&mut self, keys: HashKeys) {
+        self.keys = keys;
+    }
+
+    /// Include a geohash of the coordinates in the hashes.
+    ///
+    /// `precision` is the geohash length; `libpostal` defaults to 6.
+    pub fn set_latlon(&mut self, latitude: f64, longitude: f64, precision: Option<u32>) {
+        self.components.insert(HashComponents::LATLON);
+        self.latlon = Some((latitude, longitude));
+        self.geohash_precision = precision;
+    }
+
+    /// Return current components.
+    pub fn components(&self) -> HashComponents {
+        self.components
+    }
+
+    /// Return current key types.
+    pub fn keys(&self) -> HashKeys {
+        self.keys
+    }
+
+    fn ffi_options(&self) -> ffi::libpostal_near_dupe_hash_options {
+        let mut options = unsafe { ffi::libpostal_get_near_dupe_hash_default_options() };
+        let components = &self.components;
+        options.with_name = components.contains(HashComponents::NAME);
+        options.with_address = components.contains(HashComponents::ADDRESS);
+        options.with_unit = components.contains(HashComponents::UNIT);
+        options.with_city_or_equivalent = components.contains(HashComponents::CITY_OR_EQUIVALENT);
+        options.with_small_containing_boundaries =
+            components.contains(HashComponents::SMALL_CONTAINING_BOUNDARIES);
+        options.with_postal_code = components.contains(HashComponents::POSTAL_CODE);
+        options.with_latlon = components.contains(HashComponents::LATLON) && self.latlon.is_some();
+        if let Some((latitude, longitude)) = self.latlon {
+            options.latitude = latitude;
+            options.longitude = longitude;
+        }
+        if let Some(precision) = self.geohash_precision {
+            options.geohash_precision = precision;
+        }
+        options.name_and_address_keys = self.keys.contains(HashKeys::NAME_AND_ADDRESS);
+        options.name_only_keys = self.keys.contains(HashKeys::NAME_ONLY);
+        options.address_only_keys = self.keys.contains(HashKeys::ADDRESS_ONLY);
+        options
+    }
+
+    /// Generate near-dupe hashes for the `(label, value)` components of an address,
+    /// e.g. as returned by the address parser.
+    ///
+    /// # Errors
+    ///
+    /// It will return an error if any label or value contains an internal null byte.
+    pub fn hashes<I, L, V>(&self, components: I) -> Result<Vec<String>, NulError>
+    where
+        I: IntoIterator<Item = (L, V)>,
+        L: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let (mut labels, mut values) = components_arrays(components)?;
+        let options = self.ffi_options();
+        let mut n = 0;
+        let raw = match &self.languages {
+            Some(languages) => {
+                let mut languages = CStringArray::from_cstrings(languages.clone());
+                unsafe {
+                    ffi::libpostal_near_dupe_hashes_languages(
+                        labels.len(),
+                        labels.as_mut_ptr(),
+                        values.as_mut_ptr(),
+                        options,
+                        languages.len(),
+                        languages.as_mut_ptr(),
+                        &mut n,
+                    )
+                }
+            }
+            None => unsafe {
+                ffi::libpostal_near_dupe_hashes(
+                    labels.len(),
+                    labels.as_mut_ptr(),
+                    values.as_mut_ptr(),
+                    options,
+                    &mut n,
+                )
+            },
+        };
+        Ok(unsafe { take_string_array(raw, n) })
+    }
+}
+
+/// Generate near-dupe hashes with default options.
+///
+/// # Errors
+///
+/// It will return an error if any label or value contains an internal null byte.
+pub fn near_dupe_hashes<I, L, V>(components: I) -> Result<Vec<String>, NulError>
+where
+    I: IntoIterator<Item = (L, V)>,
+    L: AsRef<str>,
+    V: AsRef<str>,
+{
+    NearDupeHashOptions::default().hashes(components)
+}
+
+/// Classify the languages of an address from its `(label, value)` components.
+///
+/// # Errors
+///
+/// It will return an error if any label or value contains an internal null byte.
+pub fn place_languages<I, L, V>(components: I) -> Result<Vec<String>, NulError>
+where
+    I: IntoIterator<Item = (L, V)>,
+    L: AsRef<str>,
+    V: AsRef<str>,
+{
+    let (mut labels, mut values) = components_arrays(components)?;
+    let mut n = 0;
+    let raw = unsafe {
+        ffi::libpostal_place_languages(
+            labels.len(),
+            labels.as_mut_ptr(),
+            values.as_mut_ptr(),
+            &mut n,
+        )
+    };
+    Ok(unsafe { take_string_array(raw, n) })
+}
+
+type DuplicateFn = unsafe extern "C" fn(
+    *mut c_char,
+    *mut c_char,
+    ffi::libpostal_duplicate_options_t,
+) -> ffi::libpostal_duplicate_status_t;
+
+/// Options for pairwise duplicate checks.
+#[derive(Clone, Default, Debug, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub struct DuplicateOptions {
+    languages: Option<Vec<CString>>,
+}
+
+impl DuplicateOptions {
+    /// Create new instance with default options.
+    ///
+    /// `languages` are used for normalization; if not given, they are
+    /// classified from the compared values.
+    pub fn new<'a, 'b, T>(languages: Option<T>) -> Result<DuplicateOptions, NulError>
+    where
+        'a: 'b,
+        T: Iterator<Item = &'b &'a str>,
+    {
+        Ok(DuplicateOptions {
+            languages: c_languages(languages)?,
+        })
+    }
+
+    /// Return current languages.
+    pub fn languages(&self) -> Option<impl Iterator<Item = &str>> {
+        if let Some(languages) = &self.languages {
+            return Some(languages.as_slice().iter().map(|c| c.to_str().unwrap()));
+        }
+        None
+    }
+
+    fn with_ffi_options<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(ffi::libpostal_duplicate_options_t) -> R,
+    {
+        match &self.languages {
+            Some(languages) => {
+                let mut languages = CStringArray::from_cstrings(languages.clone());
+                let options = unsafe {
+                    ffi::libpostal_get_duplicate_options_with_languages(
+                        languages.len(),
+                        languages.as_mut_ptr(),
+                    )
+                };
+                f(options)
+            }
+            None => f(unsafe { ffi::libpostal_get_default_duplicate_options() }),
+        }
+    }
+
+    fn is_duplicate(
+        &self,
+        method: DuplicateFn,
+        value1: &str,
+        value2: &str,
+    ) -> Result<DuplicateStatus, NulError> {
+        let value1 = CString::new(value1)?;
+        let value2 = CString::new(value2)?;
+        let status = self.with_ffi_options(|options| unsafe {
+            method(
+                value1.as_ptr() as *mut c_char,
+                value2.as_ptr() as *mut c_char,
+                options,
+            )
+        });
+        Ok(DuplicateStatus::from(status))
+    }
+
+    /// Check whether two venue or building names are duplicates.
+    pub fn is_name_duplicate(
+        &self,
+        value1: &str,
+        value2: &str,
+    ) -> Result<DuplicateStatus, NulError> {
+        self.is_duplicate(ffi::libpostal_is_name_duplicate, value1, value2)
+    }
+
+    /// Check whether two street names are duplicates.
+    pub fn is_street_duplicate(
+        &self,
+        value1: &str,
+        value2: &str,
+    ) -> Result<DuplicateStatus, NulError> {
+        self.is_duplicate(ffi::libpostal_is_street_duplicate, value1, value2)
+    }
+
+    /// Check whether two house numbers are duplicates.
+    pub fn is_house_number_duplicate(
+        &self,
+        value1: &str,
+        value2: &str,
+    ) -> Result<DuplicateStatus, NulError> {
+        self.is_duplicate(ffi::libpostal_is_house_number_duplicate, value1, value2)
+    }
+
+    /// Check whether two PO boxes are duplicates.
+    pub fn is_po_box_duplicate(
+        &self,
+        value1: &str,
+        value2: &str,
+    ) -> Result<DuplicateStatus, NulError> {
+        self.is_duplicate(ffi::libpostal_is_po_box_duplicate, value1, value2)
+    }
+
+    /// Check whether two units are duplicates.
+    pub fn is_unit_duplicate(
+        &self,
+        value1: &str,
+        value2: &str,
+    ) -> Result<DuplicateStatus, NulError> {
+        self.is_duplicate(ffi::libpostal_is_unit_duplicate, value1, value2)
+    }
+
+    /// Check whether two floors are duplicates.
+    pub fn is_floor_duplicate(
+        &self,
+        value1: &str,
+        value2: &str,
+    ) -> Result<DuplicateStatus, NulError> {
+        self.is_duplicate(ffi::libpostal_is_floor_duplicate, value1, value2)
+    }
+
+    /// Check whether two postal codes are duplicates.
+    pub fn is_postal_code_duplicate(
+        &self,
+        value1: &str,
+        value2: &str,
+    ) -> Result<DuplicateStatus, NulError> {
+        self.is_duplicate(ffi::libpostal_is_postal_code_duplicate, value1, value2)
+    }
+
+    /// Check whether the toponyms (city, state, country etc.) of two addresses,
+    /// given as `(label, value)` components, are duplicates.
+    pub fn is_toponym_duplicate<I1, I2, L, V>(
+        &self,
+        components1: I1,
+        components2: I2,
+    ) -> Result<DuplicateStatus, NulError>
+    where
+        I1: IntoIterator<Item = (L, V)>,
+        I2: IntoIterator<Item = (L, V)>,
+        L: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let (mut labels1, mut values1) = components_arrays(components1)?;
+        let (mut labels2, mut values2) = components_arrays(components2)?;
+        let status = self.with_ffi_options(|options| unsafe {
+            ffi::libpostal_is_toponym_duplicate(
+                labels1.len(),
+                labels1.as_mut_ptr(),
+                values1.as_mut_ptr(),
+                labels2.len(),
+                labels2.as_mut_ptr(),
+                values2.as_mut_ptr(),
+                options,
+            )
+        });
+        Ok(DuplicateStatus::from(status))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::RuntimeError;
+    use crate::LibModules;
+
+    #[test]
+    fn hash_options_from_str() {
+        assert_eq!("postal_code".parse(), Ok(HashComponents::POSTAL_CODE));
+        assert_eq!("name-only".parse(), Ok(HashKeys::NAME_ONLY));
+        assert!("zip".parse::<HashComponents>().is_err());
+    }
+
+    #[test]
+    fn duplicate_status_from_ffi() {
+        assert_eq!(DuplicateStatus::from(-1), DuplicateStatus::Null);
+        assert_eq!(DuplicateStatus::from(0), DuplicateStatus::NonDuplicate);
+        assert_eq!(
+            DuplicateStatus::from(3),
+            DuplicateStatus::PossibleDuplicateNeedsReview
+        );
+        assert_eq!(DuplicateStatus::from(6), DuplicateStatus::LikelyDuplicate);
+        assert_eq!(DuplicateStatus::from(9), DuplicateStatus::ExactDuplicate);
+        assert!(DuplicateStatus::ExactDuplicate.is_duplicate());
+        assert!(!DuplicateStatus::PossibleDuplicateNeedsReview.is_duplicate());
+    }
+
+    #[test]
+    fn near_dupe_hash_options_default() {
+        let options = NearDupeHashOptions::default();
+        assert_eq!(options.components(), HashComponents::default());
+        assert_eq!(options.keys(), HashKeys::NAME_AND_ADDRESS);
+        assert!(!options.components().contains(HashComponents::LATLON));
+    }
+
+    #[test]
+    fn near_dupe_hash_options_set_latlon() {
+        let mut options = NearDupeHashOptions::default();
+        options.set_latlon(40.67, -73.95, Some(5));
+        assert!(options.components().contains(HashComponents::LATLON));
+        assert_eq!(options.latlon, Some((40.67, -73.95)));
+        assert_eq!(options.geohash_precision, Some(5));
+    }
+
+    #[test]
+    fn duplicate_options_languages() -> Result<(), NulError> {
+        let options = DuplicateOptions::default();
+        assert!(options.languages().is_none());
+        let options = DuplicateOptions::new(Some(["en", "fr"].iter()))?;
+        assert_eq!(
+            options.languages().unwrap().collect::<Vec<&str>>(),
+            vec!["en", "fr"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn near_dupe_hashes_shared() -> Result<(), RuntimeError> {
+        let postal_module = LibModules::Dedupe;
+        postal_module.setup()?;
+
+        let options = NearDupeHashOptions::new(Some(["en"].iter()))?;
+        let hashes1 = options.hashes(vec![
+            ("house_number", "660"),
+            ("road", "nostrand ave"),
+            ("postcode", "11216"),
+        ])?;
+        let hashes2 = options.hashes(vec![
+            ("house_number", "660"),
+            ("road", "nostrand avenue"),
+            ("postcode", "11216"),
+        ])?;
+        assert!(hashes1.iter().any(|h| hashes2.contains(h)));
+        Ok(())
+    }
+
+    #[test]
+    fn duplicate_options_is_street_duplicate() -> Result<(), RuntimeError> {
+        let postal_module = LibModules::Dedupe;
+        postal_module.setup()?;
+
+        let options = DuplicateOptions::new(Some(["en"].iter()))?;
+        let status = options.is_street_duplicate("nostrand ave", "nostrand avenue")?;
+        assert!(status.is_duplicate());
+        Ok(())
+    }
+}
@@ -17,6 +17,82 @@ impl fmt::Display for SetupError {
 
 impl error::Error for SetupError {}
 
+/// An error returned when parsing an option from its name fails.
+///
+/// Option names are the lowercase names of the flags, e.g. `latin_ascii`
+/// for `StringOptions::LATIN_ASCII`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ParseOptionError {
+    name: String,
+}
+
+impl ParseOptionError {
+    #[cfg(any(feature = "expand", feature = "dedupe"))]
+    pub(crate) fn new(name: &str) -> Self {
+        ParseOptionError {
+            name: String::from(name),
+        }
+    }
+}
+
+impl fmt::Display for ParseOptionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unknown option `{}`", self.name)
+    }
+}
+
+impl error::Error for ParseOptionError {}
+
+/// An error returned when parsing an [`AddressLabel`](crate::address::AddressLabel)
+/// from its name fails.
+#[cfg(feature = "parser")]
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ParseLabelError {
+    label: String,
+}
+
+#[cfg(feature = "parser")]
+impl ParseLabelError {
+    pub(crate) fn new(label: &str) -> Self {
+        ParseLabelError {
+            label: String::from(label),
+        }
+    }
+}
+
+#[cfg(feature = "parser")]
+impl fmt::Display for ParseLabelError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unknown address label `{}`", self.label)
+    }
+}
+
+#[cfg(feature = "parser")]
+impl error::Error for ParseLabelError {}
+
+/// An error returned when parsing a
+/// [`HouseNumber`](crate::house_number::HouseNumber) fails.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ParseHouseNumberError {
+    house_number: String,
+}
+
+impl ParseHouseNumberError {
+    pub(crate) fn new(house_number: &str) -> Self {
+        ParseHouseNumberError {
+            house_number: String::from(house_number),
+        }
+    }
+}
+
+impl fmt::Display for ParseHouseNumberError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid house number `{}`", self.house_number)
+    }
+}
+
+impl error::Error for ParseHouseNumberError {}
+
 /// Error indicating possible runtime failures.
 #[derive(Debug, Clone)]
 pub enum RuntimeError {
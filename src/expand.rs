@@ -24,11 +24,17 @@
 //! ```
 
 use bitflags::bitflags;
+use std::cmp::Ordering;
+use std::collections::HashSet;
 use std::ffi::{CStr, CString, NulError};
 use std::iter::Iterator;
+use std::marker::PhantomData;
+use std::str::FromStr;
+use std::sync::OnceLock;
 
 use libc::{c_char, size_t};
 
+use crate::error::ParseOptionError;
 use crate::ffi;
 
 bitflags! {
@@ -77,10 +83,87 @@ bitflags! {
         }
 }
 
+const STRING_OPTION_NAMES: &[(&str, StringOptions)] = &[
+    ("transliterate", StringOptions::TRANSLITERATE),
+    ("strip_accents", StringOptions::STRIP_ACCENTS),
+    ("decompose", StringOptions::DECOMPOSE),
+    ("lowercase", StringOptions::LOWERCASE),
+    ("trim_string", StringOptions::TRIM_STRING),
+    ("drop_parentheticals", StringOptions::DROP_PARENTHETICALS),
+    (
+        "replace_numeric_hyphens",
+        StringOptions::REPLACE_NUMERIC_HYPHENS,
+    ),
+    (
+        "delete_numeric_hyphens",
+        StringOptions::DELETE_NUMERIC_HYPHENS,
+    ),
+    (
+        "split_alpha_from_numeric",
+        StringOptions::SPLIT_ALPHA_FROM_NUMERIC,
+    ),
+    ("replace_word_hyphens", StringOptions::REPLACE_WORD_HYPHENS),
+    ("delete_word_hyphens", StringOptions::DELETE_WORD_HYPHENS),
+    ("delete_final_periods", StringOptions::DELETE_FINAL_PERIODS),
+    (
+        "delete_acronym_periods",
+        StringOptions::DELETE_ACRONYM_PERIODS,
+    ),
+    (
+        "drop_english_possessives",
+        StringOptions::DROP_ENGLISH_POSSESSIVES,
+    ),
+    ("delete_apostrophes", StringOptions::DELETE_APOSTROPHES),
+    ("expand_numex", StringOptions::EXPAND_NUMEX),
+    ("roman_numerals", StringOptions::ROMAN_NUMERALS),
+    ("latin_ascii", StringOptions::LATIN_ASCII),
+];
+
+impl FromStr for StringOptions {
+    type Err = ParseOptionError;
+
+    /// Parse a single option from its lowercase name, e.g. `latin_ascii`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        crate::flag_from_name(STRING_OPTION_NAMES, s)
+    }
+}
+
+const ADDRESS_COMPONENT_NAMES: &[(&str, AddressComponents)] = &[
+    ("none", AddressComponents::NONE),
+    ("any", AddressComponents::ANY),
+    ("name", AddressComponents::NAME),
+    ("house_number", AddressComponents::HOUSE_NUMBER),
+    ("street", AddressComponents::STREET),
+    ("unit", AddressComponents::UNIT),
+    ("level", AddressComponents::LEVEL),
+    ("staircase", AddressComponents::STAIRCASE),
+    ("entrance", AddressComponents::ENTRANCE),
+    ("category", AddressComponents::CATEGORY),
+    ("near", AddressComponents::NEAR),
+    ("toponym", AddressComponents::TOPONYM),
+    ("postal_code", AddressComponents::POSTAL_CODE),
+    ("po_box", AddressComponents::PO_BOX),
+];
+
+impl FromStr for AddressComponents {
+    type Err = ParseOptionError;
+
+    /// Parse a single component from its lowercase name, e.g. `house_number`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        crate::flag_from_name(ADDRESS_COMPONENT_NAMES, s)
+    }
+}
+
+type ExpandFn = unsafe extern "C" fn(
+    *mut c_char,
+    ffi::libpostal_normalize_options,
+    *mut size_t,
+) -> *mut *mut c_char;
+
 /// Wrap the options to pass to the C library.
 struct LibpostalNormalizeOptions {
     ffi: Option<ffi::libpostal_normalize_options>,
-    lang_buffer: Option<Vec<*const c_char>>,
+    lang_buffer: Option<Vec<*mut c_char>>,
 }
 
 /// Normalization options.
@@ -121,7 +204,14 @@ pub struct NormalizeOptions {
 }
 
 /// Collections of normalized variations of postal address.
+///
+/// With the `serde` feature it is serialized as a sequence of the variations.
 #[derive(Clone, Hash, Debug, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(from = "Vec<String>", into = "Vec<String>")
+)]
 pub struct NormalizedAddress {
     variations: Vec<String>,
     n: size_t,
@@ -164,7 +254,8 @@ impl LibpostalNormalizeOptions {
 
     /// Update languages in ffi.
     fn update_languages<'a, T: Iterator<Item = &'a CString>>(&mut self, languages: T) {
-        let mut lang_buffer: Vec<*const c_char> = languages.map(|s| s.as_ptr()).collect();
+        let mut lang_buffer: Vec<*mut c_char> =
+            languages.map(|s| s.as_ptr() as *mut c_char).collect();
         let ffi = self.inner_mut();
         ffi.languages = lang_buffer.as_mut_ptr();
         ffi.num_languages = lang_buffer.len();
@@ -173,23 +264,65 @@ impl LibpostalNormalizeOptions {
 
     /// Normalize address.
     fn expand(&mut self, address: &CStr) -> NormalizedAddress {
-        let mut result: NormalizedAddress = Default::default();
-        let options = self.ffi.take().unwrap();
-        let raw =
-            unsafe { ffi::libpostal_expand_address(address.as_ptr(), options, &mut result.n) };
-        result.variations = Vec::with_capacity(result.n);
+        self.expand_with(ffi::libpostal_expand_address, address)
+    }
+
+    /// Normalize address, keeping only the root of each variation.
+    fn expand_root(&mut self, address: &CStr) -> NormalizedAddress {
+        self.expand_with(ffi::libpostal_expand_address_root, address)
+    }
+
+    fn expand_with(&mut self, method: ExpandFn, address: &CStr) -> NormalizedAddress {
+        let mut variations = Vec::new();
+        let n = self.visit_with(method, address, |variation| {
+            variations.push(String::from(variation))
+        });
+        NormalizedAddress { variations, n }
+    }
+
+    /// Normalize address, passing each variation to `visit` as borrowed
+    /// from `libpostal`, and return the number of variations.
+    fn visit_with(
+        &mut self,
+        method: ExpandFn,
+        address: &CStr,
+        mut visit: impl FnMut(&str),
+    ) -> size_t {
+        let options = *self.inner_mut();
+        let mut n = 0;
+        let raw = unsafe { method(address.as_ptr() as *mut c_char, options, &mut n) };
         unsafe {
-            for i in 0..result.n {
+            for i in 0..n {
                 if let Some(phrase) = raw.add(i).as_ref() {
-                    let variation = CStr::from_ptr(*phrase);
-                    result
-                        .variations
-                        .push(String::from(variation.to_str().unwrap()));
+                    visit(CStr::from_ptr(*phrase).to_str().unwrap());
                 };
             }
-            ffi::libpostal_expansion_array_destroy(raw, result.n);
+            ffi::libpostal_expansion_array_destroy(raw, n);
         }
-        result
+        n
+    }
+}
+
+/// Normalizes many addresses with the same options, passing their
+/// variations to a callback instead of collecting them.
+pub(crate) struct Expander<'a> {
+    options: LibpostalNormalizeOptions,
+    buffer: Vec<u8>,
+    /// The languages of the options point into the normalize options.
+    _languages: PhantomData<&'a NormalizeOptions>,
+}
+
+impl Expander<'_> {
+    /// Normalize `address`, passing each variation to `visit`.
+    pub(crate) fn expand(
+        &mut self,
+        address: &str,
+        visit: impl FnMut(&str),
+    ) -> Result<(), NulError> {
+        let address = crate::c_str(&mut self.buffer, address)?;
+        self.options
+            .visit_with(ffi::libpostal_expand_address, address, visit);
+        Ok(())
     }
 }
 
@@ -242,6 +375,15 @@ impl NormalizeOptions {
     }
 
     /// Create libpostal options.
+    /// An expander of many addresses with these options.
+    pub(crate) fn expander(&self) -> Expander<'_> {
+        Expander {
+            options: self.libpostal_options(),
+            buffer: Vec::new(),
+            _languages: PhantomData,
+        }
+    }
+
     fn libpostal_options(&self) -> LibpostalNormalizeOptions {
         let mut options: LibpostalNormalizeOptions = Default::default();
         if let Some(string_options) = &self.string_options {
@@ -347,6 +489,49 @@ impl NormalizeOptions {
         let c_address = CString::new(address)?;
         Ok(options.expand(&c_address))
     }
+
+    /// Normalize an address to its canonical variation, see
+    /// [`NormalizedAddress::canonical`].
+    ///
+    /// With several languages, they are tried in order of priority: the
+    /// canonical variation is the one of the first language the address has
+    /// variations in.
+    ///
+    /// # Errors
+    ///
+    /// The method will return an error if the supplied address
+    /// contains an internal null byte.
+    pub fn expand_canonical(&mut self, address: &str) -> Result<Option<String>, NulError> {
+        let languages = match &self.languages {
+            Some(languages) if languages.len() > 1 => languages.clone(),
+            _ => return Ok(self.expand(address)?.canonical().map(String::from)),
+        };
+        for language in languages {
+            let mut options = NormalizeOptions {
+                languages: Some(vec![language]),
+                ..self.clone()
+            };
+            if let Some(canonical) = options.expand(address)?.canonical() {
+                return Ok(Some(String::from(canonical)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Normalize an address to the roots of its variations.
+    ///
+    /// Roots drop the generic phrases of each component, e.g. street types
+    /// and directionals, so "Main St N" and "Main Street" share the root "main".
+    ///
+    /// # Errors
+    ///
+    /// The method will return an error if the supplied address
+    /// contains an internal null byte.
+    pub fn expand_root(&mut self, address: &str) -> Result<NormalizedAddress, NulError> {
+        let mut options = self.libpostal_options();
+        let c_address = CString::new(address)?;
+        Ok(options.expand_root(&c_address))
+    }
 }
 
 impl Default for NormalizedAddress {
@@ -370,6 +555,118 @@ impl NormalizedAddress {
     pub fn iter_mut(&mut self) -> std::slice::IterMut<String> {
         self.variations.as_mut_slice().iter_mut()
     }
+
+    /// Returns the variations from the most to the least expanded.
+    ///
+    /// Variations are ordered by:
+    ///
+    /// 1. the number of words of the `libpostal` dictionaries in the position
+    ///    of their kind, descending: street types following the name and
+    ///    personal titles preceding it. House numbers are not names, so
+    ///    "main drive" comes before "main doctor", and "10 saint johns road"
+    ///    before "10 street johns road";
+    /// 2. the number of letters and digits, descending, as expanding the
+    ///    abbreviations of the dictionaries lengthens words, e.g. "st" to
+    ///    "street";
+    /// 3. the number of words, descending, e.g. "champs elysees" before
+    ///    "champselysees";
+    /// 4. the variations, in lexicographic order of bytes.
+    ///
+    /// The order only depends on the variations, not on the order `libpostal`
+    /// returns them in.
+    pub fn ranked(&self) -> Vec<&String> {
+        let mut ranked: Vec<&String> = self.iter().collect();
+        ranked.sort_by(|a, b| cmp_rank(a, b));
+        ranked.dedup();
+        ranked
+    }
+
+    /// Returns the canonical variation, the first of
+    /// [`ranked`](NormalizedAddress::ranked), if any.
+    ///
+    /// For a language priority, see [`NormalizeOptions::expand_canonical`].
+    pub fn canonical(&self) -> Option<&str> {
+        self.iter()
+            .min_by(|a, b| cmp_rank(a, b))
+            .map(String::as_str)
+    }
+}
+
+/// Read the words of a dictionary file, skipping comments.
+fn dictionary(file: &'static str) -> HashSet<&'static str> {
+    file.lines()
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect()
+}
+
+/// The street types of the `libpostal` dictionaries, e.g. "drive" or "rue".
+fn street_types() -> &'static HashSet<&'static str> {
+    static STREET_TYPES: OnceLock<HashSet<&str>> = OnceLock::new();
+    STREET_TYPES
+        .get_or_init(|| dictionary(include_str!("../resources/dictionaries/street_types.txt")))
+}
+
+/// The personal titles of the `libpostal` dictionaries, e.g. "saint" or
+/// "doctor".
+fn personal_titles() -> &'static HashSet<&'static str> {
+    static PERSONAL_TITLES: OnceLock<HashSet<&str>> = OnceLock::new();
+    PERSONAL_TITLES.get_or_init(|| {
+        dictionary(include_str!(
+            "../resources/dictionaries/personal_titles.txt"
+        ))
+    })
+}
+
+/// Count the street types following a name and the personal titles
+/// preceding one.
+///
+/// Name words are the words neither numeric nor in the dictionaries, so
+/// that neither a house number nor a street type make a name.
+fn dictionary_hits(variation: &str) -> usize {
+    let (street_types, personal_titles) = (street_types(), personal_titles());
+    let is_name = |word: &str| {
+        !word.chars().any(char::is_numeric)
+            && !street_types.contains(word)
+            && !personal_titles.contains(word)
+    };
+    let words: Vec<&str> = variation.split_whitespace().collect();
+    words
+        .iter()
+        .enumerate()
+        .filter(|&(i, word)| {
+            let follows_name = || words[..i].iter().any(|word| is_name(word));
+            let precedes_name = || words.get(i + 1).is_some_and(|word| is_name(word));
+            (street_types.contains(word) && follows_name())
+                || (personal_titles.contains(word) && precedes_name())
+        })
+        .count()
+}
+
+/// Compare variations by rank, see [`NormalizedAddress::ranked`].
+fn cmp_rank(a: &str, b: &str) -> Ordering {
+    let key = |variation: &str| {
+        (
+            dictionary_hits(variation),
+            variation.chars().filter(|c| c.is_alphanumeric()).count(),
+            variation.split_whitespace().count(),
+        )
+    };
+    key(b).cmp(&key(a)).then_with(|| a.cmp(b))
+}
+
+impl From<Vec<String>> for NormalizedAddress {
+    fn from(variations: Vec<String>) -> Self {
+        NormalizedAddress {
+            n: variations.len(),
+            variations,
+        }
+    }
+}
+
+impl From<NormalizedAddress> for Vec<String> {
+    fn from(normalized: NormalizedAddress) -> Self {
+        normalized.variations
+    }
 }
 
 impl<'a> IntoIterator for &'a NormalizedAddress {
@@ -427,6 +724,14 @@ mod test {
     use crate::error::RuntimeError;
     use crate::LibModules;
 
+    #[test]
+    fn string_options_from_str() {
+        assert_eq!("latin_ascii".parse(), Ok(StringOptions::LATIN_ASCII));
+        assert_eq!("Strip-Accents".parse(), Ok(StringOptions::STRIP_ACCENTS));
+        assert!("latin".parse::<StringOptions>().is_err());
+        assert_eq!("po_box".parse(), Ok(AddressComponents::PO_BOX));
+    }
+
     #[test]
     fn default_libpostal_normalize_options() {
         let options: LibpostalNormalizeOptions = Default::default();
@@ -541,4 +846,67 @@ mod test {
             assert_eq!(variation.as_str(), expected[i]);
         }
     }
+
+    #[test]
+    fn normalized_address_ranked() {
+        let normalized = NormalizedAddress::from(vec![
+            String::from("92 avenue des champselysees"),
+            String::from("123 main st"),
+            String::from("92 avenue des champs elysees"),
+            String::from("92 avenue des champs-elysees"),
+            String::from("92 avenue des champs elysees"),
+        ]);
+        assert_eq!(
+            normalized.ranked(),
+            [
+                "92 avenue des champs elysees",
+                "92 avenue des champs-elysees",
+                "92 avenue des champselysees",
+                "123 main st",
+            ]
+        );
+        assert_eq!(normalized.canonical(), Some("92 avenue des champs elysees"));
+
+        let normalized = NormalizedAddress::from(vec![
+            String::from("123 main saint"),
+            String::from("123 main st"),
+            String::from("123 main street"),
+        ]);
+        assert_eq!(normalized.canonical(), Some("123 main street"));
+
+        let normalized = NormalizedAddress::from(vec![
+            String::from("street johns centre"),
+            String::from("st johns centre"),
+            String::from("saint johns centre"),
+        ]);
+        assert_eq!(normalized.canonical(), Some("saint johns centre"));
+
+        let normalized = NormalizedAddress::from(vec![
+            String::from("10 street johns road"),
+            String::from("10 st johns road"),
+            String::from("10 saint johns road"),
+        ]);
+        assert_eq!(normalized.canonical(), Some("10 saint johns road"));
+
+        let normalized = NormalizedAddress::from(vec![
+            String::from("10 rue street jacques"),
+            String::from("10 rue st jacques"),
+            String::from("10 rue saint jacques"),
+        ]);
+        assert_eq!(normalized.canonical(), Some("10 rue saint jacques"));
+
+        let normalized = NormalizedAddress::from(vec![
+            String::from("calle sta maria 5"),
+            String::from("calle santa maria 5"),
+        ]);
+        assert_eq!(normalized.canonical(), Some("calle santa maria 5"));
+
+        let normalized = NormalizedAddress::from(vec![
+            String::from("main doctor"),
+            String::from("main drive"),
+            String::from("main dr"),
+        ]);
+        assert_eq!(normalized.canonical(), Some("main drive"));
+        assert_eq!(NormalizedAddress::default().canonical(), None);
+    }
 }
@@ -1,71 +1,65 @@
-use libc;
+//! Raw bindings to the `libpostal` C API.
+//!
+//! Pre-generated bindings for the pinned `libpostal.h` are checked in under
+//! `src/ffi/bindings.rs`. With the `bindgen` feature they are generated at build
+//! time from the header of the linked library instead.
+//!
+//! Declarations are re-exported according to the enabled cargo features.
 
-#[link(name = "postal")]
-extern "C" {
-    pub fn libpostal_get_address_parser_default_options() -> libpostal_address_parser_options;
-    pub fn libpostal_setup() -> bool;
-    pub fn libpostal_setup_parser() -> bool;
-    pub fn libpostal_setup_language_classifier() -> bool;
-    pub fn libpostal_teardown() -> bool;
-    pub fn libpostal_teardown_parser() -> bool;
-    pub fn libpostal_teardown_language_classifier() -> bool;
+#[allow(
+    dead_code,
+    non_camel_case_types,
+    non_snake_case,
+    non_upper_case_globals,
+    clippy::all
+)]
+mod bindings {
+    #[cfg(feature = "bindgen")]
+    include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
 
-    pub fn libpostal_parse_address(
-        address: *const libc::c_char,
-        options: libpostal_address_parser_options,
-    ) -> *const libpostal_address_parser_response;
+    #[cfg(not(feature = "bindgen"))]
+    include!("ffi/bindings.rs");
+}
 
-    pub fn libpostal_address_parser_response_destroy(
-        response: *const libpostal_address_parser_response,
-    );
+pub use self::bindings::{
+    libpostal_setup, libpostal_setup_datadir, libpostal_setup_language_classifier,
+    libpostal_setup_language_classifier_datadir, libpostal_setup_parser,
+    libpostal_setup_parser_datadir, libpostal_teardown, libpostal_teardown_language_classifier,
+    libpostal_teardown_parser,
+};
 
-    pub fn libpostal_get_default_options() -> libpostal_normalize_options;
-    pub fn libpostal_expand_address(
-        input: *const libc::c_char,
-        options: libpostal_normalize_options,
-        n: *mut libc::size_t,
-    ) -> *const *const libc::c_char;
-    pub fn libpostal_expansion_array_destroy(
-        expansions: *const *const libc::c_char,
-        n: libc::size_t,
-    );
-}
+#[cfg(any(feature = "expand", feature = "dedupe"))]
+pub use self::bindings::libpostal_expansion_array_destroy;
 
-#[repr(C)]
-pub struct libpostal_address_parser_options {
-    pub language: *const libc::c_char,
-    pub country: *const libc::c_char,
-}
+#[cfg(feature = "parser")]
+pub use self::bindings::{
+    libpostal_address_parser_options, libpostal_address_parser_response_destroy,
+    libpostal_get_address_parser_default_options, libpostal_parse_address,
+};
 
-#[repr(C)]
-pub struct libpostal_address_parser_response {
-    pub num_components: usize,
-    pub components: *mut *const libc::c_char,
-    pub labels: *mut *const libc::c_char,
-}
+#[cfg(feature = "expand")]
+pub use self::bindings::{
+    libpostal_expand_address, libpostal_expand_address_root, libpostal_get_default_options,
+    libpostal_normalize_options,
+};
 
-#[repr(C)]
-pub struct libpostal_normalize_options {
-    pub languages: *mut *const libc::c_char,
-    pub num_languages: libc::size_t,
-    pub address_components: u16,
-    // String options
-    pub latin_ascii: bool,
-    pub transliterate: bool,
-    pub strip_accents: bool,
-    pub decompose: bool,
-    pub lowercase: bool,
-    pub trim_string: bool,
-    pub drop_parentheticals: bool,
-    pub replace_numeric_hyphens: bool,
-    pub delete_numeric_hyphens: bool,
-    pub split_alpha_from_numeric: bool,
-    pub replace_word_hyphens: bool,
-    pub delete_word_hyphens: bool,
-    pub delete_final_periods: bool,
-    pub delete_acronym_periods: bool,
-    pub drop_english_possessives: bool,
-    pub delete_apostrophes: bool,
-    pub expand_numex: bool,
-    pub roman_numerals: bool,
-}
+#[cfg(feature = "language")]
+pub use self::bindings::{
+    libpostal_classify_language, libpostal_language_classifier_response_destroy,
+};
+
+#[cfg(feature = "dedupe")]
+pub use self::bindings::{
+    libpostal_duplicate_options_t, libpostal_duplicate_status_t,
+    libpostal_duplicate_status_t_LIBPOSTAL_EXACT_DUPLICATE,
+    libpostal_duplicate_status_t_LIBPOSTAL_LIKELY_DUPLICATE,
+    libpostal_duplicate_status_t_LIBPOSTAL_NON_DUPLICATE,
+    libpostal_duplicate_status_t_LIBPOSTAL_POSSIBLE_DUPLICATE_NEEDS_REVIEW,
+    libpostal_get_default_duplicate_options, libpostal_get_duplicate_options_with_languages,
+    libpostal_get_near_dupe_hash_default_options, libpostal_is_floor_duplicate,
+    libpostal_is_house_number_duplicate, libpostal_is_name_duplicate,
+    libpostal_is_po_box_duplicate, libpostal_is_postal_code_duplicate,
+    libpostal_is_street_duplicate, libpostal_is_toponym_duplicate, libpostal_is_unit_duplicate,
+    libpostal_near_dupe_hash_options, libpostal_near_dupe_hashes,
+    libpostal_near_dupe_hashes_languages, libpostal_place_languages,
+};
@@ -0,0 +1,1116 @@
+// Bindings to `libpostal.h` of libpostal 1.1.0, for 64-bit targets.
+//
+// The file mirrors the output of `bindgen` for the header shipped in
+// `vendor/libpostal/src/libpostal.h`. Build with the `bindgen` feature to
+// generate the bindings from the installed header instead.
+
+pub const LIBPOSTAL_MAX_LANGUAGE_LEN: u32 = 4;
+pub const LIBPOSTAL_ADDRESS_NONE: u32 = 0;
+pub const LIBPOSTAL_ADDRESS_ANY: u32 = 1;
+pub const LIBPOSTAL_ADDRESS_NAME: u32 = 2;
+pub const LIBPOSTAL_ADDRESS_HOUSE_NUMBER: u32 = 4;
+pub const LIBPOSTAL_ADDRESS_STREET: u32 = 8;
+pub const LIBPOSTAL_ADDRESS_UNIT: u32 = 16;
+pub const LIBPOSTAL_ADDRESS_LEVEL: u32 = 32;
+pub const LIBPOSTAL_ADDRESS_STAIRCASE: u32 = 64;
+pub const LIBPOSTAL_ADDRESS_ENTRANCE: u32 = 128;
+pub const LIBPOSTAL_ADDRESS_CATEGORY: u32 = 256;
+pub const LIBPOSTAL_ADDRESS_NEAR: u32 = 512;
+pub const LIBPOSTAL_ADDRESS_TOPONYM: u32 = 8192;
+pub const LIBPOSTAL_ADDRESS_POSTAL_CODE: u32 = 16384;
+pub const LIBPOSTAL_ADDRESS_PO_BOX: u32 = 32768;
+pub const LIBPOSTAL_ADDRESS_ALL: u32 = 65535;
+pub const LIBPOSTAL_NORMALIZE_STRING_LATIN_ASCII: u32 = 1;
+pub const LIBPOSTAL_NORMALIZE_STRING_TRANSLITERATE: u32 = 2;
+pub const LIBPOSTAL_NORMALIZE_STRING_STRIP_ACCENTS: u32 = 4;
+pub const LIBPOSTAL_NORMALIZE_STRING_DECOMPOSE: u32 = 8;
+pub const LIBPOSTAL_NORMALIZE_STRING_LOWERCASE: u32 = 16;
+pub const LIBPOSTAL_NORMALIZE_STRING_TRIM: u32 = 32;
+pub const LIBPOSTAL_NORMALIZE_STRING_REPLACE_HYPHENS: u32 = 64;
+pub const LIBPOSTAL_NORMALIZE_STRING_COMPOSE: u32 = 128;
+pub const LIBPOSTAL_NORMALIZE_STRING_SIMPLE_LATIN_ASCII: u32 = 256;
+pub const LIBPOSTAL_NORMALIZE_STRING_REPLACE_NUMEX: u32 = 512;
+pub const LIBPOSTAL_NORMALIZE_TOKEN_REPLACE_HYPHENS: u32 = 1;
+pub const LIBPOSTAL_NORMALIZE_TOKEN_DELETE_HYPHENS: u32 = 2;
+pub const LIBPOSTAL_NORMALIZE_TOKEN_DELETE_FINAL_PERIOD: u32 = 4;
+pub const LIBPOSTAL_NORMALIZE_TOKEN_DELETE_ACRONYM_PERIODS: u32 = 8;
+pub const LIBPOSTAL_NORMALIZE_TOKEN_DROP_ENGLISH_POSSESSIVES: u32 = 16;
+pub const LIBPOSTAL_NORMALIZE_TOKEN_DELETE_OTHER_APOSTROPHE: u32 = 32;
+pub const LIBPOSTAL_NORMALIZE_TOKEN_SPLIT_ALPHA_FROM_NUMERIC: u32 = 64;
+pub const LIBPOSTAL_NORMALIZE_TOKEN_REPLACE_DIGITS: u32 = 128;
+pub const LIBPOSTAL_NORMALIZE_TOKEN_REPLACE_NUMERIC_TOKEN_LETTERS: u32 = 256;
+pub const LIBPOSTAL_NORMALIZE_TOKEN_REPLACE_NUMERIC_HYPHENS: u32 = 512;
+pub const LIBPOSTAL_NORMALIZE_DEFAULT_STRING_OPTIONS: u32 = 245;
+pub const LIBPOSTAL_NORMALIZE_DEFAULT_TOKEN_OPTIONS: u32 = 61;
+pub const LIBPOSTAL_NORMALIZE_TOKEN_OPTIONS_DROP_PERIODS: u32 = 12;
+pub const LIBPOSTAL_NORMALIZE_DEFAULT_TOKEN_OPTIONS_NUMERIC: u32 = 125;
+pub const libpostal_token_type_t_LIBPOSTAL_TOKEN_TYPE_END: libpostal_token_type_t = 0;
+pub const libpostal_token_type_t_LIBPOSTAL_TOKEN_TYPE_WORD: libpostal_token_type_t = 1;
+pub const libpostal_token_type_t_LIBPOSTAL_TOKEN_TYPE_ABBREVIATION: libpostal_token_type_t = 2;
+pub const libpostal_token_type_t_LIBPOSTAL_TOKEN_TYPE_IDEOGRAPHIC_CHAR: libpostal_token_type_t = 3;
+pub const libpostal_token_type_t_LIBPOSTAL_TOKEN_TYPE_HANGUL_SYLLABLE: libpostal_token_type_t = 4;
+pub const libpostal_token_type_t_LIBPOSTAL_TOKEN_TYPE_ACRONYM: libpostal_token_type_t = 5;
+pub const libpostal_token_type_t_LIBPOSTAL_TOKEN_TYPE_PHRASE: libpostal_token_type_t = 10;
+pub const libpostal_token_type_t_LIBPOSTAL_TOKEN_TYPE_EMAIL: libpostal_token_type_t = 20;
+pub const libpostal_token_type_t_LIBPOSTAL_TOKEN_TYPE_URL: libpostal_token_type_t = 21;
+pub const libpostal_token_type_t_LIBPOSTAL_TOKEN_TYPE_US_PHONE: libpostal_token_type_t = 22;
+pub const libpostal_token_type_t_LIBPOSTAL_TOKEN_TYPE_INTL_PHONE: libpostal_token_type_t = 23;
+pub const libpostal_token_type_t_LIBPOSTAL_TOKEN_TYPE_NUMERIC: libpostal_token_type_t = 50;
+pub const libpostal_token_type_t_LIBPOSTAL_TOKEN_TYPE_ORDINAL: libpostal_token_type_t = 51;
+pub const libpostal_token_type_t_LIBPOSTAL_TOKEN_TYPE_ROMAN_NUMERAL: libpostal_token_type_t = 52;
+pub const libpostal_token_type_t_LIBPOSTAL_TOKEN_TYPE_IDEOGRAPHIC_NUMBER: libpostal_token_type_t =
+    53;
+pub const libpostal_token_type_t_LIBPOSTAL_TOKEN_TYPE_PERIOD: libpostal_token_type_t = 100;
+pub const libpostal_token_type_t_LIBPOSTAL_TOKEN_TYPE_EXCLAMATION: libpostal_token_type_t = 101;
+pub const libpostal_token_type_t_LIBPOSTAL_TOKEN_TYPE_QUESTION_MARK: libpostal_token_type_t = 102;
+pub const libpostal_token_type_t_LIBPOSTAL_TOKEN_TYPE_COMMA: libpostal_token_type_t = 103;
+pub const libpostal_token_type_t_LIBPOSTAL_TOKEN_TYPE_COLON: libpostal_token_type_t = 104;
+pub const libpostal_token_type_t_LIBPOSTAL_TOKEN_TYPE_SEMICOLON: libpostal_token_type_t = 105;
+pub const libpostal_token_type_t_LIBPOSTAL_TOKEN_TYPE_PLUS: libpostal_token_type_t = 106;
+pub const libpostal_token_type_t_LIBPOSTAL_TOKEN_TYPE_AMPERSAND: libpostal_token_type_t = 107;
+pub const libpostal_token_type_t_LIBPOSTAL_TOKEN_TYPE_AT_SIGN: libpostal_token_type_t = 108;
+pub const libpostal_token_type_t_LIBPOSTAL_TOKEN_TYPE_POUND: libpostal_token_type_t = 109;
+pub const libpostal_token_type_t_LIBPOSTAL_TOKEN_TYPE_ELLIPSIS: libpostal_token_type_t = 110;
+pub const libpostal_token_type_t_LIBPOSTAL_TOKEN_TYPE_DASH: libpostal_token_type_t = 111;
+pub const libpostal_token_type_t_LIBPOSTAL_TOKEN_TYPE_BREAKING_DASH: libpostal_token_type_t = 112;
+pub const libpostal_token_type_t_LIBPOSTAL_TOKEN_TYPE_HYPHEN: libpostal_token_type_t = 113;
+pub const libpostal_token_type_t_LIBPOSTAL_TOKEN_TYPE_PUNCT_OPEN: libpostal_token_type_t = 114;
+pub const libpostal_token_type_t_LIBPOSTAL_TOKEN_TYPE_PUNCT_CLOSE: libpostal_token_type_t = 115;
+pub const libpostal_token_type_t_LIBPOSTAL_TOKEN_TYPE_DOUBLE_QUOTE: libpostal_token_type_t = 119;
+pub const libpostal_token_type_t_LIBPOSTAL_TOKEN_TYPE_SINGLE_QUOTE: libpostal_token_type_t = 120;
+pub const libpostal_token_type_t_LIBPOSTAL_TOKEN_TYPE_OPEN_QUOTE: libpostal_token_type_t = 121;
+pub const libpostal_token_type_t_LIBPOSTAL_TOKEN_TYPE_CLOSE_QUOTE: libpostal_token_type_t = 122;
+pub const libpostal_token_type_t_LIBPOSTAL_TOKEN_TYPE_SLASH: libpostal_token_type_t = 124;
+pub const libpostal_token_type_t_LIBPOSTAL_TOKEN_TYPE_BACKSLASH: libpostal_token_type_t = 125;
+pub const libpostal_token_type_t_LIBPOSTAL_TOKEN_TYPE_GREATER_THAN: libpostal_token_type_t = 126;
+pub const libpostal_token_type_t_LIBPOSTAL_TOKEN_TYPE_LESS_THAN: libpostal_token_type_t = 127;
+pub const libpostal_token_type_t_LIBPOSTAL_TOKEN_TYPE_OTHER: libpostal_token_type_t = 200;
+pub const libpostal_token_type_t_LIBPOSTAL_TOKEN_TYPE_WHITESPACE: libpostal_token_type_t = 300;
+pub const libpostal_token_type_t_LIBPOSTAL_TOKEN_TYPE_NEWLINE: libpostal_token_type_t = 301;
+pub const libpostal_token_type_t_LIBPOSTAL_TOKEN_TYPE_INVALID_CHAR: libpostal_token_type_t = 500;
+pub type libpostal_token_type_t = ::std::os::raw::c_uint;
+pub const libpostal_duplicate_status_t_LIBPOSTAL_NULL_DUPLICATE_STATUS:
+    libpostal_duplicate_status_t = -1;
+pub const libpostal_duplicate_status_t_LIBPOSTAL_NON_DUPLICATE: libpostal_duplicate_status_t = 0;
+pub const libpostal_duplicate_status_t_LIBPOSTAL_POSSIBLE_DUPLICATE_NEEDS_REVIEW:
+    libpostal_duplicate_status_t = 3;
+pub const libpostal_duplicate_status_t_LIBPOSTAL_LIKELY_DUPLICATE: libpostal_duplicate_status_t = 6;
+pub const libpostal_duplicate_status_t_LIBPOSTAL_EXACT_DUPLICATE: libpostal_duplicate_status_t = 9;
+pub type libpostal_duplicate_status_t = ::std::os::raw::c_int;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct libpostal_normalize_options {
+    pub languages: *mut *mut ::std::os::raw::c_char,
+    pub num_languages: usize,
+    pub address_components: u16,
+    pub latin_ascii: bool,
+    pub transliterate: bool,
+    pub strip_accents: bool,
+    pub decompose: bool,
+    pub lowercase: bool,
+    pub trim_string: bool,
+    pub drop_parentheticals: bool,
+    pub replace_numeric_hyphens: bool,
+    pub delete_numeric_hyphens: bool,
+    pub split_alpha_from_numeric: bool,
+    pub replace_word_hyphens: bool,
+    pub delete_word_hyphens: bool,
+    pub delete_final_periods: bool,
+    pub delete_acronym_periods: bool,
+    pub drop_english_possessives: bool,
+    pub delete_apostrophes: bool,
+    pub expand_numex: bool,
+    pub roman_numerals: bool,
+}
+#[test]
+#[cfg(target_pointer_width = "64")]
+fn bindgen_test_layout_libpostal_normalize_options() {
+    const UNINIT: ::std::mem::MaybeUninit<libpostal_normalize_options> =
+        ::std::mem::MaybeUninit::uninit();
+    let ptr = UNINIT.as_ptr();
+    assert_eq!(
+        ::std::mem::size_of::<libpostal_normalize_options>(),
+        40usize,
+        concat!("Size of: ", stringify!(libpostal_normalize_options))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<libpostal_normalize_options>(),
+        8usize,
+        concat!("Alignment of ", stringify!(libpostal_normalize_options))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).languages) as usize - ptr as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_normalize_options),
+            "::",
+            stringify!(languages)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).num_languages) as usize - ptr as usize },
+        8usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_normalize_options),
+            "::",
+            stringify!(num_languages)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).address_components) as usize - ptr as usize },
+        16usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_normalize_options),
+            "::",
+            stringify!(address_components)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).latin_ascii) as usize - ptr as usize },
+        18usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_normalize_options),
+            "::",
+            stringify!(latin_ascii)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).transliterate) as usize - ptr as usize },
+        19usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_normalize_options),
+            "::",
+            stringify!(transliterate)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).strip_accents) as usize - ptr as usize },
+        20usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_normalize_options),
+            "::",
+            stringify!(strip_accents)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).decompose) as usize - ptr as usize },
+        21usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_normalize_options),
+            "::",
+            stringify!(decompose)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).lowercase) as usize - ptr as usize },
+        22usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_normalize_options),
+            "::",
+            stringify!(lowercase)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).trim_string) as usize - ptr as usize },
+        23usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_normalize_options),
+            "::",
+            stringify!(trim_string)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).drop_parentheticals) as usize - ptr as usize },
+        24usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_normalize_options),
+            "::",
+            stringify!(drop_parentheticals)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).replace_numeric_hyphens) as usize - ptr as usize },
+        25usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_normalize_options),
+            "::",
+            stringify!(replace_numeric_hyphens)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).delete_numeric_hyphens) as usize - ptr as usize },
+        26usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_normalize_options),
+            "::",
+            stringify!(delete_numeric_hyphens)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).split_alpha_from_numeric) as usize - ptr as usize },
+        27usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_normalize_options),
+            "::",
+            stringify!(split_alpha_from_numeric)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).replace_word_hyphens) as usize - ptr as usize },
+        28usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_normalize_options),
+            "::",
+            stringify!(replace_word_hyphens)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).delete_word_hyphens) as usize - ptr as usize },
+        29usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_normalize_options),
+            "::",
+            stringify!(delete_word_hyphens)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).delete_final_periods) as usize - ptr as usize },
+        30usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_normalize_options),
+            "::",
+            stringify!(delete_final_periods)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).delete_acronym_periods) as usize - ptr as usize },
+        31usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_normalize_options),
+            "::",
+            stringify!(delete_acronym_periods)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).drop_english_possessives) as usize - ptr as usize },
+        32usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_normalize_options),
+            "::",
+            stringify!(drop_english_possessives)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).delete_apostrophes) as usize - ptr as usize },
+        33usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_normalize_options),
+            "::",
+            stringify!(delete_apostrophes)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).expand_numex) as usize - ptr as usize },
+        34usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_normalize_options),
+            "::",
+            stringify!(expand_numex)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).roman_numerals) as usize - ptr as usize },
+        35usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_normalize_options),
+            "::",
+            stringify!(roman_numerals)
+        )
+    );
+}
+pub type libpostal_normalize_options_t = libpostal_normalize_options;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct libpostal_address_parser_response {
+    pub num_components: usize,
+    pub components: *mut *mut ::std::os::raw::c_char,
+    pub labels: *mut *mut ::std::os::raw::c_char,
+}
+#[test]
+#[cfg(target_pointer_width = "64")]
+fn bindgen_test_layout_libpostal_address_parser_response() {
+    const UNINIT: ::std::mem::MaybeUninit<libpostal_address_parser_response> =
+        ::std::mem::MaybeUninit::uninit();
+    let ptr = UNINIT.as_ptr();
+    assert_eq!(
+        ::std::mem::size_of::<libpostal_address_parser_response>(),
+        24usize,
+        concat!("Size of: ", stringify!(libpostal_address_parser_response))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<libpostal_address_parser_response>(),
+        8usize,
+        concat!(
+            "Alignment of ",
+            stringify!(libpostal_address_parser_response)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).num_components) as usize - ptr as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_address_parser_response),
+            "::",
+            stringify!(num_components)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).components) as usize - ptr as usize },
+        8usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_address_parser_response),
+            "::",
+            stringify!(components)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).labels) as usize - ptr as usize },
+        16usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_address_parser_response),
+            "::",
+            stringify!(labels)
+        )
+    );
+}
+pub type libpostal_address_parser_response_t = libpostal_address_parser_response;
+pub type libpostal_parsed_address_components_t = libpostal_address_parser_response_t;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct libpostal_address_parser_options {
+    pub language: *mut ::std::os::raw::c_char,
+    pub country: *mut ::std::os::raw::c_char,
+}
+#[test]
+#[cfg(target_pointer_width = "64")]
+fn bindgen_test_layout_libpostal_address_parser_options() {
+    const UNINIT: ::std::mem::MaybeUninit<libpostal_address_parser_options> =
+        ::std::mem::MaybeUninit::uninit();
+    let ptr = UNINIT.as_ptr();
+    assert_eq!(
+        ::std::mem::size_of::<libpostal_address_parser_options>(),
+        16usize,
+        concat!("Size of: ", stringify!(libpostal_address_parser_options))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<libpostal_address_parser_options>(),
+        8usize,
+        concat!(
+            "Alignment of ",
+            stringify!(libpostal_address_parser_options)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).language) as usize - ptr as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_address_parser_options),
+            "::",
+            stringify!(language)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).country) as usize - ptr as usize },
+        8usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_address_parser_options),
+            "::",
+            stringify!(country)
+        )
+    );
+}
+pub type libpostal_address_parser_options_t = libpostal_address_parser_options;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct libpostal_language_classifier_response {
+    pub num_languages: usize,
+    pub languages: *mut *mut ::std::os::raw::c_char,
+    pub probs: *mut f64,
+}
+#[test]
+#[cfg(target_pointer_width = "64")]
+fn bindgen_test_layout_libpostal_language_classifier_response() {
+    const UNINIT: ::std::mem::MaybeUninit<libpostal_language_classifier_response> =
+        ::std::mem::MaybeUninit::uninit();
+    let ptr = UNINIT.as_ptr();
+    assert_eq!(
+        ::std::mem::size_of::<libpostal_language_classifier_response>(),
+        24usize,
+        concat!(
+            "Size of: ",
+            stringify!(libpostal_language_classifier_response)
+        )
+    );
+    assert_eq!(
+        ::std::mem::align_of::<libpostal_language_classifier_response>(),
+        8usize,
+        concat!(
+            "Alignment of ",
+            stringify!(libpostal_language_classifier_response)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).num_languages) as usize - ptr as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_language_classifier_response),
+            "::",
+            stringify!(num_languages)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).languages) as usize - ptr as usize },
+        8usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_language_classifier_response),
+            "::",
+            stringify!(languages)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).probs) as usize - ptr as usize },
+        16usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_language_classifier_response),
+            "::",
+            stringify!(probs)
+        )
+    );
+}
+pub type libpostal_language_classifier_response_t = libpostal_language_classifier_response;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct libpostal_near_dupe_hash_options {
+    pub with_name: bool,
+    pub with_address: bool,
+    pub with_unit: bool,
+    pub with_city_or_equivalent: bool,
+    pub with_small_containing_boundaries: bool,
+    pub with_postal_code: bool,
+    pub with_latlon: bool,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub geohash_precision: u32,
+    pub name_and_address_keys: bool,
+    pub name_only_keys: bool,
+    pub address_only_keys: bool,
+}
+#[test]
+#[cfg(target_pointer_width = "64")]
+fn bindgen_test_layout_libpostal_near_dupe_hash_options() {
+    const UNINIT: ::std::mem::MaybeUninit<libpostal_near_dupe_hash_options> =
+        ::std::mem::MaybeUninit::uninit();
+    let ptr = UNINIT.as_ptr();
+    assert_eq!(
+        ::std::mem::size_of::<libpostal_near_dupe_hash_options>(),
+        32usize,
+        concat!("Size of: ", stringify!(libpostal_near_dupe_hash_options))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<libpostal_near_dupe_hash_options>(),
+        8usize,
+        concat!(
+            "Alignment of ",
+            stringify!(libpostal_near_dupe_hash_options)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).with_name) as usize - ptr as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_near_dupe_hash_options),
+            "::",
+            stringify!(with_name)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).with_address) as usize - ptr as usize },
+        1usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_near_dupe_hash_options),
+            "::",
+            stringify!(with_address)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).with_unit) as usize - ptr as usize },
+        2usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_near_dupe_hash_options),
+            "::",
+            stringify!(with_unit)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).with_city_or_equivalent) as usize - ptr as usize },
+        3usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_near_dupe_hash_options),
+            "::",
+            stringify!(with_city_or_equivalent)
+        )
+    );
+    assert_eq!(
+        unsafe {
+            ::std::ptr::addr_of!((*ptr).with_small_containing_boundaries) as usize - ptr as usize
+        },
+        4usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_near_dupe_hash_options),
+            "::",
+            stringify!(with_small_containing_boundaries)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).with_postal_code) as usize - ptr as usize },
+        5usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_near_dupe_hash_options),
+            "::",
+            stringify!(with_postal_code)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).with_latlon) as usize - ptr as usize },
+        6usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_near_dupe_hash_options),
+            "::",
+            stringify!(with_latlon)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).latitude) as usize - ptr as usize },
+        8usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_near_dupe_hash_options),
+            "::",
+            stringify!(latitude)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).longitude) as usize - ptr as usize },
+        16usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_near_dupe_hash_options),
+            "::",
+            stringify!(longitude)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).geohash_precision) as usize - ptr as usize },
+        24usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_near_dupe_hash_options),
+            "::",
+            stringify!(geohash_precision)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).name_and_address_keys) as usize - ptr as usize },
+        28usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_near_dupe_hash_options),
+            "::",
+            stringify!(name_and_address_keys)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).name_only_keys) as usize - ptr as usize },
+        29usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_near_dupe_hash_options),
+            "::",
+            stringify!(name_only_keys)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).address_only_keys) as usize - ptr as usize },
+        30usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_near_dupe_hash_options),
+            "::",
+            stringify!(address_only_keys)
+        )
+    );
+}
+pub type libpostal_near_dupe_hash_options_t = libpostal_near_dupe_hash_options;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct libpostal_duplicate_options {
+    pub num_languages: usize,
+    pub languages: *mut *mut ::std::os::raw::c_char,
+}
+#[test]
+#[cfg(target_pointer_width = "64")]
+fn bindgen_test_layout_libpostal_duplicate_options() {
+    const UNINIT: ::std::mem::MaybeUninit<libpostal_duplicate_options> =
+        ::std::mem::MaybeUninit::uninit();
+    let ptr = UNINIT.as_ptr();
+    assert_eq!(
+        ::std::mem::size_of::<libpostal_duplicate_options>(),
+        16usize,
+        concat!("Size of: ", stringify!(libpostal_duplicate_options))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<libpostal_duplicate_options>(),
+        8usize,
+        concat!("Alignment of ", stringify!(libpostal_duplicate_options))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).num_languages) as usize - ptr as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_duplicate_options),
+            "::",
+            stringify!(num_languages)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).languages) as usize - ptr as usize },
+        8usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_duplicate_options),
+            "::",
+            stringify!(languages)
+        )
+    );
+}
+pub type libpostal_duplicate_options_t = libpostal_duplicate_options;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct libpostal_fuzzy_duplicate_options {
+    pub num_languages: usize,
+    pub languages: *mut *mut ::std::os::raw::c_char,
+    pub needs_review_threshold: f64,
+    pub likely_dupe_threshold: f64,
+}
+#[test]
+#[cfg(target_pointer_width = "64")]
+fn bindgen_test_layout_libpostal_fuzzy_duplicate_options() {
+    const UNINIT: ::std::mem::MaybeUninit<libpostal_fuzzy_duplicate_options> =
+        ::std::mem::MaybeUninit::uninit();
+    let ptr = UNINIT.as_ptr();
+    assert_eq!(
+        ::std::mem::size_of::<libpostal_fuzzy_duplicate_options>(),
+        32usize,
+        concat!("Size of: ", stringify!(libpostal_fuzzy_duplicate_options))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<libpostal_fuzzy_duplicate_options>(),
+        8usize,
+        concat!(
+            "Alignment of ",
+            stringify!(libpostal_fuzzy_duplicate_options)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).num_languages) as usize - ptr as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_fuzzy_duplicate_options),
+            "::",
+            stringify!(num_languages)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).languages) as usize - ptr as usize },
+        8usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_fuzzy_duplicate_options),
+            "::",
+            stringify!(languages)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).needs_review_threshold) as usize - ptr as usize },
+        16usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_fuzzy_duplicate_options),
+            "::",
+            stringify!(needs_review_threshold)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).likely_dupe_threshold) as usize - ptr as usize },
+        24usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_fuzzy_duplicate_options),
+            "::",
+            stringify!(likely_dupe_threshold)
+        )
+    );
+}
+pub type libpostal_fuzzy_duplicate_options_t = libpostal_fuzzy_duplicate_options;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct libpostal_fuzzy_duplicate_status {
+    pub status: libpostal_duplicate_status_t,
+    pub similarity: f64,
+}
+#[test]
+#[cfg(target_pointer_width = "64")]
+fn bindgen_test_layout_libpostal_fuzzy_duplicate_status() {
+    const UNINIT: ::std::mem::MaybeUninit<libpostal_fuzzy_duplicate_status> =
+        ::std::mem::MaybeUninit::uninit();
+    let ptr = UNINIT.as_ptr();
+    assert_eq!(
+        ::std::mem::size_of::<libpostal_fuzzy_duplicate_status>(),
+        16usize,
+        concat!("Size of: ", stringify!(libpostal_fuzzy_duplicate_status))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<libpostal_fuzzy_duplicate_status>(),
+        8usize,
+        concat!(
+            "Alignment of ",
+            stringify!(libpostal_fuzzy_duplicate_status)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).status) as usize - ptr as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_fuzzy_duplicate_status),
+            "::",
+            stringify!(status)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).similarity) as usize - ptr as usize },
+        8usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_fuzzy_duplicate_status),
+            "::",
+            stringify!(similarity)
+        )
+    );
+}
+pub type libpostal_fuzzy_duplicate_status_t = libpostal_fuzzy_duplicate_status;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct libpostal_token {
+    pub offset: usize,
+    pub len: usize,
+    pub type_: u16,
+}
+#[test]
+#[cfg(target_pointer_width = "64")]
+fn bindgen_test_layout_libpostal_token() {
+    const UNINIT: ::std::mem::MaybeUninit<libpostal_token> = ::std::mem::MaybeUninit::uninit();
+    let ptr = UNINIT.as_ptr();
+    assert_eq!(
+        ::std::mem::size_of::<libpostal_token>(),
+        24usize,
+        concat!("Size of: ", stringify!(libpostal_token))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<libpostal_token>(),
+        8usize,
+        concat!("Alignment of ", stringify!(libpostal_token))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).offset) as usize - ptr as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_token),
+            "::",
+            stringify!(offset)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).len) as usize - ptr as usize },
+        8usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_token),
+            "::",
+            stringify!(len)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).type_) as usize - ptr as usize },
+        16usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_token),
+            "::",
+            stringify!(type_)
+        )
+    );
+}
+pub type libpostal_token_t = libpostal_token;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct libpostal_normalized_token {
+    pub str: *mut ::std::os::raw::c_char,
+    pub token: libpostal_token_t,
+}
+#[test]
+#[cfg(target_pointer_width = "64")]
+fn bindgen_test_layout_libpostal_normalized_token() {
+    const UNINIT: ::std::mem::MaybeUninit<libpostal_normalized_token> =
+        ::std::mem::MaybeUninit::uninit();
+    let ptr = UNINIT.as_ptr();
+    assert_eq!(
+        ::std::mem::size_of::<libpostal_normalized_token>(),
+        32usize,
+        concat!("Size of: ", stringify!(libpostal_normalized_token))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<libpostal_normalized_token>(),
+        8usize,
+        concat!("Alignment of ", stringify!(libpostal_normalized_token))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).str) as usize - ptr as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_normalized_token),
+            "::",
+            stringify!(str)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).token) as usize - ptr as usize },
+        8usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(libpostal_normalized_token),
+            "::",
+            stringify!(token)
+        )
+    );
+}
+pub type libpostal_normalized_token_t = libpostal_normalized_token;
+extern "C" {
+    pub fn libpostal_get_default_options() -> libpostal_normalize_options_t;
+    pub fn libpostal_expand_address(
+        input: *mut ::std::os::raw::c_char,
+        options: libpostal_normalize_options_t,
+        n: *mut usize,
+    ) -> *mut *mut ::std::os::raw::c_char;
+    pub fn libpostal_expand_address_root(
+        input: *mut ::std::os::raw::c_char,
+        options: libpostal_normalize_options_t,
+        n: *mut usize,
+    ) -> *mut *mut ::std::os::raw::c_char;
+    pub fn libpostal_expansion_array_destroy(
+        expansions: *mut *mut ::std::os::raw::c_char,
+        n: usize,
+    );
+    pub fn libpostal_address_parser_response_destroy(
+        self_: *mut libpostal_address_parser_response_t,
+    );
+    pub fn libpostal_get_address_parser_default_options() -> libpostal_address_parser_options_t;
+    pub fn libpostal_parse_address(
+        address: *mut ::std::os::raw::c_char,
+        options: libpostal_address_parser_options_t,
+    ) -> *mut libpostal_address_parser_response_t;
+    pub fn libpostal_parser_print_features(print_features: bool) -> bool;
+    pub fn libpostal_classify_language(
+        address: *mut ::std::os::raw::c_char,
+    ) -> *mut libpostal_language_classifier_response_t;
+    pub fn libpostal_language_classifier_response_destroy(
+        self_: *mut libpostal_language_classifier_response_t,
+    );
+    pub fn libpostal_get_near_dupe_hash_default_options() -> libpostal_near_dupe_hash_options_t;
+    pub fn libpostal_near_dupe_hashes(
+        num_components: usize,
+        labels: *mut *mut ::std::os::raw::c_char,
+        values: *mut *mut ::std::os::raw::c_char,
+        options: libpostal_near_dupe_hash_options_t,
+        num_hashes: *mut usize,
+    ) -> *mut *mut ::std::os::raw::c_char;
+    pub fn libpostal_near_dupe_hashes_languages(
+        num_components: usize,
+        labels: *mut *mut ::std::os::raw::c_char,
+        values: *mut *mut ::std::os::raw::c_char,
+        options: libpostal_near_dupe_hash_options_t,
+        num_languages: usize,
+        languages: *mut *mut ::std::os::raw::c_char,
+        num_hashes: *mut usize,
+    ) -> *mut *mut ::std::os::raw::c_char;
+    pub fn libpostal_place_languages(
+        num_components: usize,
+        labels: *mut *mut ::std::os::raw::c_char,
+        values: *mut *mut ::std::os::raw::c_char,
+        num_languages: *mut usize,
+    ) -> *mut *mut ::std::os::raw::c_char;
+    pub fn libpostal_get_default_duplicate_options() -> libpostal_duplicate_options_t;
+    pub fn libpostal_get_duplicate_options_with_languages(
+        num_languages: usize,
+        languages: *mut *mut ::std::os::raw::c_char,
+    ) -> libpostal_duplicate_options_t;
+    pub fn libpostal_is_name_duplicate(
+        value1: *mut ::std::os::raw::c_char,
+        value2: *mut ::std::os::raw::c_char,
+        options: libpostal_duplicate_options_t,
+    ) -> libpostal_duplicate_status_t;
+    pub fn libpostal_is_street_duplicate(
+        value1: *mut ::std::os::raw::c_char,
+        value2: *mut ::std::os::raw::c_char,
+        options: libpostal_duplicate_options_t,
+    ) -> libpostal_duplicate_status_t;
+    pub fn libpostal_is_house_number_duplicate(
+        value1: *mut ::std::os::raw::c_char,
+        value2: *mut ::std::os::raw::c_char,
+        options: libpostal_duplicate_options_t,
+    ) -> libpostal_duplicate_status_t;
+    pub fn libpostal_is_po_box_duplicate(
+        value1: *mut ::std::os::raw::c_char,
+        value2: *mut ::std::os::raw::c_char,
+        options: libpostal_duplicate_options_t,
+    ) -> libpostal_duplicate_status_t;
+    pub fn libpostal_is_unit_duplicate(
+        value1: *mut ::std::os::raw::c_char,
+        value2: *mut ::std::os::raw::c_char,
+        options: libpostal_duplicate_options_t,
+    ) -> libpostal_duplicate_status_t;
+    pub fn libpostal_is_floor_duplicate(
+        value1: *mut ::std::os::raw::c_char,
+        value2: *mut ::std::os::raw::c_char,
+        options: libpostal_duplicate_options_t,
+    ) -> libpostal_duplicate_status_t;
+    pub fn libpostal_is_postal_code_duplicate(
+        value1: *mut ::std::os::raw::c_char,
+        value2: *mut ::std::os::raw::c_char,
+        options: libpostal_duplicate_options_t,
+    ) -> libpostal_duplicate_status_t;
+    pub fn libpostal_is_toponym_duplicate(
+        num_components1: usize,
+        labels1: *mut *mut ::std::os::raw::c_char,
+        values1: *mut *mut ::std::os::raw::c_char,
+        num_components2: usize,
+        labels2: *mut *mut ::std::os::raw::c_char,
+        values2: *mut *mut ::std::os::raw::c_char,
+        options: libpostal_duplicate_options_t,
+    ) -> libpostal_duplicate_status_t;
+    pub fn libpostal_get_default_fuzzy_duplicate_options() -> libpostal_fuzzy_duplicate_options_t;
+    pub fn libpostal_get_default_fuzzy_duplicate_options_with_languages(
+        num_languages: usize,
+        languages: *mut *mut ::std::os::raw::c_char,
+    ) -> libpostal_fuzzy_duplicate_options_t;
+    pub fn libpostal_is_name_duplicate_fuzzy(
+        num_tokens1: usize,
+        tokens1: *mut *mut ::std::os::raw::c_char,
+        token_scores1: *mut f64,
+        num_tokens2: usize,
+        tokens2: *mut *mut ::std::os::raw::c_char,
+        token_scores2: *mut f64,
+        options: libpostal_fuzzy_duplicate_options_t,
+    ) -> libpostal_fuzzy_duplicate_status_t;
+    pub fn libpostal_is_street_duplicate_fuzzy(
+        num_tokens1: usize,
+        tokens1: *mut *mut ::std::os::raw::c_char,
+        token_scores1: *mut f64,
+        num_tokens2: usize,
+        tokens2: *mut *mut ::std::os::raw::c_char,
+        token_scores2: *mut f64,
+        options: libpostal_fuzzy_duplicate_options_t,
+    ) -> libpostal_fuzzy_duplicate_status_t;
+    pub fn libpostal_setup() -> bool;
+    pub fn libpostal_setup_datadir(datadir: *mut ::std::os::raw::c_char) -> bool;
+    pub fn libpostal_teardown();
+    pub fn libpostal_setup_parser() -> bool;
+    pub fn libpostal_setup_parser_datadir(datadir: *mut ::std::os::raw::c_char) -> bool;
+    pub fn libpostal_teardown_parser();
+    pub fn libpostal_setup_language_classifier() -> bool;
+    pub fn libpostal_setup_language_classifier_datadir(
+        datadir: *mut ::std::os::raw::c_char,
+    ) -> bool;
+    pub fn libpostal_teardown_language_classifier();
+    pub fn libpostal_tokenize(
+        input: *mut ::std::os::raw::c_char,
+        whitespace: bool,
+        n: *mut usize,
+    ) -> *mut libpostal_token_t;
+    pub fn libpostal_normalize_string_languages(
+        input: *mut ::std::os::raw::c_char,
+        options: u64,
+        num_languages: usize,
+        languages: *mut *mut ::std::os::raw::c_char,
+    ) -> *mut ::std::os::raw::c_char;
+    pub fn libpostal_normalize_string(
+        input: *mut ::std::os::raw::c_char,
+        options: u64,
+    ) -> *mut ::std::os::raw::c_char;
+    pub fn libpostal_normalized_tokens(
+        input: *mut ::std::os::raw::c_char,
+        string_options: u64,
+        token_options: u64,
+        whitespace: bool,
+        n: *mut usize,
+    ) -> *mut libpostal_normalized_token_t;
+    pub fn libpostal_normalized_tokens_languages(
+        input: *mut ::std::os::raw::c_char,
+        string_options: u64,
+        token_options: u64,
+        whitespace: bool,
+        num_languages: usize,
+        languages: *mut *mut ::std::os::raw::c_char,
+        n: *mut usize,
+    ) -> *mut libpostal_normalized_token_t;
+}
@@ -0,0 +1,760 @@
+//! Offline forward geocoding against a local gazetteer.
+//!
+//! Gazetteer entries are indexed by the canonical variation of their
+//! components, see [`NormalizedAddress::canonical`], and queries are looked
+//! up by all their variations, see [`NormalizeOptions::expand`], so that
+//! "Main St" in an address matches "Main Street" in the gazetteer. Each
+//! entry is indexed at its own [`Precision`] and at every coarser one it has
+//! the components for, e.g. an address point also locates its street and
+//! postcode.
+//!
+//! A query with a context, e.g. a city, only matches places in that
+//! context. Without one, it only matches a single place.
+//!
+//! Gazetteers are loaded from:
+//!
+//! * [GeoNames](https://www.geonames.org) dumps, e.g. `allCountries.txt`,
+//!   with [`Geocoder::load_geonames`];
+//! * delimited files with a header, e.g. [OpenAddresses](https://openaddresses.io)
+//!   CSV or TSV exported from OpenStreetMap, with [`Geocoder::load_delimited`].
+//!
+//! # Examples
+//!
+//! ```
+//! use rustpostal::error::RuntimeError;
+//! use rustpostal::geocode::{GazetteerEntry, Geocoder, Precision};
+//! use rustpostal::LibModules;
+//!
+//! fn main() -> Result<(), RuntimeError> {
+//!     let postal_module = LibModules::All;
+//!     postal_module.setup()?;
+//!
+//!     let mut geocoder = Geocoder::new(Some(["en"].iter()))?;
+//!     geocoder.insert(&GazetteerEntry {
+//!         house_number: Some(String::from("10")),
+//!         road: Some(String::from("Downing Street")),
+//!         city: Some(String::from("London")),
+//!         ..GazetteerEntry::new(51.5034, -0.1276)
+//!     })?;
+//!
+//!     let location = geocoder.geocode("10 Downing St, London")?.unwrap();
+//!     assert_eq!(location.precision, Precision::Rooftop);
+//!     Ok(())
+//! }
+//! ```
+use std::collections::{BTreeSet, HashMap};
+use std::ffi::NulError;
+use std::io::{self, Read};
+
+use csv::{ReaderBuilder, StringRecord};
+
+use crate::address::{AddressParserOptions, ParsedAddress};
+use crate::country;
+use crate::expand::NormalizeOptions;
+#[cfg(doc)]
+use crate::expand::NormalizedAddress;
+use crate::models::{self, Models};
+
+/// Precision of a location, from the coarsest to the finest.
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Precision {
+    Country,
+    City,
+    Postcode,
+    Street,
+    /// A single address.
+    Rooftop,
+}
+
+/// A geocoded location.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Location {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub precision: Precision,
+}
+
+/// A place of the gazetteer.
+///
+/// Its precision is that of its finest component, e.g. `Rooftop` for a
+/// house number on a road.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GazetteerEntry {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub house_number: Option<String>,
+    pub road: Option<String>,
+    pub postcode: Option<String>,
+    pub city: Option<String>,
+    pub state: Option<String>,
+    pub country: Option<String>,
+}
+
+impl GazetteerEntry {
+    /// Create an entry at the coordinates, without any component.
+    pub fn new(latitude: f64, longitude: f64) -> GazetteerEntry {
+        GazetteerEntry {
+            latitude,
+            longitude,
+            ..Default::default()
+        }
+    }
+
+    /// Return the precision of the entry, if it has any component.
+    pub fn precision(&self) -> Option<Precision> {
+        LEVELS
+            .iter()
+            .find(|level| level.primary.iter().all(|&c| self.get(c).is_some()))
+            .map(|level| level.precision)
+    }
+
+    fn get(&self, component: Component) -> Option<&str> {
+        let value = match component {
+            Component::HouseNumber => &self.house_number,
+            Component::Road => &self.road,
+            Component::Postcode => &self.postcode,
+            Component::City => &self.city,
+            Component::State => &self.state,
+            Component::Country => &self.country,
+        };
+        value
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    }
+}
+
+impl From<&ParsedAddress> for GazetteerEntry {
+    /// The components of a parsed address, at no particular coordinates.
+    fn from(parsed: &ParsedAddress) -> Self {
+        GazetteerEntry {
+            house_number: parsed.house_number(),
+            road: parsed.road(),
+            postcode: parsed.postcode(),
+            city: parsed.city(),
+            state: parsed.state(),
+            country: parsed.country(),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Component {
+    HouseNumber,
+    Road,
+    Postcode,
+    City,
+    State,
+    Country,
+}
+
+impl Component {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Component::HouseNumber => "house_number",
+            Component::Road => "road",
+            Component::Postcode => "postcode",
+            Component::City => "city",
+            Component::State => "state",
+            Component::Country => "country",
+        }
+    }
+}
+
+/// The components locating a place at a precision.
+struct Level {
+    precision: Precision,
+    /// Components all required at this precision.
+    primary: &'static [Component],
+    /// Components narrowing down the matches, tried in order.
+    context: &'static [Component],
+}
+
+/// The levels, from the finest to the coarsest.
+const LEVELS: &[Level] = &[
+    Level {
+        precision: Precision::Rooftop,
+        primary: &[Component::HouseNumber, Component::Road],
+        context: &[Component::Postcode, Component::City],
+    },
+    Level {
+        precision: Precision::Street,
+        primary: &[Component::Road],
+        context: &[Component::Postcode, Component::City],
+    },
+    Level {
+        precision: Precision::Postcode,
+        primary: &[Component::Postcode],
+        context: &[Component::Country],
+    },
+    Level {
+        precision: Precision::City,
+        primary: &[Component::City],
+        context: &[Component::State, Component::Country],
+    },
+    Level {
+        precision: Precision::Country,
+        primary: &[Component::Country],
+        context: &[],
+    },
+];
+
+/// Variations kept per component of a query, bounding its keys.
+const MAX_VARIATIONS: usize = 8;
+
+/// The ISO 3166-1 alpha-2 code of a country, so that names and codes match,
+/// e.g. "United Kingdom" and "GB".
+fn country_code(value: &str) -> Option<String> {
+    country::find(value).map(|country| country.alpha2.to_lowercase())
+}
+
+/// Separator of the parts of a key.
+const KEY_SEPARATOR: char = '\u{1f}';
+
+/// An indexed place.
+#[derive(Clone, Copy, Debug)]
+struct Point {
+    latitude: f64,
+    longitude: f64,
+    precision: Precision,
+}
+
+/// Index of gazetteer places by their normalized components.
+#[derive(Clone, Debug)]
+pub struct Geocoder {
+    options: NormalizeOptions,
+    points: Vec<Point>,
+    keys: HashMap<String, Vec<u32>>,
+}
+
+impl Geocoder {
+    /// Create an empty geocoder.
+    ///
+    /// `languages` are used for normalization; if not given, they are
+    /// classified from each component.
+    pub fn new<'a, 'b, T>(languages: Option<T>) -> Result<Geocoder, NulError>
+    where
+        'a: 'b,
+        T: Iterator<Item = &'b &'a str>,
+    {
+        Ok(Geocoder {
+            options: NormalizeOptions::new(languages)?,
+            points: Vec::new(),
+            keys: HashMap::new(),
+        })
+    }
+
+    /// Return the number of entries.
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Return `true` if there are no entries.
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// The variations of a component of a query, the most expanded first,
+    /// or the lowercase value without the models for expansion.
+    fn variations(&self, value: &str) -> Result<Vec<String>, NulError> {
+        let mut variations = Vec::new();
+        if models::loaded().contains(Models::BASE | Models::CLASSIFIER) {
+            let normalized = self.options.clone().expand(value)?;
+            variations.extend(normalized.ranked().into_iter().cloned());
+        }
+        if variations.is_empty() {
+            variations.push(value.to_lowercase());
+        }
+        variations.truncate(MAX_VARIATIONS);
+        Ok(variations)
+    }
+
+    /// The canonical variation of a component of an entry, or the lowercase
+    /// value without the models for expansion.
+    fn canonical(&self, value: &str) -> Result<String, NulError> {
+        if models::loaded().contains(Models::BASE | Models::CLASSIFIER) {
+            if let Some(canonical) = self.options.clone().expand(value)?.canonical() {
+                return Ok(String::from(canonical));
+            }
+        }
+        Ok(value.to_lowercase())
+    }
+
+    /// The keys of `entry` at `level` with the `context` component, if it
+    /// has the components, with the given variations of each component.
+    fn level_keys<F>(
+        entry: &GazetteerEntry,
+        level: &Level,
+        context: Option<Component>,
+        mut variations: F,
+    ) -> Result<Option<Vec<String>>, NulError>
+    where
+        F: FnMut(&str) -> Result<Vec<String>, NulError>,
+    {
+        let components = level.primary.iter().copied().chain(context);
+        let mut keys = vec![format!("{:?}", level.precision)];
+        for component in components {
+            let value = match entry.get(component) {
+                Some(value) => value,
+                None => return Ok(None),
+            };
+            let variations = match (component, country_code(value)) {
+                (Component::Country, Some(code)) => vec![code],
+                _ => variations(value)?,
+            };
+            keys = keys
+                .iter()
+                .flat_map(|key| {
+                    variations.iter().map(move |variation| {
+                        format!(
+                            "{}{}{}={}",
+                            key,
+                            KEY_SEPARATOR,
+                            component.as_str(),
+                            variation
+                        )
+                    })
+                })
+                .collect();
+        }
+        Ok(Some(keys))
+    }
+
+    /// Index a place of the gazetteer.
+    ///
+    /// The entry is indexed under one key per level and context, made of
+    /// the canonical variations of its components. Entries without any
+    /// component are ignored.
+    ///
+    /// # Errors
+    ///
+    /// It will return an error if a component contains an internal null byte.
+    pub fn insert(&mut self, entry: &GazetteerEntry) -> Result<(), NulError> {
+        let precision = match entry.precision() {
+            Some(precision) => precision,
+            None => return Ok(()),
+        };
+        let id = self.points.len() as u32;
+        let mut canonicals: HashMap<String, String> = HashMap::new();
+        let mut keys = BTreeSet::new();
+        for level in LEVELS.iter().filter(|level| level.precision <= precision) {
+            let contexts = level.context.iter().copied().map(Some).chain([None]);
+            for context in contexts {
+                let level_keys = Geocoder::level_keys(entry, level, context, |value| {
+                    if let Some(canonical) = canonicals.get(value) {
+                        return Ok(vec![canonical.clone()]);
+                    }
+                    let canonical = self.canonical(value)?;
+                    canonicals.insert(value.to_string(), canonical.clone());
+                    Ok(vec![canonical])
+                })?;
+                keys.extend(level_keys.into_iter().flatten());
+            }
+        }
+        for key in keys {
+            self.keys.entry(key).or_default().push(id);
+        }
+        self.points.push(Point {
+            latitude: entry.latitude,
+            longitude: entry.longitude,
+            precision,
+        });
+        Ok(())
+    }
+
+    /// The entries matching `query` at `level` with the `context`
+    /// component, if the query has the components.
+    fn lookup(
+        &self,
+        query: &GazetteerEntry,
+        level: &Level,
+        context: Option<Component>,
+    ) -> Result<Option<BTreeSet<u32>>, NulError> {
+        let keys = Geocoder::level_keys(query, level, context, |value| self.variations(value))?;
+        Ok(keys.map(|keys| {
+            keys.iter()
+                .filter_map(|key| self.keys.get(key))
+                .flatten()
+                .copied()
+                .collect()
+        }))
+    }
+
+    /// Locate the matches of a level.
+    ///
+    /// Places of the level itself are ambiguous and the first one is
+    /// returned, while finer places are averaged, e.g. the address points
+    /// of a street.
+    fn locate(&self, precision: Precision, ids: &BTreeSet<u32>) -> Option<Location> {
+        let points: Vec<&Point> = ids.iter().map(|&id| &self.points[id as usize]).collect();
+        if let Some(point) = points.iter().find(|point| point.precision == precision) {
+            return Some(Location {
+                latitude: point.latitude,
+                longitude: point.longitude,
+                precision,
+            });
+        }
+        if points.is_empty() {
+            return None;
+        }
+        let n = points.len() as f64;
+        Some(Location {
+            latitude: points.iter().map(|point| point.latitude).sum::<f64>() / n,
+            longitude: points.iter().map(|point| point.longitude).sum::<f64>() / n,
+            precision,
+        })
+    }
+
+    /// Locate the matches of a level found without their context, which
+    /// may be in different contexts, e.g. the streets of the same name in
+    /// different cities.
+    ///
+    /// Returns a location only if there is a single place of the level, or
+    /// else a single match.
+    fn locate_unique(&self, precision: Precision, ids: &BTreeSet<u32>) -> Option<Location> {
+        let mut places = ids
+            .iter()
+            .map(|&id| &self.points[id as usize])
+            .filter(|point| point.precision == precision);
+        let point = match (places.next(), places.next()) {
+            (Some(place), None) => place,
+            (None, _) if ids.len() == 1 => &self.points[*ids.iter().next()? as usize],
+            _ => return None,
+        };
+        Some(Location {
+            latitude: point.latitude,
+            longitude: point.longitude,
+            precision,
+        })
+    }
+
+    /// Resolve the components of an address to the finest location found.
+    ///
+    /// # Errors
+    ///
+    /// It will return an error if a component contains an internal null byte.
+    pub fn resolve(&self, parsed: &ParsedAddress) -> Result<Option<Location>, NulError> {
+        self.resolve_entry(&GazetteerEntry::from(parsed))
+    }
+
+    fn resolve_entry(&self, query: &GazetteerEntry) -> Result<Option<Location>, NulError> {
+        for level in LEVELS {
+            let contexts: Vec<Component> = level
+                .context
+                .iter()
+                .copied()
+                .filter(|&context| query.get(context).is_some())
+                .collect();
+            // A context of the query that does not match rules the level
+            // out, rather than matching the places of any context.
+            if !contexts.is_empty() {
+                for &context in &contexts {
+                    let ids = self.lookup(query, level, Some(context))?;
+                    if let Some(location) = ids.and_then(|ids| self.locate(level.precision, &ids)) {
+                        return Ok(Some(location));
+                    }
+                }
+                continue;
+            }
+            let ids = match self.lookup(query, level, None)? {
+                Some(ids) => ids,
+                None => continue,
+            };
+            let location = if level.context.is_empty() {
+                self.locate(level.precision, &ids)
+            } else {
+                self.locate_unique(level.precision, &ids)
+            };
+            if location.is_some() {
+                return Ok(location);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Parse an address with the default options and resolve it.
+    ///
+    /// # Errors
+    ///
+    /// It will return an error if the address contains an internal null byte.
+    pub fn geocode(&self, address: &str) -> Result<Option<Location>, NulError> {
+        let parsed = ParsedAddress::from(AddressParserOptions::new(None, None)?.parse(address)?);
+        self.resolve(&parsed)
+    }
+
+    /// Load the populated places and countries of a GeoNames dump.
+    ///
+    /// Places are located at the city precision in their country code, and
+    /// countries by their name; both are matched by the ISO 3166-1 code of
+    /// the country. Other features are skipped. Returns the
+    /// number of loaded entries.
+    ///
+    /// # Errors
+    ///
+    /// It will return an error if reading fails or a line is malformed.
+    pub fn load_geonames<R: Read>(&mut self, reader: R) -> io::Result<usize> {
+        let mut reader = ReaderBuilder::new()
+            .delimiter(b'\t')
+            .has_headers(false)
+            .flexible(true)
+            .quoting(false)
+            .from_reader(reader);
+        let mut loaded = 0;
+        for record in reader.records() {
+            let record = record?;
+            let field = |index: usize| record.get(index).unwrap_or_default();
+            let mut entry = GazetteerEntry::new(
+                parse_coordinate(&record, field(4), 90.0)?,
+                parse_coordinate(&record, field(5), 180.0)?,
+            );
+            match (field(6), field(7)) {
+                ("P", _) => {
+                    entry.city = Some(field(1).to_string());
+                    entry.country = Some(field(8).to_string());
+                }
+                ("A", code) if code.starts_with("PCL") => {
+                    entry.country = Some(field(1).to_string());
+                }
+                _ => continue,
+            }
+            self.insert(&entry).map_err(invalid_data)?;
+            loaded += 1;
+        }
+        Ok(loaded)
+    }
+
+    /// Load a delimited file with a header row.
+    ///
+    /// The coordinates are read from the `lat`/`latitude` and
+    /// `lon`/`lng`/`longitude` columns, and the components from the
+    /// `house_number`/`number`, `road`/`street`, `postcode`/`zip`, `city`,
+    /// `state`/`region` and `country` columns, ignoring case. Returns the
+    /// number of loaded entries.
+    ///
+    /// # Errors
+    ///
+    /// It will return an error if reading fails, the coordinate columns are
+    /// missing, a coordinate is out of range or a row is malformed.
+    pub fn load_delimited<R: Read>(&mut self, reader: R, delimiter: u8) -> io::Result<usize> {
+        read_delimited(reader, delimiter, |entry| {
+            self.insert(&entry).map_err(invalid_data)
+        })
+    }
+}
+
+/// Read the entries of a delimited file, see [`Geocoder::load_delimited`].
+///
+/// Rows without any component are skipped. Returns the number of entries.
+pub(crate) fn read_delimited<R, F>(reader: R, delimiter: u8, mut f: F) -> io::Result<usize>
+where
+    R: Read,
+    F: FnMut(GazetteerEntry) -> io::Result<()>,
+{
+    let mut reader = ReaderBuilder::new()
+        .delimiter(delimiter)
+        .flexible(true)
+        .from_reader(reader);
+    let headers = reader.headers()?.clone();
+    let column = |names: &[&str]| {
+        headers
+            .iter()
+            .position(|header| names.contains(&header.trim().to_lowercase().as_str()))
+    };
+    let (latitude, longitude) = match (
+        column(&["lat", "latitude"]),
+        column(&["lon", "lng", "longitude"]),
+    ) {
+        (Some(latitude), Some(longitude)) => (latitude, longitude),
+        _ => return Err(invalid_data("missing latitude or longitude column")),
+    };
+    let components = [
+        column(&["house_number", "housenumber", "number"]),
+        column(&["road", "street"]),
+        column(&["postcode", "postal_code", "zip"]),
+        column(&["city"]),
+        column(&["state", "region"]),
+        column(&["country"]),
+    ];
+    let mut loaded = 0;
+    for record in reader.records() {
+        let record = record?;
+        let value = |index: Option<usize>| {
+            index
+                .and_then(|index| record.get(index))
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(String::from)
+        };
+        let entry = GazetteerEntry {
+            latitude: parse_coordinate(&record, record.get(latitude).unwrap_or_default(), 90.0)?,
+            longitude: parse_coordinate(&record, record.get(longitude).unwrap_or_default(), 180.0)?,
+            house_number: value(components[0]),
+            road: value(components[1]),
+            postcode: value(components[2]),
+            city: value(components[3]),
+            state: value(components[4]),
+            country: value(components[5]),
+        };
+        if entry.precision().is_some() {
+            f(entry)?;
+            loaded += 1;
+        }
+    }
+    Ok(loaded)
+}
+
+pub(crate) fn invalid_data<E>(err: E) -> io::Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
+
+/// Parse a coordinate within `-bound..=bound`, 90 for a latitude and 180
+/// for a longitude.
+fn parse_coordinate(record: &StringRecord, value: &str, bound: f64) -> io::Result<f64> {
+    match value.trim().parse::<f64>() {
+        Ok(coordinate) if coordinate.is_finite() && coordinate.abs() <= bound => Ok(coordinate),
+        _ => {
+            let line = record.position().map_or(0, |position| position.line());
+            Err(invalid_data(format!(
+                "invalid coordinate `{}` on line {}",
+                value, line
+            )))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entry_precision() {
+        let mut entry = GazetteerEntry::new(0.0, 0.0);
+        assert_eq!(entry.precision(), None);
+        entry.country = Some(String::from("gb"));
+        assert_eq!(entry.precision(), Some(Precision::Country));
+        entry.house_number = Some(String::from("10"));
+        entry.city = Some(String::from(" "));
+        assert_eq!(entry.precision(), Some(Precision::Country));
+        entry.road = Some(String::from("downing street"));
+        assert_eq!(entry.precision(), Some(Precision::Rooftop));
+    }
+
+    #[test]
+    fn country_codes_of_names_and_codes() {
+        assert_eq!(country_code("United Kingdom").as_deref(), Some("gb"));
+        assert_eq!(country_code("GB"), country_code("united kingdom"));
+        assert_eq!(country_code("Atlantis"), None);
+    }
+
+    #[test]
+    fn locate_prefers_places_of_the_level() {
+        let mut geocoder = Geocoder::new(Some(["en"].iter())).unwrap();
+        geocoder.points = vec![
+            Point {
+                latitude: 1.0,
+                longitude: 1.0,
+                precision: Precision::Rooftop,
+            },
+            Point {
+                latitude: 3.0,
+                longitude: 3.0,
+                precision: Precision::Rooftop,
+            },
+            Point {
+                latitude: 5.0,
+                longitude: 5.0,
+                precision: Precision::Street,
+            },
+        ];
+        let street = geocoder.locate(Precision::Street, &[0, 1, 2].iter().copied().collect());
+        assert_eq!(street.map(|l| l.latitude), Some(5.0));
+        let street = geocoder.locate(Precision::Street, &[0, 1].iter().copied().collect());
+        assert_eq!(street.map(|l| l.latitude), Some(2.0));
+        assert_eq!(geocoder.locate(Precision::City, &BTreeSet::new()), None);
+    }
+
+    fn address(house_number: &str, road: &str, city: &str, latitude: f64) -> GazetteerEntry {
+        GazetteerEntry {
+            house_number: Some(String::from(house_number)).filter(|n| !n.is_empty()),
+            road: Some(String::from(road)),
+            city: Some(String::from(city)).filter(|c| !c.is_empty()),
+            ..GazetteerEntry::new(latitude, 0.0)
+        }
+    }
+
+    #[test]
+    fn insert_one_key_per_level_and_context() {
+        let mut geocoder = Geocoder::new(Some(["en"].iter())).unwrap();
+        geocoder
+            .insert(&GazetteerEntry {
+                postcode: Some(String::from("SW1A 2AA")),
+                country: Some(String::from("GB")),
+                ..address("10", "Downing Street", "London", 51.5)
+            })
+            .unwrap();
+        // Rooftop and street with postcode, city and no context, postcode
+        // and city with country and no context, and country.
+        assert_eq!(geocoder.keys.len(), 11);
+    }
+
+    #[test]
+    fn resolve_within_the_context_only() {
+        let mut geocoder = Geocoder::new(Some(["en"].iter())).unwrap();
+        geocoder
+            .insert(&address("10", "Main Street", "Shelbyville", 1.0))
+            .unwrap();
+        geocoder
+            .insert(&address("12", "Main Street", "Shelbyville", 3.0))
+            .unwrap();
+        geocoder
+            .insert(&address("10", "Main Street", "Ogdenville", 5.0))
+            .unwrap();
+
+        let resolve = |query: &GazetteerEntry| geocoder.resolve_entry(query).unwrap();
+        let location = resolve(&address("10", "Main Street", "Shelbyville", 0.0)).unwrap();
+        assert_eq!(
+            (location.latitude, location.precision),
+            (1.0, Precision::Rooftop)
+        );
+        let location = resolve(&address("", "Main Street", "Shelbyville", 0.0)).unwrap();
+        assert_eq!(
+            (location.latitude, location.precision),
+            (2.0, Precision::Street)
+        );
+        // The streets of other cities are not matched, nor averaged.
+        assert_eq!(
+            resolve(&address("10", "Main Street", "Springfield", 0.0)),
+            None
+        );
+        assert_eq!(resolve(&address("", "Main Street", "", 0.0)), None);
+        assert_eq!(resolve(&address("10", "Main Street", "", 0.0)), None);
+        let location = resolve(&address("12", "Main Street", "", 0.0)).unwrap();
+        assert_eq!(
+            (location.latitude, location.precision),
+            (3.0, Precision::Rooftop)
+        );
+    }
+
+    #[test]
+    fn load_delimited_requires_coordinates() {
+        let mut geocoder = Geocoder::new(Some(["en"].iter())).unwrap();
+        let data = "NUMBER,STREET\n10,Downing Street\n";
+        assert!(geocoder.load_delimited(data.as_bytes(), b',').is_err());
+        let data = "LON,LAT,CITY\nnot a number,51.5,London\n";
+        assert!(geocoder.load_delimited(data.as_bytes(), b',').is_err());
+        for coordinates in ["0,200", "200,0", "nan,0", "0,inf"].iter() {
+            let data = format!("LON,LAT,CITY\n{},London\n", coordinates);
+            assert!(geocoder.load_delimited(data.as_bytes(), b',').is_err());
+        }
+    }
+}
@@ -0,0 +1,345 @@
+//! Structured house numbers: ranges, fractions, suffixes and "no number".
+//!
+//! The parser returns house numbers as text, e.g. "660", "12-14", "4998a",
+//! "12 1/2" or "s/n". A [`HouseNumber`] represents them so that they can be
+//! compared, and so that a number can be found within a range.
+//!
+//! # Examples
+//!
+//! ```
+//! use rustpostal::house_number::HouseNumber;
+//!
+//! fn main() -> Result<(), rustpostal::error::ParseHouseNumberError> {
+//!     let range: HouseNumber = "12-14".parse()?;
+//!     assert!(range.contains(&"12".parse()?));
+//!     assert!(range.contains(&"13a".parse()?));
+//!     assert!(!range.contains(&"16".parse()?));
+//!
+//!     assert!("4998a".parse::<HouseNumber>()? < "4998b".parse()?);
+//!     assert_eq!("S/N".parse::<HouseNumber>()?, HouseNumber::NoNumber);
+//!     Ok(())
+//! }
+//! ```
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::ParseHouseNumberError;
+
+/// Markers of addresses without a house number, e.g. "s/n" for "sin número".
+const NO_NUMBER_MARKERS: &[&str] = &[
+    "s/n",
+    "s/nº",
+    "sn",
+    "s n",
+    "sin numero",
+    "sin número",
+    "snc",
+    "s/nc",
+    "ohne nummer",
+    "o/n",
+    "zn",
+    "z/n",
+    "zonder nummer",
+];
+
+/// A fraction of a house number, e.g. 1/2 in "12 1/2".
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Fraction {
+    pub numerator: u32,
+    pub denominator: u32,
+}
+
+impl Ord for Fraction {
+    /// Compare the values of the fractions.
+    fn cmp(&self, other: &Self) -> Ordering {
+        let left = u64::from(self.numerator) * u64::from(other.denominator);
+        let right = u64::from(other.numerator) * u64::from(self.denominator);
+        left.cmp(&right)
+            .then(self.denominator.cmp(&other.denominator))
+    }
+}
+
+impl PartialOrd for Fraction {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// A single house number, e.g. "12 1/2" or "4998a".
+///
+/// Numbers are ordered by number, then fraction, then suffix, so that
+/// "12" < "12a" < "12 1/2" < "13".
+#[derive(Clone, Debug, Default, Hash, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Number {
+    /// The number, 0 for a fraction alone, e.g. "1/2".
+    pub number: u32,
+    pub fraction: Option<Fraction>,
+    /// The lowercase letter suffix, e.g. "a" or "bis".
+    pub suffix: Option<String>,
+}
+
+impl Number {
+    fn parse(s: &str) -> Option<Number> {
+        let s = s.trim();
+        let digits = |s: &str| -> (Option<u32>, usize) {
+            let len = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+            (s[..len].parse().ok(), len)
+        };
+        let fraction = |s: &str| -> Option<(Fraction, usize)> {
+            let vulgar = [
+                ('½', 1, 2),
+                ('¼', 1, 4),
+                ('¾', 3, 4),
+                ('⅓', 1, 3),
+                ('⅔', 2, 3),
+            ];
+            if let Some(&(c, numerator, denominator)) =
+                vulgar.iter().find(|(c, _, _)| s.starts_with(*c))
+            {
+                return Some((
+                    Fraction {
+                        numerator,
+                        denominator,
+                    },
+                    c.len_utf8(),
+                ));
+            }
+            let (numerator, len) = digits(s);
+            let rest = s[len..].strip_prefix('/')?;
+            let (denominator, denominator_len) = digits(rest);
+            match (numerator, denominator) {
+                (Some(numerator), Some(denominator)) if denominator > 0 => Some((
+                    Fraction {
+                        numerator,
+                        denominator,
+                    },
+                    len + 1 + denominator_len,
+                )),
+                _ => None,
+            }
+        };
+
+        let mut result = Number::default();
+        let mut rest = s;
+        if let Some((value, len)) = fraction(rest) {
+            result.fraction = Some(value);
+            rest = &rest[len..];
+        } else {
+            let (number, len) = digits(rest);
+            result.number = number?;
+            rest = rest[len..].trim_start();
+            if let Some((value, len)) = fraction(rest) {
+                result.fraction = Some(value);
+                rest = &rest[len..];
+            }
+        }
+        let suffix = rest.trim_start_matches(|c: char| c.is_whitespace() || c == '-' || c == '/');
+        if !rest.is_empty() {
+            if suffix.is_empty() || !suffix.chars().all(char::is_alphabetic) {
+                return None;
+            }
+            result.suffix = Some(suffix.to_lowercase());
+        }
+        Some(result)
+    }
+}
+
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.number, self.fraction) {
+            (0, Some(fraction)) => write!(f, "{}/{}", fraction.numerator, fraction.denominator)?,
+            (number, Some(fraction)) => write!(
+                f,
+                "{} {}/{}",
+                number, fraction.numerator, fraction.denominator
+            )?,
+            (number, None) => write!(f, "{}", number)?,
+        }
+        if let Some(suffix) = &self.suffix {
+            write!(f, "{}", suffix)?;
+        }
+        Ok(())
+    }
+}
+
+/// A structured house number.
+///
+/// Values are ordered by their first number, addresses without a number
+/// coming first.
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum HouseNumber {
+    /// An address without a number, e.g. "s/n".
+    NoNumber,
+    /// A single number, e.g. "4998a".
+    Single(Number),
+    /// An inclusive range of numbers, e.g. "12-14", with start < end.
+    Range(Number, Number),
+}
+
+impl HouseNumber {
+    /// Return the first and last numbers, equal for a single number.
+    fn bounds(&self) -> Option<(&Number, &Number)> {
+        match self {
+            HouseNumber::NoNumber => None,
+            HouseNumber::Single(number) => Some((number, number)),
+            HouseNumber::Range(start, end) => Some((start, end)),
+        }
+    }
+
+    /// Check whether every number of `other` is within `self`, e.g. "12" or
+    /// "12a" within "12-14".
+    ///
+    /// Addresses without a number contain nothing and are in nothing.
+    pub fn contains(&self, other: &HouseNumber) -> bool {
+        match (self.bounds(), other.bounds()) {
+            (Some((start, end)), Some((other_start, other_end))) => {
+                start <= other_start && other_end <= end
+            }
+            _ => false,
+        }
+    }
+
+    /// Check whether `self` and `other` share any number, e.g. "12-14" and
+    /// "14-16".
+    pub fn overlaps(&self, other: &HouseNumber) -> bool {
+        match (self.bounds(), other.bounds()) {
+            (Some((start, end)), Some((other_start, other_end))) => {
+                start <= other_end && other_start <= end
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Ord for HouseNumber {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.bounds().cmp(&other.bounds())
+    }
+}
+
+impl PartialOrd for HouseNumber {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl FromStr for HouseNumber {
+    type Err = ParseHouseNumberError;
+
+    /// Parse a house number, e.g. "660", "12-14", "4998a", "1/2" or "s/n".
+    ///
+    /// Ranges are separated by a hyphen, a dash or "to", and must be
+    /// increasing; otherwise, e.g. in "31-05", parsing fails.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized = s.trim().to_lowercase().replace('.', "");
+        if NO_NUMBER_MARKERS.contains(&normalized.as_str()) {
+            return Ok(HouseNumber::NoNumber);
+        }
+        let error = || ParseHouseNumberError::new(s);
+        let normalized = normalized.replace(" to ", "-").replace('–', "-");
+        if let Some(number) = Number::parse(&normalized) {
+            return Ok(HouseNumber::Single(number));
+        }
+        let (start, end) = normalized.split_once('-').ok_or_else(error)?;
+        let start = Number::parse(start).ok_or_else(error)?;
+        let end = Number::parse(end).ok_or_else(error)?;
+        match start.cmp(&end) {
+            Ordering::Less => Ok(HouseNumber::Range(start, end)),
+            Ordering::Equal => Ok(HouseNumber::Single(start)),
+            Ordering::Greater => Err(error()),
+        }
+    }
+}
+
+impl fmt::Display for HouseNumber {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HouseNumber::NoNumber => write!(f, "s/n"),
+            HouseNumber::Single(number) => write!(f, "{}", number),
+            HouseNumber::Range(start, end) => write!(f, "{}-{}", start, end),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> HouseNumber {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn parse_house_numbers() {
+        let cases = [
+            ("660", "660"),
+            ("12-14", "12-14"),
+            ("12 – 14", "12-14"),
+            ("10 to 20", "10-20"),
+            ("4998A", "4998a"),
+            ("4998-a", "4998a"),
+            ("12 bis", "12bis"),
+            ("1/2", "1/2"),
+            ("12 1/2", "12 1/2"),
+            ("12½", "12 1/2"),
+            ("S/N", "s/n"),
+            ("s.n.", "s/n"),
+            ("7-7", "7"),
+        ];
+        for (s, expected) in cases.iter() {
+            assert_eq!(parse(s).to_string(), *expected, "{}", s);
+        }
+        let number = match parse("12 1/2b") {
+            HouseNumber::Single(number) => number,
+            other => panic!("{:?}", other),
+        };
+        assert_eq!(number.number, 12);
+        assert_eq!(
+            number.fraction,
+            Some(Fraction {
+                numerator: 1,
+                denominator: 2
+            })
+        );
+        assert_eq!(number.suffix.as_deref(), Some("b"));
+    }
+
+    #[test]
+    fn invalid_house_numbers() {
+        for s in ["", "abc", "31-05", "12-", "1/0", "12 #3"].iter() {
+            assert!(s.parse::<HouseNumber>().is_err(), "{}", s);
+        }
+    }
+
+    #[test]
+    fn compare_house_numbers() {
+        assert!(parse("12") < parse("12a"));
+        assert!(parse("12a") < parse("12 1/2"));
+        assert!(parse("12 1/2") < parse("13"));
+        assert!(parse("s/n") < parse("1"));
+        assert!(parse("12-14") < parse("12-16"));
+        assert_eq!(parse("1/2").cmp(&parse("2/4")), Ordering::Less);
+    }
+
+    #[test]
+    fn contain_house_numbers() {
+        let range = parse("12-14");
+        assert!(range.contains(&parse("12")));
+        assert!(range.contains(&parse("14")));
+        assert!(range.contains(&parse("13-14")));
+        assert!(!range.contains(&parse("14a")));
+        assert!(!range.contains(&parse("11-13")));
+        assert!(range.overlaps(&parse("11-13")));
+        assert!(!range.overlaps(&parse("15")));
+        assert!(parse("12").contains(&parse("12")));
+        assert!(!parse("s/n").contains(&parse("s/n")));
+    }
+}
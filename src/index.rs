@@ -0,0 +1,600 @@
+//! In-memory index of addresses for fuzzy lookup.
+//!
+//! Records are keyed by the tokens of the roots of their normalized
+//! variations, see [`NormalizeOptions::expand_root`], in an inverted index.
+//! A search ranks the records sharing tokens with the query by the
+//! idf-weighted overlap of their tokens, and then by the agreement of the
+//! parsed components they have in common, compared by their normalized
+//! variations so that "ave" agrees with "avenue".
+//!
+//! Posting lists are kept sorted, and removed records are only marked until
+//! they make up half of the index, which is then compacted. Tokens shared by
+//! many records, e.g. a city name or "street", are not walked by a search
+//! but looked up in the postings of the candidates found by rarer tokens.
+//!
+//! The index can be saved to disk and loaded back without reprocessing the
+//! addresses.
+//!
+//! # Examples
+//!
+//! ```
+//! use rustpostal::error::RuntimeError;
+//! use rustpostal::index::AddressIndex;
+//! use rustpostal::LibModules;
+//!
+//! fn main() -> Result<(), RuntimeError> {
+//!     let postal_module = LibModules::All;
+//!     postal_module.setup()?;
+//!
+//!     let mut index = AddressIndex::new(Some(["en"].iter()))?;
+//!     index.insert(1, "660 Nostrand Ave, Brooklyn, NY 11216")?;
+//!     index.insert(2, "1 Main St, Springfield")?;
+//!
+//!     let hits = index.search("660 nostrand avenue brooklyn", 1)?;
+//!     assert_eq!(hits[0].id, 1);
+//!     Ok(())
+//! }
+//! ```
+use std::collections::{BTreeSet, HashMap};
+use std::ffi::NulError;
+use std::hash::Hash;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::address::{AddressLabel, AddressParserOptions, AddressParserResponse};
+use crate::expand::{NormalizeOptions, NormalizedAddress};
+
+/// Version of the serialized index, checked on load.
+const FORMAT_VERSION: u32 = 2;
+
+/// Weight of the token overlap in the score; the rest is component agreement.
+const OVERLAP_WEIGHT: f64 = 0.7;
+
+/// Candidates ranked by component agreement, per requested hit.
+const CANDIDATES_PER_HIT: usize = 10;
+
+/// Length of the longest posting list a search walks; longer ones are
+/// those of stop words, only looked up for the candidates.
+const MAX_WALKED_POSTINGS: usize = 10_000;
+
+/// A search result.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SearchHit<K> {
+    pub id: K,
+    /// The combined score, from 0 to 1.
+    pub score: f64,
+    /// The idf-weighted share of the query tokens found in the record.
+    pub overlap: f64,
+    /// The share of the components in both addresses with equal normalized
+    /// values.
+    pub agreement: f64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Entry<K> {
+    id: K,
+    tokens: Vec<u32>,
+    /// The canonical variations of the parsed components.
+    components: Vec<(AddressLabel, String)>,
+}
+
+/// Inverted index of addresses by the tokens of their normalized roots.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "K: Serialize + Eq + Hash",
+    deserialize = "K: Deserialize<'de> + Eq + Hash"
+))]
+pub struct AddressIndex<K> {
+    languages: Option<Vec<String>>,
+    /// The records by slot, `None` once removed.
+    entries: Vec<Option<Entry<K>>>,
+    /// The number of removed entries.
+    removed: usize,
+    slots: HashMap<K, u32>,
+    tokens: HashMap<String, u32>,
+    /// The slots of each token, in increasing order.
+    postings: Vec<Vec<u32>>,
+}
+
+fn components(response: &AddressParserResponse) -> Vec<(AddressLabel, String)> {
+    AddressLabel::ALL
+        .iter()
+        .filter_map(|&label| response.get(label).map(|value| (label, value)))
+        .collect()
+}
+
+/// The share of the labels in both the query and the record whose
+/// canonical value is among the variations of the query.
+fn agreement(query: &[(AddressLabel, Vec<String>)], record: &[(AddressLabel, String)]) -> f64 {
+    let (mut shared, mut equal) = (0, 0);
+    for (label, variations) in query {
+        if let Some((_, value)) = record.iter().find(|(other, _)| other == label) {
+            shared += 1;
+            if variations.contains(value) {
+                equal += 1;
+            }
+        }
+    }
+    if shared > 0 {
+        f64::from(equal) / f64::from(shared)
+    } else {
+        0.0
+    }
+}
+
+#[allow(clippy::boxed_local)]
+fn io_error(err: bincode::Error) -> io::Error {
+    match *err {
+        bincode::ErrorKind::Io(err) => err,
+        err => io::Error::new(io::ErrorKind::InvalidData, err),
+    }
+}
+
+impl<K: Clone + Eq + Hash> AddressIndex<K> {
+    /// Create an empty index.
+    ///
+    /// `languages` are used for normalization; if not given, they are
+    /// classified from each address.
+    pub fn new<'a, 'b, T>(languages: Option<T>) -> Result<AddressIndex<K>, NulError>
+    where
+        'a: 'b,
+        T: Iterator<Item = &'b &'a str>,
+    {
+        let languages: Option<Vec<String>> =
+            languages.map(|languages| languages.map(|l| String::from(*l)).collect());
+        let index = AddressIndex {
+            languages,
+            entries: Vec::new(),
+            removed: 0,
+            slots: HashMap::new(),
+            tokens: HashMap::new(),
+            postings: Vec::new(),
+        };
+        // Fail early on languages that cannot be passed to libpostal.
+        index.normalize_options()?;
+        Ok(index)
+    }
+
+    /// Return the number of records.
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Return `true` if the index has no records.
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// Return `true` if the index has a record with `id`.
+    pub fn contains(&self, id: &K) -> bool {
+        self.slots.contains_key(id)
+    }
+
+    fn normalize_options(&self) -> Result<NormalizeOptions, NulError> {
+        let languages: Option<Vec<&str>> = self
+            .languages
+            .as_ref()
+            .map(|languages| languages.iter().map(String::as_str).collect());
+        NormalizeOptions::new(languages.as_ref().map(|l| l.iter()))
+    }
+
+    /// The distinct tokens of the roots of `address`, or of its variations
+    /// if it has no roots.
+    fn keys(&self, address: &str) -> Result<BTreeSet<String>, NulError> {
+        let mut options = self.normalize_options()?;
+        let mut variations = Vec::from(options.expand_root(address)?);
+        if variations.iter().all(|v| v.trim().is_empty()) {
+            variations = Vec::from(options.expand(address)?);
+        }
+        Ok(variations
+            .iter()
+            .flat_map(|variation| variation.split_whitespace())
+            .map(String::from)
+            .collect())
+    }
+
+    /// The parsed components of `address`, each mapped by `normalize`
+    /// from its normalized variations.
+    fn parse<T>(
+        &self,
+        address: &str,
+        normalize: impl Fn(&NormalizedAddress, &str) -> T,
+    ) -> Result<Vec<(AddressLabel, T)>, NulError> {
+        let response = AddressParserOptions::new(None, None)?.parse(address)?;
+        let mut options = self.normalize_options()?;
+        components(&response)
+            .into_iter()
+            .map(|(label, value)| Ok((label, normalize(&options.expand(&value)?, &value))))
+            .collect()
+    }
+
+    /// Insert the record `id`, replacing any record with the same id.
+    ///
+    /// # Errors
+    ///
+    /// It will return an error if the address contains an internal null byte.
+    pub fn insert(&mut self, id: K, address: &str) -> Result<(), NulError> {
+        let keys = self.keys(address)?;
+        let components = self.parse(address, |normalized, value| {
+            normalized
+                .canonical()
+                .map_or_else(|| value.to_lowercase(), String::from)
+        })?;
+        self.insert_entry(id, keys, components);
+        Ok(())
+    }
+
+    fn insert_entry(
+        &mut self,
+        id: K,
+        keys: BTreeSet<String>,
+        components: Vec<(AddressLabel, String)>,
+    ) {
+        self.remove(&id);
+        // Slots only increase, which keeps the postings sorted.
+        let slot = self.entries.len() as u32;
+        let mut tokens = Vec::with_capacity(keys.len());
+        for key in keys {
+            let next = self.postings.len() as u32;
+            let token = *self.tokens.entry(key).or_insert(next);
+            if token == next {
+                self.postings.push(Vec::new());
+            }
+            self.postings[token as usize].push(slot);
+            tokens.push(token);
+        }
+        self.slots.insert(id.clone(), slot);
+        self.entries.push(Some(Entry {
+            id,
+            tokens,
+            components,
+        }));
+    }
+
+    /// Remove the record `id`, returning `true` if it was in the index.
+    ///
+    /// The record is only marked as removed, until removed records make up
+    /// half of the index, which is then compacted.
+    pub fn remove(&mut self, id: &K) -> bool {
+        let slot = match self.slots.remove(id) {
+            Some(slot) => slot,
+            None => return false,
+        };
+        self.entries[slot as usize] = None;
+        self.removed += 1;
+        if self.removed * 2 > self.entries.len() {
+            self.compact();
+        }
+        true
+    }
+
+    /// Drop the removed entries, renumbering the slots in the same order.
+    fn compact(&mut self) {
+        let mut renumbered = vec![None; self.entries.len()];
+        let mut entries = Vec::with_capacity(self.entries.len() - self.removed);
+        for (slot, entry) in self.entries.drain(..).enumerate() {
+            if let Some(entry) = entry {
+                renumbered[slot] = Some(entries.len() as u32);
+                entries.push(Some(entry));
+            }
+        }
+        for posting in &mut self.postings {
+            *posting = posting
+                .iter()
+                .filter_map(|&slot| renumbered[slot as usize])
+                .collect();
+        }
+        for slot in self.slots.values_mut() {
+            *slot = renumbered[*slot as usize].expect("slot of a removed entry");
+        }
+        self.entries = entries;
+        self.removed = 0;
+    }
+
+    /// Find the `k` records best matching `query`, by decreasing score.
+    ///
+    /// # Errors
+    ///
+    /// It will return an error if the query contains an internal null byte.
+    pub fn search(&self, query: &str, k: usize) -> Result<Vec<SearchHit<K>>, NulError> {
+        if k == 0 || self.is_empty() {
+            return Ok(Vec::new());
+        }
+        let keys = self.keys(query)?;
+        let components = self.parse(query, |normalized, value| {
+            let mut variations: Vec<String> = normalized.ranked().into_iter().cloned().collect();
+            if variations.is_empty() {
+                variations.push(value.to_lowercase());
+            }
+            variations
+        })?;
+        Ok(self.rank(&keys, &components, k))
+    }
+
+    fn rank(
+        &self,
+        keys: &BTreeSet<String>,
+        components: &[(AddressLabel, Vec<String>)],
+        k: usize,
+    ) -> Vec<SearchHit<K>> {
+        self.rank_walking(keys, components, k, MAX_WALKED_POSTINGS)
+    }
+
+    /// Rank the records, walking the postings of at most `max_walked`
+    /// slots, see [`MAX_WALKED_POSTINGS`].
+    fn rank_walking(
+        &self,
+        keys: &BTreeSet<String>,
+        components: &[(AddressLabel, Vec<String>)],
+        k: usize,
+        max_walked: usize,
+    ) -> Vec<SearchHit<K>> {
+        let records = self.len() as f64;
+        let idf = |frequency: usize| (1.0 + records / frequency.max(1) as f64).ln();
+        let mut total = 0.0;
+        let mut overlaps: HashMap<u32, f64> = HashMap::new();
+        let mut stop_words: Vec<(&[u32], f64)> = Vec::new();
+        for key in keys {
+            match self.tokens.get(key) {
+                Some(&token) => {
+                    let postings = &self.postings[token as usize];
+                    let weight = idf(postings.len());
+                    total += weight;
+                    if postings.len() > max_walked {
+                        stop_words.push((postings, weight));
+                        continue;
+                    }
+                    for &slot in postings {
+                        *overlaps.entry(slot).or_default() += weight;
+                    }
+                }
+                None => total += idf(1),
+            }
+        }
+        if total <= 0.0 {
+            return Vec::new();
+        }
+        // With stop words only, the candidates are the first records of the
+        // rarest one.
+        stop_words.sort_by_key(|(postings, _)| postings.len());
+        if overlaps.is_empty() {
+            if let Some((postings, _)) = stop_words.first() {
+                overlaps.extend(postings.iter().take(max_walked).map(|&slot| (slot, 0.0)));
+            }
+        }
+        for (slot, overlap) in overlaps.iter_mut() {
+            for (postings, weight) in &stop_words {
+                if postings.binary_search(slot).is_ok() {
+                    *overlap += weight;
+                }
+            }
+        }
+
+        let mut candidates: Vec<(u32, f64)> = overlaps.into_iter().collect();
+        candidates.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        candidates.truncate(k.saturating_mul(CANDIDATES_PER_HIT));
+
+        let mut hits: Vec<SearchHit<K>> = candidates
+            .into_iter()
+            .filter_map(|(slot, overlap)| {
+                let entry = self.entries[slot as usize].as_ref()?;
+                let overlap = overlap / total;
+                let agreement = agreement(components, &entry.components);
+                Some(SearchHit {
+                    id: entry.id.clone(),
+                    score: OVERLAP_WEIGHT * overlap + (1.0 - OVERLAP_WEIGHT) * agreement,
+                    overlap,
+                    agreement,
+                })
+            })
+            .collect();
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+        hits.truncate(k);
+        hits
+    }
+}
+
+impl<K: Eq + Hash> AddressIndex<K> {
+    /// Check that the slots and tokens of the index refer to its entries
+    /// and postings, as indexing them would otherwise panic.
+    fn validate(&self) -> Result<(), &'static str> {
+        let entry = |slot: u32| self.entries.get(slot as usize);
+        let posting = |token: u32| (token as usize) < self.postings.len();
+        if self
+            .postings
+            .iter()
+            .any(|posting| posting.windows(2).any(|pair| pair[0] >= pair[1]))
+        {
+            return Err("unsorted postings");
+        }
+        if self
+            .postings
+            .iter()
+            .flatten()
+            .any(|&slot| entry(slot).is_none())
+        {
+            return Err("posting of a missing entry");
+        }
+        if !self.tokens.values().all(|&token| posting(token)) {
+            return Err("token without postings");
+        }
+        for (id, &slot) in &self.slots {
+            match entry(slot) {
+                Some(Some(entry))
+                    if entry.id == *id && entry.tokens.iter().all(|&t| posting(t)) => {}
+                _ => return Err("record of a missing or invalid entry"),
+            }
+        }
+        if self.slots.len() != self.entries.iter().flatten().count() {
+            return Err("entry without record");
+        }
+        if self.removed != self.entries.len() - self.slots.len() {
+            return Err("wrong count of removed entries");
+        }
+        Ok(())
+    }
+}
+
+impl<K: Clone + Eq + Hash + Serialize + DeserializeOwned> AddressIndex<K> {
+    /// Serialize the index to `writer`.
+    pub fn save<W: Write>(&self, writer: W) -> io::Result<()> {
+        let mut writer = BufWriter::new(writer);
+        bincode::serialize_into(&mut writer, &(FORMAT_VERSION, self)).map_err(io_error)?;
+        writer.flush()
+    }
+
+    /// Deserialize an index saved with [`save`](AddressIndex::save).
+    ///
+    /// # Errors
+    ///
+    /// It will return an error if reading fails, or if the data is not an
+    /// index of the current format.
+    pub fn load<R: Read>(reader: R) -> io::Result<AddressIndex<K>> {
+        let reader = BufReader::new(reader);
+        let (version, index): (u32, AddressIndex<K>) =
+            bincode::deserialize_from(reader).map_err(io_error)?;
+        if version != FORMAT_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unsupported index format version {}", version),
+            ));
+        }
+        index.validate().map_err(|message| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("corrupt index: {}", message),
+            )
+        })?;
+        Ok(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(tokens: &[&str]) -> BTreeSet<String> {
+        tokens.iter().map(|&t| String::from(t)).collect()
+    }
+
+    fn index() -> AddressIndex<u32> {
+        let mut index = AddressIndex::new(Some(["en"].iter())).unwrap();
+        index.insert_entry(
+            1,
+            keys(&["660", "nostrand", "brooklyn"]),
+            vec![(AddressLabel::HouseNumber, String::from("660"))],
+        );
+        index.insert_entry(
+            2,
+            keys(&["1", "main", "springfield"]),
+            vec![(AddressLabel::HouseNumber, String::from("1"))],
+        );
+        index
+    }
+
+    #[test]
+    fn rank_by_overlap_and_agreement() {
+        let index = index();
+        let components = [(AddressLabel::HouseNumber, vec![String::from("660")])];
+        let hits = index.rank(&keys(&["660", "nostrand"]), &components, 2);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].id, 1);
+        assert_eq!(hits[0].agreement, 1.0);
+        assert!((hits[0].overlap - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn insert_replace_and_remove() {
+        let mut index = index();
+        index.insert_entry(1, keys(&["main"]), Vec::new());
+        assert_eq!(index.len(), 2);
+        let hits = index.rank(&keys(&["nostrand"]), &[], 2);
+        assert!(hits.is_empty());
+        assert!(index.remove(&1));
+        assert!(!index.remove(&1));
+        assert_eq!(index.rank(&keys(&["main"]), &[], 2).len(), 1);
+    }
+
+    #[test]
+    fn agree_on_normalized_variations() {
+        let mut index = index();
+        index.insert_entry(
+            3,
+            keys(&["660", "nostrand", "avenue"]),
+            vec![(AddressLabel::Road, String::from("nostrand avenue"))],
+        );
+        let components = [(
+            AddressLabel::Road,
+            vec![
+                String::from("nostrand avenue"),
+                String::from("nostrand ave"),
+            ],
+        )];
+        let hits = index.rank(&keys(&["nostrand"]), &components, 1);
+        assert_eq!(hits[0].id, 3);
+        assert_eq!(hits[0].agreement, 1.0);
+    }
+
+    #[test]
+    fn remove_marks_then_compacts() {
+        let mut index = index();
+        index.insert_entry(3, keys(&["3", "main"]), Vec::new());
+        assert!(index.remove(&1));
+        assert_eq!((index.entries.len(), index.removed), (3, 1));
+        assert_eq!(index.postings[index.tokens["main"] as usize], [1, 2]);
+        assert!(index.rank(&keys(&["nostrand"]), &[], 1).is_empty());
+
+        assert!(index.remove(&2));
+        assert_eq!((index.entries.len(), index.removed), (1, 0));
+        assert_eq!(index.postings[index.tokens["main"] as usize], [0]);
+        assert_eq!(index.rank(&keys(&["main"]), &[], 2)[0].id, 3);
+        assert_eq!(index.validate(), Ok(()));
+    }
+
+    #[test]
+    fn rank_without_walking_stop_words() {
+        let mut index = index();
+        index.insert_entry(3, keys(&["3", "main", "springfield"]), Vec::new());
+        let hits = index.rank_walking(&keys(&["3", "main", "springfield"]), &[], 2, 1);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].id, 3);
+        assert!((hits[0].overlap - 1.0).abs() < 1e-9);
+
+        // With stop words only, the records of the rarest are candidates.
+        let hits = index.rank_walking(&keys(&["main"]), &[], 2, 1);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].id, 2);
+    }
+
+    #[test]
+    fn save_and_load() -> io::Result<()> {
+        let index = index();
+        let mut buffer = Vec::new();
+        index.save(&mut buffer)?;
+        let loaded = AddressIndex::<u32>::load(buffer.as_slice())?;
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded.rank(&keys(&["main"]), &[], 1)[0].id, 2);
+        assert!(AddressIndex::<u32>::load(&buffer[..4]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn load_corrupt_index() -> io::Result<()> {
+        let mut corrupt = index();
+        corrupt.postings[0].push(7);
+        let mut buffer = Vec::new();
+        corrupt.save(&mut buffer)?;
+        let err = AddressIndex::<u32>::load(buffer.as_slice()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let mut corrupt = index();
+        corrupt.removed += 1;
+        let mut buffer = Vec::new();
+        corrupt.save(&mut buffer)?;
+        assert!(AddressIndex::<u32>::load(buffer.as_slice()).is_err());
+        Ok(())
+    }
+}
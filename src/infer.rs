@@ -0,0 +1,232 @@
+//! Inference of the language and country of an address, for parsing.
+//!
+//! [`AddressParserOptions`] accept a language and a country, which are
+//! seldom known in advance. An address is first parsed without them, and
+//! its language is classified. The country is inferred from cues in the
+//! parsed components: a country name, a postcode whose shape belongs to a
+//! single country, or a known state or province. The address is then parsed
+//! again with the hints that are confident enough.
+//!
+//! # Examples
+//!
+//! ```
+//! use rustpostal::error::RuntimeError;
+//! use rustpostal::infer;
+//! use rustpostal::LibModules;
+//!
+//! fn main() -> Result<(), RuntimeError> {
+//!     let postal_module = LibModules::All;
+//!     postal_module.setup()?;
+//!
+//!     let inference = infer::infer("St Johns Centre, Rope Walk, Bedford, MK42 0XE")?;
+//!     if let Some(country) = &inference.country {
+//!         println!("{} ({:?}, {:.2})", country.value, country.cue, country.confidence);
+//!     }
+//!     for (label, token) in &inference.response {
+//!         println!("{}: {}", label, token);
+//!     }
+//!     Ok(())
+//! }
+//! ```
+use std::ffi::NulError;
+
+use crate::address::{AddressLabel, AddressParserOptions, AddressParserResponse};
+use crate::country;
+use crate::language;
+use crate::postcode;
+
+/// Confidence of a country named in the address.
+const COUNTRY_NAME_CONFIDENCE: f64 = 1.0;
+/// Confidence of a postcode shape matching a single country.
+const POSTCODE_CONFIDENCE: f64 = 0.9;
+/// Confidence of a state or province known in a single country.
+const TOPONYM_CONFIDENCE: f64 = 0.8;
+
+/// The cue a hint was inferred from.
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Cue {
+    /// The language classifier.
+    Classifier,
+    /// A country name or code.
+    CountryName,
+    /// The shape of the postcode.
+    Postcode,
+    /// A known state or province.
+    Toponym,
+}
+
+/// A language or country inferred for an address.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Hint {
+    /// The language code, or the lowercase ISO 3166-1 alpha-2 code.
+    pub value: String,
+    /// The confidence of the hint, between 0 and 1.
+    pub confidence: f64,
+    pub cue: Cue,
+}
+
+/// The response of the parser along with the hints it was given.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Inference {
+    pub response: AddressParserResponse,
+    /// The language given to the parser, if any.
+    pub language: Option<Hint>,
+    /// The country given to the parser, if any.
+    pub country: Option<Hint>,
+}
+
+/// Inference options.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InferOptions {
+    threshold: f64,
+}
+
+impl Default for InferOptions {
+    fn default() -> InferOptions {
+        InferOptions { threshold: 0.8 }
+    }
+}
+
+/// Infer the country of a parsed address from its cues.
+///
+/// The most confident cue wins, and its confidence is halved if another
+/// cue points to a different country.
+fn country_hint(response: &AddressParserResponse) -> Option<Hint> {
+    let mut cues: Vec<(&'static str, f64, Cue)> = Vec::new();
+    if let Some(name) = response.get(AddressLabel::Country) {
+        if let Some(country) = country::find(&name) {
+            cues.push((country.alpha2, COUNTRY_NAME_CONFIDENCE, Cue::CountryName));
+        }
+    }
+    if let Some(code) = response.get(AddressLabel::Postcode) {
+        if let [country] = postcode::countries(&code)[..] {
+            cues.push((country, POSTCODE_CONFIDENCE, Cue::Postcode));
+        }
+    }
+    if let Some(state) = response.get(AddressLabel::State) {
+        if let Some(subdivision) = country::find_subdivision(None, &state) {
+            cues.push((subdivision.country_code(), TOPONYM_CONFIDENCE, Cue::Toponym));
+        }
+    }
+    let &(alpha2, mut confidence, cue) = cues.iter().max_by(|a, b| a.1.total_cmp(&b.1))?;
+    if cues.iter().any(|&(other, _, _)| other != alpha2) {
+        confidence /= 2.0;
+    }
+    Some(Hint {
+        value: alpha2.to_ascii_lowercase(),
+        confidence,
+        cue,
+    })
+}
+
+impl InferOptions {
+    /// Create options with the default threshold.
+    pub fn new() -> InferOptions {
+        Default::default()
+    }
+
+    /// Get the minimum confidence of the hints given to the parser.
+    pub fn threshold(&self) -> f64 {
+        self.threshold
+    }
+
+    /// Set the minimum confidence of the hints given to the parser.
+    pub fn set_threshold(&mut self, threshold: f64) {
+        self.threshold = threshold;
+    }
+
+    /// Parse an address with its inferred language and country.
+    ///
+    /// Requires the parser and the language classifier to be setup, e.g.
+    /// with [`LibModules::All`](crate::LibModules::All). Without confident
+    /// hints, the response is that of the parse without options.
+    ///
+    /// # Errors
+    ///
+    /// It will return an error if the address contains an internal null byte.
+    pub fn infer(&self, address: &str) -> Result<Inference, NulError> {
+        let response = AddressParserOptions::default().parse(address)?;
+        let language = language::classify_language(address)?
+            .most_probable()
+            .map(|(language, probability)| Hint {
+                value: String::from(language),
+                confidence: probability,
+                cue: Cue::Classifier,
+            })
+            .filter(|hint| hint.confidence >= self.threshold);
+        let country = country_hint(&response).filter(|hint| hint.confidence >= self.threshold);
+        if language.is_none() && country.is_none() {
+            return Ok(Inference {
+                response,
+                ..Default::default()
+            });
+        }
+        let options = AddressParserOptions::new(
+            language.as_ref().map(|hint| hint.value.as_str()),
+            country.as_ref().map(|hint| hint.value.as_str()),
+        )?;
+        Ok(Inference {
+            response: options.parse(address)?,
+            language,
+            country,
+        })
+    }
+}
+
+/// Parse an address with its inferred language and country.
+///
+/// The function wraps [`InferOptions::infer`] with the default options.
+pub fn infer(address: &str) -> Result<Inference, NulError> {
+    InferOptions::new().infer(address)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(components: &[(AddressLabel, &str)]) -> AddressParserResponse {
+        components
+            .iter()
+            .map(|&(label, value)| (label, String::from(value)))
+            .collect()
+    }
+
+    #[test]
+    fn country_from_cues() {
+        let hint = |components| country_hint(&response(components));
+        assert_eq!(hint(&[]), None);
+        assert_eq!(hint(&[(AddressLabel::Postcode, "10115")]), None);
+
+        let postcode = hint(&[(AddressLabel::Postcode, "mk42 0xe")]).unwrap();
+        assert_eq!(
+            (postcode.value.as_str(), postcode.cue),
+            ("gb", Cue::Postcode)
+        );
+
+        let name = hint(&[
+            (AddressLabel::State, "california"),
+            (AddressLabel::Country, "usa"),
+        ])
+        .unwrap();
+        assert_eq!((name.value.as_str(), name.cue), ("us", Cue::CountryName));
+        assert_eq!(name.confidence, COUNTRY_NAME_CONFIDENCE);
+    }
+
+    #[test]
+    fn conflicting_cues() {
+        let hint = country_hint(&response(&[
+            (AddressLabel::Postcode, "k1a 0b1"),
+            (AddressLabel::Country, "deutschland"),
+        ]))
+        .unwrap();
+        assert_eq!(hint.value, "de");
+        assert!(hint.confidence < InferOptions::new().threshold());
+    }
+}
@@ -0,0 +1,125 @@
+//! Language classification of postal addresses.
+//!
+//! # Examples
+//!
+//! ```
+//! use rustpostal::{language, LibModules};
+//! use rustpostal::error::RuntimeError;
+//!
+//! fn main() -> Result<(), RuntimeError> {
+//!     let postal_module = LibModules::Language;
+//!     postal_module.setup()?;
+//!
+//!     let address = "Museo del Prado C. de Ruiz de Alarcón, 23 28014 Madrid, España";
+//!
+//!     let classified = language::classify_language(address)?;
+//!
+//!     for (language, probability) in &classified {
+//!         println!("{}: {}", language, probability);
+//!     }
+//!     Ok(())
+//! }
+//! ```
+use std::ffi::{CStr, CString, NulError};
+use std::iter::Zip;
+use std::slice::Iter;
+
+use crate::ffi;
+
+/// Languages of an address along with their probabilities, in descending order.
+#[derive(Clone, Default, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LanguageClassifierResponse {
+    languages: Vec<String>,
+    probs: Vec<f64>,
+}
+
+impl LanguageClassifierResponse {
+    /// Create a new value.
+    pub fn new() -> LanguageClassifierResponse {
+        Default::default()
+    }
+
+    /// Return the most probable language, if any.
+    pub fn most_probable(&self) -> Option<(&str, f64)> {
+        self.languages
+            .first()
+            .map(|language| (language.as_str(), self.probs[0]))
+    }
+
+    /// Iterate over `(language, probability)` pairs.
+    pub fn iter(&self) -> Zip<Iter<'_, String>, Iter<'_, f64>> {
+        self.languages.iter().zip(self.probs.iter())
+    }
+}
+
+impl<'a> IntoIterator for &'a LanguageClassifierResponse {
+    type Item = (&'a String, &'a f64);
+    type IntoIter = Zip<Iter<'a, String>, Iter<'a, f64>>;
+
+    /// Iterates over `(language, probability)` pairs.
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Classify the language of an address using `libpostal`.
+///
+/// Requires the language classifier to be setup, e.g. with
+/// [`LibModules::Language`](crate::LibModules::Language).
+///
+/// # Errors
+///
+/// It will return an error if the address contains an internal null byte.
+pub fn classify_language(address: &str) -> Result<LanguageClassifierResponse, NulError> {
+    let c_address = CString::new(address)?;
+    let mut response = LanguageClassifierResponse::new();
+    let ptr = c_address.into_raw();
+
+    let raw = unsafe { ffi::libpostal_classify_language(ptr) };
+    if let Some(classified) = unsafe { raw.as_ref() } {
+        for i in 0..classified.num_languages {
+            let language = unsafe { CStr::from_ptr(*classified.languages.add(i)) };
+            let prob = unsafe { *classified.probs.add(i) };
+            response
+                .languages
+                .push(String::from(language.to_str().unwrap()));
+            response.probs.push(prob);
+        }
+        unsafe {
+            ffi::libpostal_language_classifier_response_destroy(raw);
+        }
+    };
+    let _c_address = unsafe { CString::from_raw(ptr) };
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::RuntimeError;
+    use crate::LibModules;
+
+    #[test]
+    fn language_classifier_response_most_probable() {
+        let mut response = LanguageClassifierResponse::new();
+        assert_eq!(response.most_probable(), None);
+        response.languages.push(String::from("es"));
+        response.probs.push(0.9);
+        response.languages.push(String::from("ca"));
+        response.probs.push(0.1);
+        assert_eq!(response.most_probable(), Some(("es", 0.9)));
+        assert_eq!(response.iter().count(), 2);
+    }
+
+    #[test]
+    fn classify_language_es() -> Result<(), RuntimeError> {
+        let postal_module = LibModules::Language;
+        postal_module.setup()?;
+
+        let address = "Museo del Prado C. de Ruiz de Alarcón, 23 28014 Madrid, España";
+        let classified = classify_language(address)?;
+        assert_eq!(classified.most_probable().map(|(l, _)| l), Some("es"));
+        Ok(())
+    }
+}
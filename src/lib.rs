@@ -30,49 +30,141 @@
 //!
 //! [libpostal]: https://github.com/openvenues/libpostal
 
+use std::mem;
+use std::path::Path;
 use std::process;
 
 use self::LibModules::*;
 
+#[cfg(feature = "parser")]
 pub mod address;
+#[cfg(feature = "arrow")]
+pub mod arrow;
+#[cfg(all(feature = "parser", feature = "dedupe"))]
+pub mod cluster;
+#[cfg(feature = "expand")]
+pub mod country;
+#[cfg(feature = "datafusion")]
+pub mod datafusion;
+#[cfg(feature = "dedupe")]
+pub mod dedupe;
 pub mod error;
+#[cfg(feature = "expand")]
 pub mod expand;
 mod ffi;
+#[cfg(feature = "geocode")]
+pub mod geocode;
+pub mod house_number;
+#[cfg(feature = "index")]
+pub mod index;
+#[cfg(all(feature = "parser", feature = "expand", feature = "language"))]
+pub mod infer;
+#[cfg(feature = "language")]
+pub mod language;
+#[cfg(all(feature = "parser", feature = "dedupe"))]
+pub mod linkage;
+pub mod models;
+pub mod postcode;
+#[cfg(feature = "reverse")]
+pub mod reverse;
+#[cfg(feature = "expand")]
+pub mod road;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+#[cfg(all(feature = "parser", feature = "expand"))]
+pub mod standardize;
+#[cfg(feature = "expand")]
+pub mod subpremise;
 
 use error::SetupError;
+use models::{Models, ModelsGuard};
+
+/// Look up a flag by name, ignoring case and treating `-` as `_`.
+#[cfg(any(feature = "expand", feature = "dedupe"))]
+fn flag_from_name<F: Copy>(names: &[(&str, F)], name: &str) -> Result<F, error::ParseOptionError> {
+    let normalized = name.trim().to_ascii_lowercase().replace('-', "_");
+    names
+        .iter()
+        .find(|(flag_name, _)| *flag_name == normalized)
+        .map(|&(_, flag)| flag)
+        .ok_or_else(|| error::ParseOptionError::new(name))
+}
+
+/// Copy `s` into `buffer` as a C string, so that passing many strings to
+/// `libpostal` reuses one allocation.
+#[cfg(any(feature = "parser", feature = "expand"))]
+fn c_str<'a>(buffer: &'a mut Vec<u8>, s: &str) -> Result<&'a std::ffi::CStr, std::ffi::NulError> {
+    if s.as_bytes().contains(&0) {
+        return Err(std::ffi::CString::new(s).unwrap_err());
+    }
+    buffer.clear();
+    buffer.extend_from_slice(s.as_bytes());
+    buffer.push(0);
+    Ok(std::ffi::CStr::from_bytes_with_nul(buffer).expect("no internal null byte"))
+}
 
 /// Library modules to setup and teardown, at the start
 /// and at the end of our program.
+///
+/// The variants follow the enabled cargo features, and setup only
+/// loads the models that the enabled features can use. Models are
+/// reference counted across modules, see [`models`].
 #[derive(Clone, Debug, Hash, Ord, PartialOrd, Eq, PartialEq)]
 pub enum LibModules {
+    /// The address parser, used by [`address`].
+    #[cfg(feature = "parser")]
     Address,
+    /// The language classifier, used by [`expand`].
+    #[cfg(feature = "expand")]
     Expand,
+    /// The language classifier, used by [`dedupe`].
+    #[cfg(feature = "dedupe")]
+    Dedupe,
+    /// The language classifier, used by [`language`].
+    #[cfg(feature = "language")]
+    Language,
+    /// Every model used by the enabled features.
     All,
 }
 
-unsafe fn setup_parser() {
-    if !ffi::libpostal_setup_parser() {
-        process::exit(1)
-    };
-}
-
-unsafe fn setup_classifier() {
-    if !ffi::libpostal_setup_language_classifier() {
-        process::exit(1)
-    };
-}
-
-unsafe fn teardown_parser() {
-    ffi::libpostal_teardown_parser();
-}
-
-unsafe fn teardown_classifier() {
-    ffi::libpostal_teardown_language_classifier();
-}
-
 impl LibModules {
+    /// Return the models the module requires.
+    ///
+    /// # Examples
+    /// ```
+    /// use rustpostal::models::Models;
+    /// use rustpostal::LibModules;
+    ///
+    /// assert!(LibModules::All.models().contains(Models::BASE));
+    /// ```
+    pub fn models(&self) -> Models {
+        match self {
+            #[cfg(feature = "parser")]
+            Address => Models::BASE | Models::PARSER,
+            All => {
+                let mut models = Models::BASE;
+                models.set(Models::PARSER, cfg!(feature = "parser"));
+                models.set(
+                    Models::CLASSIFIER,
+                    cfg!(any(
+                        feature = "expand",
+                        feature = "dedupe",
+                        feature = "language"
+                    )),
+                );
+                models
+            }
+            #[allow(unreachable_patterns)]
+            _ => Models::BASE | Models::CLASSIFIER,
+        }
+    }
+
     /// Setup the necessary `libpostal` resources.
     ///
+    /// The models stay loaded until the value is dropped, see
+    /// [`setup_guarded`](LibModules::setup_guarded) to tie them to a guard
+    /// instead.
+    ///
     /// # Examples
     /// ```
     /// use rustpostal::error::SetupError;
@@ -85,37 +177,69 @@ impl LibModules {
     /// }
     /// ```
     pub fn setup(&self) -> Result<(), SetupError> {
-        if unsafe { !ffi::libpostal_setup() } {
-            return Err(SetupError);
-        }
-        match self {
-            Expand => unsafe {
-                setup_classifier();
-            },
-            Address => unsafe {
-                setup_parser();
-            },
-            All => unsafe {
-                setup_parser();
-                setup_classifier();
-            },
-        }
-        Ok(())
+        models::load_module(self.models(), None)
+    }
+
+    /// Setup the necessary `libpostal` resources, loading the model data
+    /// from `datadir` instead of the directory configured when `libpostal`
+    /// was built.
+    ///
+    /// This is required when the data are installed separately, e.g.
+    /// with the `vendored` feature.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use rustpostal::error::SetupError;
+    /// use rustpostal::LibModules;
+    ///
+    /// fn main() -> Result<(), SetupError> {
+    ///     let postal_module = LibModules::All;
+    ///     postal_module.setup_with_datadir("/opt/libpostal/data")?;
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// It will return an error if any of the resources fail to load.
+    pub fn setup_with_datadir<P: AsRef<Path>>(&self, datadir: P) -> Result<(), SetupError> {
+        models::load_module(self.models(), Some(datadir.as_ref()))
+    }
+
+    /// Setup the necessary `libpostal` resources, held by the returned guard
+    /// rather than by the value.
+    ///
+    /// Unlike [`setup`](LibModules::setup), dropping the value, or any
+    /// other value of the module, leaves the models loaded as long as the
+    /// guard is alive.
+    ///
+    /// # Examples
+    /// ```
+    /// use rustpostal::error::SetupError;
+    /// use rustpostal::LibModules;
+    ///
+    /// fn main() -> Result<(), SetupError> {
+    ///     let _models = LibModules::Expand.setup_guarded()?;
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// It will return an error if any of the resources fail to load.
+    pub fn setup_guarded(&self) -> Result<ModelsGuard, SetupError> {
+        ModelsGuard::load(self.models())
     }
 }
 
 impl Drop for LibModules {
-    /// Tear down the ffi resources that were initialized during setup.
+    /// Release the models acquired during setup.
+    ///
+    /// Setups are counted per set of models, so a value that was never set
+    /// up only releases models if another value of a module requiring the
+    /// same models was. Models still in use elsewhere stay loaded.
     fn drop(&mut self) {
-        unsafe { ffi::libpostal_teardown() };
-        match self {
-            Expand => unsafe { teardown_classifier() },
-            Address => unsafe { teardown_parser() },
-            All => unsafe {
-                teardown_parser();
-                teardown_classifier();
-            },
-        }
+        models::release_module(self.models());
     }
 }
 
@@ -130,21 +254,11 @@ impl Drop for LibModules {
     note = "Please use the `setup` method in `LibModules` instead"
 )]
 pub unsafe fn setup(component: LibModules) {
-    if !ffi::libpostal_setup() {
+    if component.setup().is_err() {
         process::exit(1);
     }
-    match component {
-        Expand => {
-            setup_classifier();
-        }
-        Address => {
-            setup_parser();
-        }
-        All => {
-            setup_parser();
-            setup_classifier();
-        }
-    }
+    // Released by `teardown`.
+    mem::forget(component);
 }
 
 /// Teardown initialized `libpostal` components.
@@ -158,19 +272,7 @@ pub unsafe fn setup(component: LibModules) {
     note = "This can be handled by the `Drop` trait when `LibModules` values go out of scope"
 )]
 pub unsafe fn teardown(component: LibModules) {
-    ffi::libpostal_teardown();
-    match component {
-        Expand => {
-            teardown_classifier();
-        }
-        Address => {
-            teardown_parser();
-        }
-        All => {
-            teardown_parser();
-            teardown_classifier();
-        }
-    }
+    drop(component);
 }
 
 #[cfg(test)]
@@ -178,20 +280,61 @@ mod tests {
     use super::*;
 
     #[test]
+    #[cfg(feature = "expand")]
     fn libmodules_setup_expand() {
         let postal_module = Expand;
         assert!(postal_module.setup().is_ok());
     }
 
     #[test]
+    #[cfg(feature = "parser")]
     fn libmodules_setup_parser() {
         let postal_module = Address;
         assert!(postal_module.setup().is_ok());
     }
 
+    #[test]
+    #[cfg(feature = "parser")]
+    fn libmodules_models_parser() {
+        assert_eq!(Address.models(), Models::BASE | Models::PARSER);
+        assert!(All.models().contains(Models::PARSER));
+    }
+
+    #[test]
+    #[cfg(feature = "expand")]
+    fn libmodules_models_classifier() {
+        assert_eq!(Expand.models(), Models::BASE | Models::CLASSIFIER);
+        assert!(All.models().contains(Models::CLASSIFIER));
+    }
+
     #[test]
     fn libmodules_setup_all() {
         let postal_module = All;
         assert!(postal_module.setup().is_ok());
     }
+
+    #[test]
+    #[cfg(feature = "parser")]
+    fn libmodules_drop_without_setup() -> Result<(), SetupError> {
+        models::load(Models::PARSER)?;
+        drop(Address);
+        assert!(models::loaded().contains(Models::BASE | Models::PARSER));
+        models::unload(Models::PARSER);
+        Ok(())
+    }
+
+    #[test]
+    fn libmodules_setup_guarded() {
+        let models = All.setup_guarded();
+        assert!(models.is_ok());
+        assert!(models::loaded().contains(Models::BASE));
+    }
+
+    #[test]
+    fn libmodules_setup_with_missing_datadir() {
+        let postal_module = All;
+        assert!(postal_module
+            .setup_with_datadir("/nonexistent/libpostal")
+            .is_err());
+    }
 }
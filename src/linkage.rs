@@ -0,0 +1,492 @@
+//! Record linkage between two address datasets.
+//!
+//! Every address is parsed once. Candidate pairs are the pairs sharing a
+//! near-dupe hash, so that only similar addresses are compared. Each
+//! candidate pair is compared field by field with the pairwise duplicate
+//! checks of [`dedupe`](crate::dedupe), and the statuses are combined into a
+//! weighted score between 0 and 1.
+//!
+//! # Examples
+//!
+//! ```
+//! use rustpostal::error::RuntimeError;
+//! use rustpostal::linkage::LinkageOptions;
+//! use rustpostal::LibModules;
+//!
+//! fn main() -> Result<(), RuntimeError> {
+//!     let postal_module = LibModules::All;
+//!     postal_module.setup()?;
+//!
+//!     let customers = vec![(1, "660 Nostrand Ave, Brooklyn, NY 11216")];
+//!     let reference = vec![
+//!         ("a", "660 Nostrand Avenue, Brooklyn, NY 11216"),
+//!         ("b", "1 Main St, Springfield"),
+//!     ];
+//!     let options = LinkageOptions::new(Some(["en"].iter()))?;
+//!     for linked in options.link(customers, reference)? {
+//!         println!("{} -> {} ({:.2})", linked.left, linked.right, linked.score);
+//!         for comparison in &linked.comparisons {
+//!             println!("  {}", comparison);
+//!         }
+//!     }
+//!     Ok(())
+//! }
+//! ```
+use std::collections::{BTreeSet, HashMap};
+use std::ffi::NulError;
+use std::fmt;
+
+use crate::address::{AddressLabel, AddressParserOptions, AddressParserResponse};
+use crate::dedupe::{DuplicateOptions, DuplicateStatus, NearDupeHashOptions};
+
+/// Fields compared between two addresses.
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Field {
+    /// Venue or building name.
+    Name,
+    HouseNumber,
+    Road,
+    Unit,
+    Level,
+    PoBox,
+    Postcode,
+    /// The containing places, i.e. suburb, city, state, country etc.
+    Toponyms,
+}
+
+impl Field {
+    /// All fields, in comparison order.
+    pub const ALL: &'static [Field] = &[
+        Field::Name,
+        Field::HouseNumber,
+        Field::Road,
+        Field::Unit,
+        Field::Level,
+        Field::PoBox,
+        Field::Postcode,
+        Field::Toponyms,
+    ];
+
+    /// Return the snake case name of the field.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Field::Name => "name",
+            Field::HouseNumber => "house_number",
+            Field::Road => "road",
+            Field::Unit => "unit",
+            Field::Level => "level",
+            Field::PoBox => "po_box",
+            Field::Postcode => "postcode",
+            Field::Toponyms => "toponyms",
+        }
+    }
+
+    /// The default weight of the field in the match score.
+    pub fn default_weight(&self) -> f64 {
+        match self {
+            Field::HouseNumber | Field::Road => 3.0,
+            Field::PoBox | Field::Postcode => 2.0,
+            Field::Name | Field::Unit | Field::Toponyms => 1.0,
+            Field::Level => 0.5,
+        }
+    }
+
+    /// The labels of the components making up the field.
+    fn labels(&self) -> &'static [AddressLabel] {
+        match self {
+            Field::Name => &[AddressLabel::House],
+            Field::HouseNumber => &[AddressLabel::HouseNumber],
+            Field::Road => &[AddressLabel::Road],
+            Field::Unit => &[AddressLabel::Unit],
+            Field::Level => &[AddressLabel::Level],
+            Field::PoBox => &[AddressLabel::PoBox],
+            Field::Postcode => &[AddressLabel::Postcode],
+            Field::Toponyms => TOPONYM_LABELS,
+        }
+    }
+}
+
+impl fmt::Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Labels compared with [`DuplicateOptions::is_toponym_duplicate`].
+const TOPONYM_LABELS: &[AddressLabel] = &[
+    AddressLabel::Suburb,
+    AddressLabel::CityDistrict,
+    AddressLabel::City,
+    AddressLabel::StateDistrict,
+    AddressLabel::Island,
+    AddressLabel::State,
+    AddressLabel::CountryRegion,
+    AddressLabel::Country,
+    AddressLabel::WorldRegion,
+];
+
+/// The contribution of a duplicate status to the match score, from 0 to 1.
+///
+/// `Null` statuses are not scored at all.
+fn status_score(status: DuplicateStatus) -> Option<f64> {
+    match status {
+        DuplicateStatus::Null => None,
+        DuplicateStatus::NonDuplicate => Some(0.0),
+        DuplicateStatus::PossibleDuplicateNeedsReview => Some(0.5),
+        DuplicateStatus::LikelyDuplicate => Some(0.9),
+        DuplicateStatus::ExactDuplicate => Some(1.0),
+    }
+}
+
+/// The comparison of a field present in both addresses.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FieldComparison {
+    pub field: Field,
+    /// The value of the left address.
+    pub left: String,
+    /// The value of the right address.
+    pub right: String,
+    pub status: DuplicateStatus,
+    pub weight: f64,
+}
+
+impl fmt::Display for FieldComparison {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}: {:?} ~ {:?}: {:?} (weight {})",
+            self.field, self.left, self.right, self.status, self.weight
+        )
+    }
+}
+
+/// The weighted mean of the scores of the compared fields.
+///
+/// It is 0 if no field could be compared.
+fn weighted_score(comparisons: &[FieldComparison]) -> f64 {
+    let (total, weights) = comparisons
+        .iter()
+        .filter_map(|c| status_score(c.status).map(|score| (score * c.weight, c.weight)))
+        .fold((0.0, 0.0), |(total, weights), (score, weight)| {
+            (total + score, weights + weight)
+        });
+    if weights > 0.0 {
+        total / weights
+    } else {
+        0.0
+    }
+}
+
+/// A matched pair of records, with the comparisons explaining its score.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Match<L, R> {
+    /// The id of the left record.
+    pub left: L,
+    /// The id of the right record.
+    pub right: R,
+    /// The weighted score, from 0 to 1.
+    pub score: f64,
+    /// The comparisons of the fields present in both addresses.
+    pub comparisons: Vec<FieldComparison>,
+}
+
+/// A parsed record.
+pub(crate) struct Record<K> {
+    pub(crate) id: K,
+    pub(crate) response: AddressParserResponse,
+}
+
+impl<K> Record<K> {
+    fn value(&self, field: Field) -> Option<String> {
+        self.response.get(field.labels()[0])
+    }
+
+    fn toponyms(&self) -> Vec<(&String, &String)> {
+        (&self.response)
+            .into_iter()
+            .filter(|(label, _)| TOPONYM_LABELS.iter().any(|t| t.as_str() == label.as_str()))
+            .collect()
+    }
+}
+
+/// Options for linking two address datasets.
+#[derive(Clone, Debug)]
+pub struct LinkageOptions {
+    parser: AddressParserOptions,
+    hashes: NearDupeHashOptions,
+    duplicates: DuplicateOptions,
+    weights: HashMap<Field, f64>,
+    threshold: f64,
+}
+
+impl LinkageOptions {
+    /// Create new instance with the default weights and a threshold of 0.8.
+    ///
+    /// `languages` are used for normalization; if not given, they are
+    /// classified from the addresses.
+    pub fn new<'a, 'b, T>(languages: Option<T>) -> Result<LinkageOptions, NulError>
+    where
+        'a: 'b,
+        T: Iterator<Item = &'b &'a str> + Clone,
+    {
+        Ok(LinkageOptions {
+            parser: AddressParserOptions::new(None, None)?,
+            hashes: NearDupeHashOptions::new(languages.clone())?,
+            duplicates: DuplicateOptions::new(languages)?,
+            weights: Field::ALL
+                .iter()
+                .map(|&field| (field, field.default_weight()))
+                .collect(),
+            threshold: 0.8,
+        })
+    }
+
+    /// Set the options used to parse both datasets.
+    pub fn set_parser_options(&mut self, options: AddressParserOptions) {
+        self.parser = options;
+    }
+
+    /// Set the near-dupe hash options used for blocking.
+    pub fn set_hash_options(&mut self, options: NearDupeHashOptions) {
+        self.hashes = options;
+    }
+
+    /// Set the weight of `field` in the match score; 0 skips the field, as
+    /// do negative and non-finite weights.
+    pub fn set_weight(&mut self, field: Field, weight: f64) {
+        let weight = if weight.is_finite() {
+            weight.max(0.0)
+        } else {
+            0.0
+        };
+        self.weights.insert(field, weight);
+    }
+
+    /// Set the minimum score of a match.
+    pub fn set_threshold(&mut self, threshold: f64) {
+        self.threshold = threshold;
+    }
+
+    /// Return the weight of `field`.
+    pub fn weight(&self, field: Field) -> f64 {
+        self.weights.get(&field).copied().unwrap_or(0.0)
+    }
+
+    /// Return current threshold.
+    pub fn threshold(&self) -> f64 {
+        self.threshold
+    }
+
+    pub(crate) fn parse<K, A, I>(&self, records: I) -> Result<Vec<Record<K>>, NulError>
+    where
+        I: IntoIterator<Item = (K, A)>,
+        A: AsRef<str>,
+    {
+        records
+            .into_iter()
+            .map(|(id, address)| {
+                Ok(Record {
+                    id,
+                    response: self.parser.parse(address.as_ref())?,
+                })
+            })
+            .collect()
+    }
+
+    fn compare_field<L, R>(
+        &self,
+        field: Field,
+        left: &Record<L>,
+        right: &Record<R>,
+    ) -> Result<Option<FieldComparison>, NulError> {
+        let weight = self.weight(field);
+        if weight <= 0.0 {
+            return Ok(None);
+        }
+        let (values, status) = if field == Field::Toponyms {
+            let (left, right) = (left.toponyms(), right.toponyms());
+            if left.is_empty() || right.is_empty() {
+                return Ok(None);
+            }
+            let status = self
+                .duplicates
+                .is_toponym_duplicate(left.iter().copied(), right.iter().copied())?;
+            let join = |toponyms: &[(&String, &String)]| {
+                toponyms
+                    .iter()
+                    .map(|(_, value)| value.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+            ((join(&left), join(&right)), status)
+        } else {
+            let (left, right) = match (left.value(field), right.value(field)) {
+                (Some(left), Some(right)) => (left, right),
+                _ => return Ok(None),
+            };
+            let options = &self.duplicates;
+            let status = match field {
+                Field::Name => options.is_name_duplicate(&left, &right)?,
+                Field::HouseNumber => options.is_house_number_duplicate(&left, &right)?,
+                Field::Road => options.is_street_duplicate(&left, &right)?,
+                Field::Unit => options.is_unit_duplicate(&left, &right)?,
+                Field::Level => options.is_floor_duplicate(&left, &right)?,
+                Field::PoBox => options.is_po_box_duplicate(&left, &right)?,
+                Field::Postcode => options.is_postal_code_duplicate(&left, &right)?,
+                Field::Toponyms => unreachable!(),
+            };
+            ((left, right), status)
+        };
+        Ok(Some(FieldComparison {
+            field,
+            left: values.0,
+            right: values.1,
+            status,
+            weight,
+        }))
+    }
+
+    /// Compare the fields present in both records.
+    fn compare<L, R>(
+        &self,
+        left: &Record<L>,
+        right: &Record<R>,
+    ) -> Result<Vec<FieldComparison>, NulError> {
+        let mut comparisons = Vec::new();
+        for &field in Field::ALL {
+            if let Some(comparison) = self.compare_field(field, left, right)? {
+                comparisons.push(comparison);
+            }
+        }
+        Ok(comparisons)
+    }
+
+    /// The near-dupe hashes of a record, used as blocking keys.
+    pub(crate) fn blocking_keys<K>(&self, record: &Record<K>) -> Result<Vec<String>, NulError> {
+        self.hashes.hashes(&record.response)
+    }
+
+    /// Compare two records, returning the comparisons if they match.
+    pub(crate) fn matches<L, R>(
+        &self,
+        left: &Record<L>,
+        right: &Record<R>,
+    ) -> Result<Option<(f64, Vec<FieldComparison>)>, NulError> {
+        let comparisons = self.compare(left, right)?;
+        let score = weighted_score(&comparisons);
+        Ok(if score >= self.threshold {
+            Some((score, comparisons))
+        } else {
+            None
+        })
+    }
+
+    /// Link the `(id, address)` records of `left` to those of `right`.
+    ///
+    /// Every pair scoring at least the threshold is returned, in the order of
+    /// the left records and then by decreasing score, so a record may match
+    /// more than one record of the other dataset.
+    ///
+    /// # Errors
+    ///
+    /// It will return an error if an address contains an internal null byte.
+    pub fn link<L, R, A, B, I, J>(&self, left: I, right: J) -> Result<Vec<Match<L, R>>, NulError>
+    where
+        L: Clone,
+        R: Clone,
+        A: AsRef<str>,
+        B: AsRef<str>,
+        I: IntoIterator<Item = (L, A)>,
+        J: IntoIterator<Item = (R, B)>,
+    {
+        let right = self.parse(right)?;
+        let mut blocks: HashMap<String, Vec<usize>> = HashMap::new();
+        for (index, record) in right.iter().enumerate() {
+            for hash in self.blocking_keys(record)? {
+                blocks.entry(hash).or_default().push(index);
+            }
+        }
+
+        let mut matches = Vec::new();
+        for record in self.parse(left)? {
+            let mut candidates = BTreeSet::new();
+            for hash in self.blocking_keys(&record)? {
+                if let Some(indices) = blocks.get(&hash) {
+                    candidates.extend(indices.iter().copied());
+                }
+            }
+            let mut linked = Vec::new();
+            for index in candidates {
+                let candidate = &right[index];
+                if let Some((score, comparisons)) = self.matches(&record, candidate)? {
+                    linked.push(Match {
+                        left: record.id.clone(),
+                        right: candidate.id.clone(),
+                        score,
+                        comparisons,
+                    });
+                }
+            }
+            linked.sort_by(|a, b| b.score.total_cmp(&a.score));
+            matches.extend(linked);
+        }
+        Ok(matches)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comparison(field: Field, status: DuplicateStatus) -> FieldComparison {
+        FieldComparison {
+            field,
+            left: String::new(),
+            right: String::new(),
+            status,
+            weight: field.default_weight(),
+        }
+    }
+
+    #[test]
+    fn weighted_score_of_comparisons() {
+        let comparisons = [
+            comparison(Field::HouseNumber, DuplicateStatus::ExactDuplicate),
+            comparison(Field::Road, DuplicateStatus::LikelyDuplicate),
+            comparison(Field::Postcode, DuplicateStatus::NonDuplicate),
+            comparison(Field::Toponyms, DuplicateStatus::Null),
+        ];
+        let expected = (3.0 + 3.0 * 0.9) / 8.0;
+        assert!((weighted_score(&comparisons) - expected).abs() < 1e-9);
+        assert_eq!(weighted_score(&[]), 0.0);
+    }
+
+    #[test]
+    fn linkage_options_weights() -> Result<(), NulError> {
+        let mut options = LinkageOptions::new(Some(["en"].iter()))?;
+        assert_eq!(options.weight(Field::Road), 3.0);
+        options.set_weight(Field::Road, -1.0);
+        assert_eq!(options.weight(Field::Road), 0.0);
+        options.set_weight(Field::Road, f64::INFINITY);
+        assert_eq!(options.weight(Field::Road), 0.0);
+        options.set_weight(Field::Road, f64::NAN);
+        assert_eq!(options.weight(Field::Road), 0.0);
+        options.set_threshold(0.5);
+        assert_eq!(options.threshold(), 0.5);
+        Ok(())
+    }
+
+    #[test]
+    fn field_names() {
+        let names: Vec<&str> = Field::ALL.iter().map(Field::as_str).collect();
+        assert_eq!(names[1], "house_number");
+        assert_eq!(Field::Toponyms.to_string(), "toponyms");
+    }
+}
@@ -0,0 +1,398 @@
+//! Loading, unloading and memory accounting of the `libpostal` models.
+//!
+//! `libpostal` keeps its models in process-wide state. The functions here
+//! reference count every model, so that independent parts of a program can
+//! load what they need and unload it again without tearing down models that
+//! are still in use elsewhere.
+//!
+//! # Examples
+//!
+//! ```
+//! use rustpostal::error::SetupError;
+//! use rustpostal::models::{self, Models};
+//!
+//! fn main() -> Result<(), SetupError> {
+//!     // Expansion with explicit languages does not need the classifier.
+//!     models::load(Models::BASE)?;
+//!     assert!(models::loaded().contains(Models::BASE));
+//!
+//!     println!("{} bytes", models::memory_usage().total());
+//!
+//!     models::unload(Models::BASE);
+//!     Ok(())
+//! }
+//! ```
+use std::ffi::CString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+use bitflags::bitflags;
+use libc::c_char;
+
+use crate::error::SetupError;
+use crate::ffi;
+
+/// The data directory `libpostal` loads from when none is given explicitly.
+///
+/// Taken from `LIBPOSTAL_DATA_DIR` at build time, as for the `vendored` build.
+pub const DEFAULT_DATA_DIR: &str = match option_env!("LIBPOSTAL_DATA_DIR") {
+    Some(datadir) => datadir,
+    None => "/usr/local/share/libpostal",
+};
+
+bitflags! {
+    /// Bit set of `libpostal` models.
+    ///
+    /// The parser and the classifier depend on the base model, so loading or
+    /// unloading either of them implies [`BASE`](Models::BASE) as well.
+    pub struct Models: u8 {
+        /// Transliteration tables, address dictionaries and numeric
+        /// expressions. Enough for [`expand`](crate::expand) and
+        /// [`dedupe`](crate::dedupe) when languages are given explicitly.
+        const BASE = 1 << 0;
+        /// The address parser CRF model.
+        const PARSER = 1 << 1;
+        /// The language classifier.
+        const CLASSIFIER = 1 << 2;
+    }
+}
+
+impl Models {
+    fn with_base(self) -> Models {
+        if self.is_empty() {
+            self
+        } else {
+            self | Models::BASE
+        }
+    }
+}
+
+/// Estimated memory used by the `libpostal` models, in bytes.
+///
+/// The estimate is the size of the data files each model reads in full
+/// during setup.
+#[derive(Clone, Copy, Default, Debug, Hash, Eq, PartialEq)]
+pub struct MemoryUsage {
+    /// The address parser CRF model, vocabulary and phrases.
+    pub parser: u64,
+    /// The language classifier.
+    pub classifier: u64,
+    /// The transliteration tables, part of the base model.
+    pub transliteration: u64,
+    /// The address dictionaries, part of the base model.
+    pub address_dictionary: u64,
+    /// The numeric expressions, part of the base model.
+    pub numex: u64,
+}
+
+impl MemoryUsage {
+    /// Estimate the memory used by `models` when loaded from `datadir`.
+    ///
+    /// Missing data files count as zero.
+    pub fn estimate<P: AsRef<Path>>(models: Models, datadir: P) -> MemoryUsage {
+        let datadir = datadir.as_ref();
+        let models = models.with_base();
+        let mut usage = MemoryUsage::default();
+        if models.contains(Models::BASE) {
+            usage.transliteration = dir_size(&datadir.join("transliteration"));
+            usage.address_dictionary = dir_size(&datadir.join("address_expansions"));
+            usage.numex = dir_size(&datadir.join("numex"));
+        }
+        if models.contains(Models::PARSER) {
+            usage.parser = dir_size(&datadir.join("address_parser"));
+        }
+        if models.contains(Models::CLASSIFIER) {
+            usage.classifier = dir_size(&datadir.join("language_classifier"));
+        }
+        usage
+    }
+
+    /// The total in bytes.
+    pub fn total(&self) -> u64 {
+        self.parser + self.classifier + self.transliteration + self.address_dictionary + self.numex
+    }
+}
+
+fn dir_size(dir: &Path) -> u64 {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .filter_map(|entry| entry.metadata().ok())
+                .filter(|metadata| metadata.is_file())
+                .map(|metadata| metadata.len())
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+/// Reference counts of the loaded models.
+struct Registry {
+    base: usize,
+    parser: usize,
+    classifier: usize,
+    datadir: Option<PathBuf>,
+    /// The models of each [`LibModules::setup`](crate::LibModules::setup)
+    /// not yet released by dropping a `LibModules` value.
+    modules: Vec<Models>,
+}
+
+static REGISTRY: Mutex<Registry> = Mutex::new(Registry {
+    base: 0,
+    parser: 0,
+    classifier: 0,
+    datadir: None,
+    modules: Vec::new(),
+});
+
+fn registry() -> MutexGuard<'static, Registry> {
+    REGISTRY
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+impl Registry {
+    fn count(&mut self, model: Models) -> &mut usize {
+        match model {
+            Models::PARSER => &mut self.parser,
+            Models::CLASSIFIER => &mut self.classifier,
+            _ => &mut self.base,
+        }
+    }
+}
+
+/// Models in the order they have to be set up.
+const SETUP_ORDER: [Models; 3] = [Models::BASE, Models::PARSER, Models::CLASSIFIER];
+
+unsafe fn setup(model: Models, datadir: Option<*mut c_char>) -> bool {
+    match (model, datadir) {
+        (Models::PARSER, None) => ffi::libpostal_setup_parser(),
+        (Models::PARSER, Some(ptr)) => ffi::libpostal_setup_parser_datadir(ptr),
+        (Models::CLASSIFIER, None) => ffi::libpostal_setup_language_classifier(),
+        (Models::CLASSIFIER, Some(ptr)) => ffi::libpostal_setup_language_classifier_datadir(ptr),
+        (_, None) => ffi::libpostal_setup(),
+        (_, Some(ptr)) => ffi::libpostal_setup_datadir(ptr),
+    }
+}
+
+unsafe fn teardown(model: Models) {
+    match model {
+        Models::PARSER => ffi::libpostal_teardown_parser(),
+        Models::CLASSIFIER => ffi::libpostal_teardown_language_classifier(),
+        _ => ffi::libpostal_teardown(),
+    }
+}
+
+fn load_from(models: Models, datadir: Option<&Path>) -> Result<(), SetupError> {
+    let c_datadir = match datadir {
+        Some(datadir) => {
+            let datadir = datadir.to_str().ok_or(SetupError)?;
+            Some(CString::new(datadir).map_err(|_| SetupError)?)
+        }
+        None => None,
+    };
+    let ptr = c_datadir.as_ref().map(|s| s.as_ptr() as *mut c_char);
+
+    let models = models.with_base();
+    let mut registry = registry();
+    let mut acquired = Models::empty();
+    for &model in SETUP_ORDER.iter().filter(|&&m| models.contains(m)) {
+        if *registry.count(model) == 0 && !unsafe { setup(model, ptr) } {
+            release(&mut registry, acquired);
+            return Err(SetupError);
+        }
+        *registry.count(model) += 1;
+        acquired |= model;
+    }
+    if datadir.is_some() {
+        registry.datadir = datadir.map(Path::to_path_buf);
+    }
+    Ok(())
+}
+
+fn release(registry: &mut Registry, models: Models) {
+    for &model in SETUP_ORDER.iter().rev().filter(|&&m| models.contains(m)) {
+        let count = registry.count(model);
+        if *count == 0 {
+            continue;
+        }
+        *count -= 1;
+        if *count == 0 {
+            unsafe { teardown(model) };
+        }
+    }
+}
+
+/// Load `models` from the default data directory.
+///
+/// Models that are already loaded are only reference counted.
+///
+/// # Errors
+///
+/// It will return an error if any of the models fails to load, in which
+/// case none of `models` is acquired.
+pub fn load(models: Models) -> Result<(), SetupError> {
+    load_from(models, None)
+}
+
+/// Load `models` from `datadir`.
+///
+/// Models that are already loaded are only reference counted, and keep
+/// the data they were loaded with.
+///
+/// # Errors
+///
+/// It will return an error if `datadir` is not valid UTF-8 or if any of the
+/// models fails to load, in which case none of `models` is acquired.
+pub fn load_with_datadir<P: AsRef<Path>>(models: Models, datadir: P) -> Result<(), SetupError> {
+    load_from(models, Some(datadir.as_ref()))
+}
+
+/// Release `models`, tearing down those no longer in use.
+///
+/// Models that are not loaded are ignored.
+pub fn unload(models: Models) {
+    release(&mut registry(), models.with_base());
+}
+
+/// Load `models` on behalf of a `LibModules` value, see
+/// [`release_module`].
+pub(crate) fn load_module(models: Models, datadir: Option<&Path>) -> Result<(), SetupError> {
+    load_from(models, datadir)?;
+    registry().modules.push(models);
+    Ok(())
+}
+
+/// Release the models of a `LibModules` value, if a value with the same
+/// models was set up and not yet released.
+pub(crate) fn release_module(models: Models) {
+    let mut registry = registry();
+    if let Some(index) = registry.modules.iter().position(|&m| m == models) {
+        registry.modules.swap_remove(index);
+        release(&mut registry, models.with_base());
+    }
+}
+
+/// Models held for as long as the guard is alive.
+///
+/// The models are released when the guard is dropped, and torn down if no
+/// other guard or [`load`] still uses them.
+#[must_use = "the models are released when the guard is dropped"]
+#[derive(Debug, Eq, PartialEq)]
+pub struct ModelsGuard {
+    models: Models,
+}
+
+impl ModelsGuard {
+    /// Load `models` from the default data directory, see [`load`].
+    ///
+    /// # Errors
+    ///
+    /// It will return an error if any of the models fails to load.
+    pub fn load(models: Models) -> Result<ModelsGuard, SetupError> {
+        load(models)?;
+        Ok(ModelsGuard { models })
+    }
+
+    /// Load `models` from `datadir`, see [`load_with_datadir`].
+    ///
+    /// # Errors
+    ///
+    /// It will return an error if `datadir` is not valid UTF-8 or if any of
+    /// the models fails to load.
+    pub fn load_with_datadir<P: AsRef<Path>>(
+        models: Models,
+        datadir: P,
+    ) -> Result<ModelsGuard, SetupError> {
+        load_with_datadir(models, datadir)?;
+        Ok(ModelsGuard { models })
+    }
+
+    /// Return the models held by the guard.
+    pub fn models(&self) -> Models {
+        self.models
+    }
+}
+
+impl Drop for ModelsGuard {
+    fn drop(&mut self) {
+        unload(self.models);
+    }
+}
+
+/// Return the models currently loaded.
+pub fn loaded() -> Models {
+    let registry = registry();
+    let mut models = Models::empty();
+    models.set(Models::BASE, registry.base > 0);
+    models.set(Models::PARSER, registry.parser > 0);
+    models.set(Models::CLASSIFIER, registry.classifier > 0);
+    models
+}
+
+/// Estimate the memory used by the models currently loaded.
+///
+/// See [`MemoryUsage::estimate`].
+pub fn memory_usage() -> MemoryUsage {
+    let datadir = registry().datadir.clone();
+    let datadir = datadir.unwrap_or_else(|| PathBuf::from(DEFAULT_DATA_DIR));
+    MemoryUsage::estimate(loaded(), datadir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn models_with_base() {
+        assert_eq!(Models::empty().with_base(), Models::empty());
+        assert_eq!(Models::PARSER.with_base(), Models::BASE | Models::PARSER);
+        assert_eq!(
+            Models::CLASSIFIER.with_base(),
+            Models::BASE | Models::CLASSIFIER
+        );
+    }
+
+    #[test]
+    fn memory_usage_estimate() {
+        let datadir = std::env::temp_dir().join("rustpostal-models-estimate");
+        fs::create_dir_all(datadir.join("address_parser")).unwrap();
+        fs::create_dir_all(datadir.join("numex")).unwrap();
+        fs::write(
+            datadir.join("address_parser/address_parser_crf.dat"),
+            [0; 64],
+        )
+        .unwrap();
+        fs::write(datadir.join("numex/numex.dat"), [0; 8]).unwrap();
+
+        let usage = MemoryUsage::estimate(Models::PARSER, &datadir);
+        assert_eq!(usage.parser, 64);
+        assert_eq!(usage.numex, 8);
+        assert_eq!(usage.classifier, 0);
+        assert_eq!(usage.total(), 72);
+        assert_eq!(MemoryUsage::estimate(Models::BASE, &datadir).total(), 8);
+    }
+
+    #[test]
+    fn load_with_missing_datadir() {
+        assert!(load_with_datadir(Models::all(), "/nonexistent/libpostal").is_err());
+    }
+
+    #[test]
+    fn models_guard_holds_models() -> Result<(), SetupError> {
+        let guard = ModelsGuard::load(Models::PARSER)?;
+        assert_eq!(guard.models(), Models::PARSER);
+        assert!(loaded().contains(Models::BASE | Models::PARSER));
+        drop(guard);
+        Ok(())
+    }
+
+    #[test]
+    fn load_and_unload_parser() -> Result<(), SetupError> {
+        load(Models::PARSER)?;
+        assert!(loaded().contains(Models::BASE | Models::PARSER));
+        unload(Models::PARSER);
+        Ok(())
+    }
+}
@@ -0,0 +1,270 @@
+//! Validation and normalization of postcodes by country.
+//!
+//! Each supported country has a list of postcode formats, written as masks
+//! where `#` is a digit, `@` a letter, `*` either, and any other character
+//! is matched literally. Spaces and hyphens in the masks are the canonical
+//! separators; they are optional in the input, as is the case of letters,
+//! so the lowercased output of the parser is accepted.
+//!
+//! Countries are given as ISO 3166-1 alpha-2 codes, ignoring case.
+//!
+//! # Examples
+//!
+//! ```
+//! use rustpostal::postcode;
+//!
+//! assert!(postcode::validate("gb", "mk42 0xe"));
+//! assert_eq!(postcode::normalize("GB", "mk420xe").as_deref(), Some("MK42 0XE"));
+//! assert_eq!(postcode::normalize("US", "2134").as_deref(), Some("02134"));
+//! assert_eq!(postcode::normalize("NL", "1234ab").as_deref(), Some("1234 AB"));
+//! assert_eq!(postcode::normalize("CA", "k1a0b1").as_deref(), Some("K1A 0B1"));
+//! assert_eq!(postcode::normalize("US", "1234567"), None);
+//! ```
+
+/// The postcode formats of a country.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PostcodeFormat {
+    /// ISO 3166-1 alpha-2 code of the country.
+    pub country: &'static str,
+    /// The masks of the valid postcodes, in canonical form.
+    pub masks: &'static [&'static str],
+    /// Whether numeric postcodes may have lost their leading zeros, e.g.
+    /// in spreadsheets, and are padded back.
+    pub leading_zeros: bool,
+}
+
+macro_rules! formats {
+    ($(($country:expr, [$($mask:expr),+ $(,)?], $leading_zeros:expr)),+ $(,)?) => {
+        &[$(PostcodeFormat {
+            country: $country,
+            masks: &[$($mask),+],
+            leading_zeros: $leading_zeros,
+        }),+]
+    };
+}
+
+/// The bundled formats, by country code.
+const FORMATS: &[PostcodeFormat] = formats![
+    ("AR", ["@####@@@", "####"], false),
+    ("AT", ["####"], false),
+    ("AU", ["####"], true),
+    ("BE", ["####"], false),
+    ("BR", ["#####-###"], true),
+    ("CA", ["@#@ #@#"], false),
+    ("CH", ["####"], false),
+    ("CN", ["######"], true),
+    ("CZ", ["### ##"], false),
+    ("DE", ["#####"], true),
+    ("DK", ["####"], false),
+    ("ES", ["#####"], true),
+    ("FI", ["#####"], true),
+    ("FR", ["#####"], true),
+    (
+        "GB",
+        ["@# #@@", "@## #@@", "@@# #@@", "@@## #@@", "@#@ #@@", "@@#@ #@@", "GIR 0AA",],
+        false
+    ),
+    ("GR", ["### ##"], false),
+    ("HU", ["####"], false),
+    ("IE", ["@## ****", "@#@ ****"], false),
+    ("IL", ["#######"], false),
+    ("IN", ["######"], false),
+    ("IT", ["#####"], true),
+    ("JP", ["###-####"], true),
+    ("KR", ["#####"], true),
+    ("LU", ["####"], false),
+    ("MX", ["#####"], true),
+    ("NL", ["#### @@"], false),
+    ("NO", ["####"], true),
+    ("NZ", ["####"], true),
+    ("PL", ["##-###"], true),
+    ("PT", ["####-###"], false),
+    ("RU", ["######"], false),
+    ("SE", ["### ##"], false),
+    ("SG", ["######"], true),
+    ("SK", ["### ##"], false),
+    ("TR", ["#####"], true),
+    ("TW", ["###", "#####"], false),
+    ("UA", ["#####"], true),
+    ("US", ["#####", "#####-####"], true),
+    ("ZA", ["####"], true),
+];
+
+/// Return the postcode formats of `country`, if it is supported.
+pub fn format(country: &str) -> Option<&'static PostcodeFormat> {
+    let country = country.trim();
+    FORMATS
+        .iter()
+        .find(|format| format.country.eq_ignore_ascii_case(country))
+}
+
+fn is_separator(c: char) -> bool {
+    c == ' ' || c == '-'
+}
+
+/// Match the separator-free `code` against `mask`, returning it formatted.
+fn apply(mask: &str, code: &[char]) -> Option<String> {
+    let slots = mask.chars().filter(|&c| !is_separator(c)).count();
+    if slots != code.len() {
+        return None;
+    }
+    let mut code = code.iter();
+    let mut formatted = String::with_capacity(mask.len());
+    for slot in mask.chars() {
+        if is_separator(slot) {
+            formatted.push(slot);
+            continue;
+        }
+        let c = *code.next()?;
+        let matches = match slot {
+            '#' => c.is_ascii_digit(),
+            '@' => c.is_ascii_alphabetic(),
+            '*' => c.is_ascii_alphanumeric(),
+            literal => c == literal,
+        };
+        if !matches {
+            return None;
+        }
+        formatted.push(c);
+    }
+    Some(formatted)
+}
+
+/// Return the separator-free, uppercase characters of `code`.
+fn compact(code: &str) -> Vec<char> {
+    code.trim()
+        .chars()
+        .filter(|&c| !is_separator(c))
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+/// Return the countries whose formats `code` matches, ignoring case and
+/// separators but not missing leading zeros.
+///
+/// Shapes such as "MK42 0XE" match a single country, while five digits
+/// match many.
+pub fn countries(code: &str) -> Vec<&'static str> {
+    let compact = compact(code);
+    FORMATS
+        .iter()
+        .filter(|format| {
+            format
+                .masks
+                .iter()
+                .any(|mask| apply(mask, &compact).is_some())
+        })
+        .map(|format| format.country)
+        .collect()
+}
+
+/// Return the canonical form of a postcode of `country`, if it is valid.
+///
+/// Letters are uppercased, separators are those of the matching format,
+/// and numeric postcodes of countries using leading zeros are padded back
+/// to the length of their primary, first format.
+pub fn normalize(country: &str, code: &str) -> Option<String> {
+    let format = format(country)?;
+    let compact = compact(code);
+    if compact.is_empty() {
+        return None;
+    }
+    if let Some(formatted) = format.masks.iter().find_map(|mask| apply(mask, &compact)) {
+        return Some(formatted);
+    }
+    let mask = format.masks[0];
+    let slots = mask.chars().filter(|&c| !is_separator(c)).count();
+    if format.leading_zeros
+        && slots > compact.len()
+        && slots - compact.len() <= 2
+        && compact.iter().all(char::is_ascii_digit)
+    {
+        let mut padded = vec!['0'; slots - compact.len()];
+        padded.extend(&compact);
+        return apply(mask, &padded);
+    }
+    None
+}
+
+/// Check whether `code` is a valid postcode of `country`.
+///
+/// Case, spacing and hyphens are ignored, and so are missing leading
+/// zeros for countries using them. Unsupported countries are never valid.
+pub fn validate(country: &str, code: &str) -> bool {
+    normalize(country, code).is_some()
+}
+
+/// Restore the case of a postcode, e.g. from the lowercased parser output.
+///
+/// Valid postcodes of supported countries are normalized; other postcodes
+/// are uppercased with their whitespace collapsed.
+pub fn canonical_case(country: Option<&str>, code: &str) -> String {
+    if let Some(normalized) = country.and_then(|country| normalize(country, code)) {
+        return normalized;
+    }
+    code.split_whitespace()
+        .map(str::to_uppercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_postcodes() {
+        let cases = [
+            ("gb", "mk42 0xe", Some("MK42 0XE")),
+            ("GB", "SW1A1AA", Some("SW1A 1AA")),
+            ("GB", "gir0aa", Some("GIR 0AA")),
+            ("GB", "12345", None),
+            ("US", "12345-6789", Some("12345-6789")),
+            ("US", "123456789", Some("12345-6789")),
+            ("US", "501", Some("00501")),
+            ("US", "1", None),
+            ("US", "1234567", None),
+            ("NL", "1234 ab", Some("1234 AB")),
+            ("NL", "ab 1234", None),
+            ("CA", "K1A-0B1", Some("K1A 0B1")),
+            ("JP", "1000001", Some("100-0001")),
+            ("IE", "d6w xy12", Some("D6W XY12")),
+            ("XX", "12345", None),
+            ("DE", "   ", None),
+        ];
+        for (country, code, expected) in cases.iter() {
+            assert_eq!(
+                normalize(country, code).as_deref(),
+                *expected,
+                "{} {}",
+                country,
+                code
+            );
+        }
+    }
+
+    #[test]
+    fn countries_of_postcodes() {
+        assert_eq!(countries("mk42 0xe"), ["GB"]);
+        assert_eq!(countries("1234AB"), ["NL"]);
+        assert!(countries("10115").len() > 1);
+        assert!(countries("").is_empty());
+    }
+
+    #[test]
+    fn formats_are_unique_and_uppercase() {
+        for (i, format) in FORMATS.iter().enumerate() {
+            assert_eq!(format.country, format.country.to_uppercase());
+            assert!(FORMATS[i + 1..].iter().all(|f| f.country != format.country));
+        }
+    }
+
+    #[test]
+    fn canonical_case_fallback() {
+        assert_eq!(canonical_case(Some("gb"), "mk42 0xe"), "MK42 0XE");
+        assert_eq!(canonical_case(Some("xx"), " ab1  2cd "), "AB1 2CD");
+        assert_eq!(canonical_case(None, "k1a 0b1"), "K1A 0B1");
+        assert!(validate("us", "02134"));
+        assert!(!validate("us", "abcde"));
+    }
+}
@@ -0,0 +1,299 @@
+//! Reverse geocoding: the nearest known addresses of a location.
+//!
+//! Addresses are kept in an R-tree of points on the unit sphere, so that
+//! the nearest neighbours are the nearest along the surface of the Earth.
+//! Results carry the address as an [`AddressParserResponse`] and a
+//! [`ParsedAddress`], so that they can be fed to the near-dupe hashing and
+//! duplicate checks like parsed addresses.
+//!
+//! # Examples
+//!
+//! ```
+//! use rustpostal::reverse::ReverseGeocoder;
+//!
+//! fn main() -> std::io::Result<()> {
+//!     let data = "lat,lon,number,street,city\n\
+//!                 51.5034,-0.1276,10,Downing Street,London\n\
+//!                 51.5014,-0.1419,,The Mall,London\n";
+//!     let geocoder = ReverseGeocoder::load_delimited(data.as_bytes(), b',')?;
+//!
+//!     let nearest = geocoder.nearest(51.5035, -0.1277, 1);
+//!     assert_eq!(nearest[0].address.house_number().as_deref(), Some("10"));
+//!     assert!(nearest[0].distance < 20.0);
+//!     println!("{}", nearest[0].address_line());
+//!     Ok(())
+//! }
+//! ```
+use std::io::{self, Read};
+
+use rstar::primitives::GeomWithData;
+use rstar::RTree;
+
+use crate::address::{AddressLabel, AddressParserResponse, ParsedAddress};
+use crate::geocode::{self, GazetteerEntry};
+
+/// Mean radius of the Earth, in meters.
+const EARTH_RADIUS: f64 = 6_371_008.8;
+
+/// A point on the unit sphere with the index of its record.
+type IndexedPoint = GeomWithData<[f64; 3], usize>;
+
+/// A known address near a location.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReverseMatch {
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Great-circle distance from the queried location, in meters.
+    pub distance: f64,
+    /// The labeled components of the address.
+    pub response: AddressParserResponse,
+    pub address: ParsedAddress,
+}
+
+impl ReverseMatch {
+    /// Format the address on a single line, e.g. to display or parse it.
+    ///
+    /// Components are ordered as "house number road, city, state postcode,
+    /// country", skipping missing ones.
+    pub fn address_line(&self) -> String {
+        let get = |label| self.response.get(label);
+        let join = |parts: &[Option<String>], separator: &str| {
+            let parts: Vec<&str> = parts.iter().flatten().map(String::as_str).collect();
+            if parts.is_empty() {
+                None
+            } else {
+                Some(parts.join(separator))
+            }
+        };
+        let lines = [
+            join(
+                &[get(AddressLabel::HouseNumber), get(AddressLabel::Road)],
+                " ",
+            ),
+            get(AddressLabel::City),
+            join(
+                &[get(AddressLabel::State), get(AddressLabel::Postcode)],
+                " ",
+            ),
+            get(AddressLabel::Country),
+        ];
+        join(&lines, ", ").unwrap_or_default()
+    }
+}
+
+/// A known address.
+#[derive(Clone, Debug)]
+struct Record {
+    latitude: f64,
+    longitude: f64,
+    response: AddressParserResponse,
+}
+
+/// Spatial index of known addresses.
+#[derive(Clone, Debug, Default)]
+pub struct ReverseGeocoder {
+    records: Vec<Record>,
+    tree: RTree<IndexedPoint>,
+}
+
+/// The position of a location on the unit sphere.
+fn unit_vector(latitude: f64, longitude: f64) -> [f64; 3] {
+    let (latitude, longitude) = (latitude.to_radians(), longitude.to_radians());
+    [
+        latitude.cos() * longitude.cos(),
+        latitude.cos() * longitude.sin(),
+        latitude.sin(),
+    ]
+}
+
+/// The great-circle distance between two locations, in meters.
+fn haversine(latitude1: f64, longitude1: f64, latitude2: f64, longitude2: f64) -> f64 {
+    let d_latitude = (latitude2 - latitude1).to_radians();
+    let d_longitude = (longitude2 - longitude1).to_radians();
+    let a = (d_latitude / 2.0).sin().powi(2)
+        + latitude1.to_radians().cos()
+            * latitude2.to_radians().cos()
+            * (d_longitude / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS * a.sqrt().min(1.0).asin()
+}
+
+/// The labeled components of a gazetteer entry.
+fn response(entry: GazetteerEntry) -> AddressParserResponse {
+    vec![
+        (AddressLabel::HouseNumber, entry.house_number),
+        (AddressLabel::Road, entry.road),
+        (AddressLabel::Postcode, entry.postcode),
+        (AddressLabel::City, entry.city),
+        (AddressLabel::State, entry.state),
+        (AddressLabel::Country, entry.country),
+    ]
+    .into_iter()
+    .filter_map(|(label, value)| value.map(|value| (label, value)))
+    .collect()
+}
+
+impl ReverseGeocoder {
+    /// Create an empty index.
+    pub fn new() -> ReverseGeocoder {
+        Default::default()
+    }
+
+    /// Return the number of addresses.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Return `true` if there are no addresses.
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Add an address at a location.
+    ///
+    /// Locations with non-finite coordinates are ignored.
+    pub fn insert(&mut self, latitude: f64, longitude: f64, response: AddressParserResponse) {
+        if !(latitude.is_finite() && longitude.is_finite()) {
+            return;
+        }
+        let point = IndexedPoint::new(unit_vector(latitude, longitude), self.records.len());
+        self.records.push(Record {
+            latitude,
+            longitude,
+            response,
+        });
+        self.tree.insert(point);
+    }
+
+    /// Add the address of a gazetteer entry.
+    pub fn insert_entry(&mut self, entry: GazetteerEntry) {
+        let (latitude, longitude) = (entry.latitude, entry.longitude);
+        self.insert(latitude, longitude, response(entry));
+    }
+
+    /// Load the addresses of a delimited file with a header row.
+    ///
+    /// The columns are those of [`Geocoder::load_delimited`](geocode::Geocoder::load_delimited),
+    /// and the index is bulk loaded, which is faster than inserting each address.
+    ///
+    /// # Errors
+    ///
+    /// It will return an error if reading fails, the coordinate columns are
+    /// missing or a row is malformed.
+    pub fn load_delimited<R: Read>(reader: R, delimiter: u8) -> io::Result<ReverseGeocoder> {
+        let mut records = Vec::new();
+        geocode::read_delimited(reader, delimiter, |entry| {
+            records.push(Record {
+                latitude: entry.latitude,
+                longitude: entry.longitude,
+                response: response(entry),
+            });
+            Ok(())
+        })?;
+        let points = records
+            .iter()
+            .enumerate()
+            .map(|(index, record)| {
+                IndexedPoint::new(unit_vector(record.latitude, record.longitude), index)
+            })
+            .collect();
+        Ok(ReverseGeocoder {
+            records,
+            tree: RTree::bulk_load(points),
+        })
+    }
+
+    fn to_match(&self, point: &IndexedPoint, latitude: f64, longitude: f64) -> ReverseMatch {
+        let record = &self.records[point.data];
+        ReverseMatch {
+            latitude: record.latitude,
+            longitude: record.longitude,
+            distance: haversine(latitude, longitude, record.latitude, record.longitude),
+            response: record.response.clone(),
+            address: ParsedAddress::from(record.response.clone()),
+        }
+    }
+
+    /// Return the `k` addresses nearest to a location, by increasing distance.
+    pub fn nearest(&self, latitude: f64, longitude: f64, k: usize) -> Vec<ReverseMatch> {
+        if !(latitude.is_finite() && longitude.is_finite()) {
+            return Vec::new();
+        }
+        self.tree
+            .nearest_neighbor_iter(&unit_vector(latitude, longitude))
+            .take(k)
+            .map(|point| self.to_match(point, latitude, longitude))
+            .collect()
+    }
+
+    /// Return the addresses within `radius` meters of a location, by
+    /// increasing distance.
+    pub fn within(&self, latitude: f64, longitude: f64, radius: f64) -> Vec<ReverseMatch> {
+        if !(latitude.is_finite() && longitude.is_finite()) || radius.is_nan() {
+            return Vec::new();
+        }
+        // The chord subtending the arc of the radius, on the unit sphere.
+        let angle = (radius / EARTH_RADIUS).min(std::f64::consts::PI);
+        let chord = 2.0 * (angle / 2.0).sin();
+        let mut matches: Vec<ReverseMatch> = self
+            .tree
+            .locate_within_distance(unit_vector(latitude, longitude), chord * chord)
+            .map(|point| self.to_match(point, latitude, longitude))
+            .collect();
+        matches.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn geocoder() -> ReverseGeocoder {
+        let mut geocoder = ReverseGeocoder::new();
+        let mut entry = GazetteerEntry::new(51.5034, -0.1276);
+        entry.house_number = Some(String::from("10"));
+        entry.road = Some(String::from("downing street"));
+        entry.city = Some(String::from("london"));
+        geocoder.insert_entry(entry);
+        let mut entry = GazetteerEntry::new(48.8584, 2.2945);
+        entry.city = Some(String::from("paris"));
+        geocoder.insert_entry(entry);
+        geocoder
+    }
+
+    #[test]
+    fn haversine_distance() {
+        let distance = haversine(51.5034, -0.1276, 48.8584, 2.2945);
+        assert!((distance - 342_000.0).abs() < 2_000.0, "{}", distance);
+        assert_eq!(haversine(10.0, 10.0, 10.0, 10.0), 0.0);
+    }
+
+    #[test]
+    fn nearest_addresses() {
+        let geocoder = geocoder();
+        let nearest = geocoder.nearest(48.0, 2.0, 2);
+        assert_eq!(nearest.len(), 2);
+        assert_eq!(nearest[0].address.city().as_deref(), Some("paris"));
+        assert!(nearest[0].distance < nearest[1].distance);
+        assert_eq!(nearest[1].address_line(), "10 downing street, london");
+    }
+
+    #[test]
+    fn addresses_within_radius() {
+        let geocoder = geocoder();
+        assert_eq!(geocoder.within(51.5, -0.13, 1_000.0).len(), 1);
+        assert_eq!(geocoder.within(51.5, -0.13, 400_000.0).len(), 2);
+        assert!(geocoder.within(0.0, 0.0, 1_000.0).is_empty());
+    }
+
+    #[test]
+    fn non_finite_coordinates() {
+        let mut geocoder = geocoder();
+        geocoder.insert(f64::NAN, 0.0, AddressParserResponse::new());
+        assert_eq!(geocoder.nearest(51.5, -0.13, 5).len(), 2);
+        assert!(geocoder.nearest(f64::NAN, 0.0, 1).is_empty());
+        assert!(geocoder.within(51.5, f64::INFINITY, 1_000.0).is_empty());
+        assert!(geocoder.within(51.5, -0.13, f64::NAN).is_empty());
+    }
+}
@@ -0,0 +1,306 @@
+//! Decomposition of roads into street name, types and directionals.
+//!
+//! The road is expanded with the street dictionaries of `libpostal`, see
+//! [`AddressComponents::STREET`], and to its root, which drops the street
+//! types and directionals, see [`NormalizeOptions::expand_root`]. The words
+//! of the expansion before the root are the prefix, e.g. "calle", and the
+//! ones after it the suffix, e.g. "avenue". Both are split into the street
+//! type and the directional.
+//!
+//! # Examples
+//!
+//! ```
+//! use rustpostal::error::RuntimeError;
+//! use rustpostal::road::RoadParts;
+//! use rustpostal::LibModules;
+//!
+//! fn main() -> Result<(), RuntimeError> {
+//!     let postal_module = LibModules::Expand;
+//!     postal_module.setup()?;
+//!
+//!     if let Some(parts) = RoadParts::parse("N Nostrand Ave")? {
+//!         println!("{:?} {} {:?}", parts.predirectional, parts.name, parts.suffix_type);
+//!     }
+//!     Ok(())
+//! }
+//! ```
+use std::ffi::NulError;
+
+use crate::expand::{AddressComponents, NormalizeOptions};
+use crate::models::{self, Models};
+
+/// Directionals, in the languages of the main address formats, and the
+/// abbreviations some variations keep.
+const DIRECTIONALS: &[&str] = &[
+    "n",
+    "s",
+    "e",
+    "w",
+    "ne",
+    "nw",
+    "se",
+    "sw",
+    "north",
+    "south",
+    "east",
+    "west",
+    "northeast",
+    "northwest",
+    "southeast",
+    "southwest",
+    "nord",
+    "sud",
+    "est",
+    "ouest",
+    "norte",
+    "sur",
+    "este",
+    "oeste",
+    "ost",
+    "noord",
+    "zuid",
+    "oost",
+];
+
+/// Particles between a prefix street type and the name, e.g. "de" in
+/// "calle de alcala", which belong to neither.
+const PARTICLES: &[&str] = &[
+    "d", "da", "das", "de", "del", "della", "des", "di", "do", "dos", "du", "la", "le", "les",
+];
+
+/// Ordinal markers following a number, e.g. "th" in "5th".
+const ORDINAL_MARKERS: &[&str] = &["st", "nd", "rd", "th", "er", "re", "eme", "º", "ª"];
+
+/// Single-letter ordinal markers and the languages using them, e.g. "e" in
+/// the French "5e". In other languages they are suffixes, e.g. in "12a".
+const LETTER_ORDINAL_MARKERS: &[(&str, &[&str])] = &[
+    ("e", &["fr", "nl"]),
+    ("o", &["es", "it", "pt"]),
+    ("a", &["es", "it", "pt"]),
+];
+
+/// Directionals that are also common words, e.g. "este" for "this", which
+/// are only directionals as the outermost word of the road.
+const AMBIGUOUS_DIRECTIONALS: &[&str] = &["e", "est", "este", "sur"];
+
+/// The components of a road.
+///
+/// Values are normalized by the expansion, e.g. lowercase and without
+/// abbreviations.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RoadParts {
+    /// The street type before the name, e.g. "calle".
+    pub prefix_type: Option<String>,
+    pub predirectional: Option<String>,
+    /// The street name, e.g. "ruiz de alarcon" or "5th".
+    pub name: String,
+    /// The street type after the name, e.g. "avenue".
+    pub suffix_type: Option<String>,
+    pub postdirectional: Option<String>,
+    /// The ordinal marker of a numbered name, e.g. "th" in "5th".
+    pub ordinal: Option<String>,
+}
+
+/// Join the words of the street type and the directional, among `words`,
+/// the words before the name if `prefix`, or else after it.
+fn split_affix(words: &[&str], prefix: bool) -> (Option<String>, Option<String>) {
+    let join = |words: Vec<&str>| {
+        if words.is_empty() {
+            None
+        } else {
+            Some(words.join(" "))
+        }
+    };
+    let outermost = if prefix {
+        0
+    } else {
+        words.len().saturating_sub(1)
+    };
+    let mut types = Vec::new();
+    let mut directionals = Vec::new();
+    for (i, &word) in words.iter().enumerate() {
+        if PARTICLES.contains(&word) {
+            continue;
+        }
+        if DIRECTIONALS.contains(&word)
+            && (i == outermost || !AMBIGUOUS_DIRECTIONALS.contains(&word))
+        {
+            directionals.push(word);
+        } else {
+            types.push(word);
+        }
+    }
+    (join(types), join(directionals))
+}
+
+/// Return the ordinal marker of a word, e.g. "th" for "5th".
+///
+/// Single letters, e.g. "a" in "12a", are not markers, see
+/// [`ordinal_marker_in`].
+pub(crate) fn ordinal_marker(word: &str) -> Option<&str> {
+    ordinal_marker_in(word, None)
+}
+
+/// Return the ordinal marker of a word in `language`, e.g. "e" for "5e" in
+/// French.
+pub(crate) fn ordinal_marker_in<'a>(word: &'a str, language: Option<&str>) -> Option<&'a str> {
+    let marker = word.trim_start_matches(|c: char| c.is_ascii_digit());
+    let is_letter_marker = || {
+        LETTER_ORDINAL_MARKERS.iter().any(|&(letter, languages)| {
+            letter == marker && language.is_some_and(|language| languages.contains(&language))
+        })
+    };
+    if marker.len() < word.len() && (ORDINAL_MARKERS.contains(&marker) || is_letter_marker()) {
+        Some(marker)
+    } else {
+        None
+    }
+}
+
+/// Split an expanded road in `language`, if known, around its root.
+///
+/// If the root is not found in the expansion, it is all name.
+fn split(expansion: &str, root: &str, language: Option<&str>) -> RoadParts {
+    let words: Vec<&str> = expansion.split_whitespace().collect();
+    let root: Vec<&str> = root.split_whitespace().collect();
+    let start = if root.is_empty() {
+        None
+    } else {
+        words
+            .windows(root.len())
+            .position(|window| window == root.as_slice())
+    };
+    let (prefix, name, suffix) = match start {
+        Some(start) => (
+            &words[..start],
+            &words[start..start + root.len()],
+            &words[start + root.len()..],
+        ),
+        None => (&words[..0], &words[..], &words[..0]),
+    };
+    let (prefix_type, predirectional) = split_affix(prefix, true);
+    let (suffix_type, postdirectional) = split_affix(suffix, false);
+    RoadParts {
+        prefix_type,
+        predirectional,
+        name: name.join(" "),
+        suffix_type,
+        postdirectional,
+        ordinal: name
+            .iter()
+            .find_map(|word| ordinal_marker_in(word, language))
+            .map(String::from),
+    }
+}
+
+impl RoadParts {
+    /// Decompose a road, e.g. "c. de ruiz de alarcón" or "nostrand ave".
+    ///
+    /// Requires the models for expansion to be setup, e.g. with
+    /// [`LibModules::Expand`](crate::LibModules::Expand). Returns `None`
+    /// if they are not, or if the road has no expansion.
+    ///
+    /// # Errors
+    ///
+    /// It will return an error if the road contains an internal null byte.
+    pub fn parse(road: &str) -> Result<Option<RoadParts>, NulError> {
+        RoadParts::parse_in(road, None)
+    }
+
+    /// Decompose a road in `language`, e.g. "fr" for "5e avenue", so that
+    /// the single-letter ordinal markers of the language are recognized.
+    ///
+    /// See [`parse`](RoadParts::parse).
+    ///
+    /// # Errors
+    ///
+    /// It will return an error if the road or the language contains an
+    /// internal null byte.
+    pub fn parse_in(road: &str, language: Option<&str>) -> Result<Option<RoadParts>, NulError> {
+        if !models::loaded().contains(Models::BASE | Models::CLASSIFIER) {
+            return Ok(None);
+        }
+        let languages = language.map(|language| [language]);
+        let mut options = NormalizeOptions::new(languages.as_ref().map(|l| l.iter()))?;
+        options.add_address_component(AddressComponents::STREET);
+        let expansions = options.expand(road)?;
+        let roots = options.expand_root(road)?;
+        let root = roots.iter().next().map(String::as_str).unwrap_or_default();
+        // Prefer the expansion containing the root, among languages.
+        let parts = expansions
+            .iter()
+            .map(|expansion| split(expansion, root, language))
+            .find(|parts| parts.name == root)
+            .or_else(|| {
+                expansions
+                    .iter()
+                    .next()
+                    .map(|expansion| split(expansion, root, language))
+            });
+        Ok(parts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_around_root() {
+        let parts = split("calle de ruiz de alarcon", "ruiz de alarcon", None);
+        assert_eq!(parts.prefix_type.as_deref(), Some("calle"));
+        assert_eq!(parts.name, "ruiz de alarcon");
+        assert_eq!(parts.suffix_type, None);
+
+        let parts = split("north 5th avenue southwest", "5th", None);
+        assert_eq!(parts.predirectional.as_deref(), Some("north"));
+        assert_eq!(parts.suffix_type.as_deref(), Some("avenue"));
+        assert_eq!(parts.postdirectional.as_deref(), Some("southwest"));
+        assert_eq!(parts.ordinal.as_deref(), Some("th"));
+        assert_eq!(parts.name, "5th");
+    }
+
+    #[test]
+    fn split_without_root() {
+        let parts = split("avenue of the americas", "", None);
+        assert_eq!(parts.name, "avenue of the americas");
+        assert_eq!(parts, split("avenue of the americas", "broadway", None));
+        assert_eq!(parts.prefix_type, None);
+    }
+
+    #[test]
+    fn ordinal_markers() {
+        assert_eq!(ordinal_marker("1er"), Some("er"));
+        assert_eq!(ordinal_marker("3º"), Some("º"));
+        assert_eq!(ordinal_marker("th"), None);
+        assert_eq!(ordinal_marker("12b"), None);
+        assert_eq!(ordinal_marker("12a"), None);
+        assert_eq!(ordinal_marker("5e"), None);
+        assert_eq!(ordinal_marker_in("12a", Some("en")), None);
+        assert_eq!(ordinal_marker_in("2a", Some("es")), Some("a"));
+        assert_eq!(ordinal_marker_in("5e", Some("fr")), Some("e"));
+    }
+
+    #[test]
+    fn split_suffixed_numbers() {
+        let parts = split("12a street", "12a", None);
+        assert_eq!(parts.name, "12a");
+        assert_eq!(parts.ordinal, None);
+        assert_eq!(
+            split("5e avenue", "5e", Some("fr")).ordinal.as_deref(),
+            Some("e")
+        );
+    }
+
+    #[test]
+    fn split_ambiguous_directionals() {
+        let parts = split("5th avenue e", "5th", None);
+        assert_eq!(parts.suffix_type.as_deref(), Some("avenue"));
+        assert_eq!(parts.postdirectional.as_deref(), Some("e"));
+
+        let parts = split("calle este 5th", "5th", None);
+        assert_eq!(parts.prefix_type.as_deref(), Some("calle este"));
+        assert_eq!(parts.predirectional, None);
+    }
+}
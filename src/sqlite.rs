@@ -0,0 +1,324 @@
+//! SQL functions for [SQLite](https://sqlite.org), through `rusqlite`.
+//!
+//! * `postal_parse_json(address [, language [, country]])`: a JSON object of
+//!   the parsed address, from label to value, e.g. to use with `json_extract`.
+//! * `postal_component(address, label [, language [, country]])`: the tokens
+//!   of a single label, e.g. `postal_component(address, 'postcode')`.
+//! * `postal_expand(address [, languages])`: a table-valued function with one
+//!   `value` row per normalized variation.
+//!
+//! `languages` are comma-separated language codes, e.g. `'en,fr'`. Null or
+//! empty hints are ignored, and the functions return null for null addresses.
+//!
+//! [`register_functions`] does not load any models; set up the modules the
+//! functions use first. The `sqlite-extension` feature builds the
+//! `rustpostal_sqlite` example as a loadable extension instead, which loads
+//! every model on load:
+//!
+//! ```sh
+//! cargo build --release --example rustpostal_sqlite --features sqlite-extension
+//! sqlite3 customers.db
+//! sqlite> .load target/release/examples/librustpostal_sqlite
+//! sqlite> SELECT postal_component(addr, 'postcode') FROM customers;
+//! ```
+//!
+//! # Examples
+//!
+//! ```
+//! use rusqlite::Connection;
+//! use rustpostal::LibModules;
+//!
+//! fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let postal_module = LibModules::All;
+//!     postal_module.setup()?;
+//!
+//!     let conn = Connection::open_in_memory()?;
+//!     rustpostal::sqlite::register_functions(&conn)?;
+//!     let city: String = conn.query_row(
+//!         "SELECT postal_component('10 Downing St, London', 'city')",
+//!         [],
+//!         |row| row.get(0),
+//!     )?;
+//!     assert_eq!(city, "london");
+//!     Ok(())
+//! }
+//! ```
+use std::marker::PhantomData;
+use std::os::raw::c_int;
+
+use rusqlite::functions::{Context, FunctionFlags};
+use rusqlite::vtab::{
+    eponymous_only_module, Filters, IndexConstraintOp, IndexInfo, VTab, VTabConfig, VTabConnection,
+    VTabCursor,
+};
+use rusqlite::{ffi, Connection, Error, Result};
+use serde_json::{Map, Value};
+
+use crate::address::{AddressLabel, AddressParserOptions, AddressParserResponse};
+use crate::expand::NormalizeOptions;
+
+/// Register every function of the module with `conn`.
+pub fn register_functions(conn: &Connection) -> Result<()> {
+    let flags = FunctionFlags::SQLITE_UTF8
+        | FunctionFlags::SQLITE_DETERMINISTIC
+        | FunctionFlags::SQLITE_INNOCUOUS;
+    conn.create_scalar_function("postal_parse_json", -1, flags, postal_parse_json)?;
+    conn.create_scalar_function("postal_component", -1, flags, postal_component)?;
+    conn.create_module("postal_expand", eponymous_only_module::<ExpandTab>(), None)
+}
+
+fn user_error<E>(err: E) -> Error
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    Error::UserFunctionError(Box::new(err))
+}
+
+fn check_arguments(ctx: &Context<'_>, name: &str, min: usize, max: usize) -> Result<()> {
+    if (min..=max).contains(&ctx.len()) {
+        Ok(())
+    } else {
+        Err(Error::UserFunctionError(
+            format!("{} takes {} to {} arguments", name, min, max).into(),
+        ))
+    }
+}
+
+/// The non-empty value of an optional argument.
+fn hint(ctx: &Context<'_>, index: usize) -> Result<Option<String>> {
+    if index >= ctx.len() {
+        return Ok(None);
+    }
+    let value: Option<String> = ctx.get(index)?;
+    Ok(value
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty()))
+}
+
+/// Parse the first argument, with the language and country hints at `hints`.
+fn parse(ctx: &Context<'_>, hints: usize) -> Result<Option<AddressParserResponse>> {
+    let address: Option<String> = ctx.get(0)?;
+    let address = match address {
+        Some(address) => address,
+        None => return Ok(None),
+    };
+    let language = hint(ctx, hints)?;
+    let country = hint(ctx, hints + 1)?;
+    let response = AddressParserOptions::new(language.as_deref(), country.as_deref())
+        .and_then(|options| options.parse(&address))
+        .map_err(user_error)?;
+    Ok(Some(response))
+}
+
+fn postal_parse_json(ctx: &Context<'_>) -> Result<Option<String>> {
+    check_arguments(ctx, "postal_parse_json", 1, 3)?;
+    Ok(parse(ctx, 1)?.map(|response| {
+        let components: Map<String, Value> = AddressLabel::ALL
+            .iter()
+            .filter_map(|&label| {
+                response
+                    .get(label)
+                    .map(|value| (label.to_string(), Value::String(value)))
+            })
+            .collect();
+        Value::Object(components).to_string()
+    }))
+}
+
+fn postal_component(ctx: &Context<'_>) -> Result<Option<String>> {
+    check_arguments(ctx, "postal_component", 2, 4)?;
+    let label: Option<String> = ctx.get(1)?;
+    let label: AddressLabel = match label {
+        Some(label) => label.parse().map_err(user_error)?,
+        None => return Ok(None),
+    };
+    Ok(parse(ctx, 2)?.and_then(|response| response.get(label)))
+}
+
+/// Column of the `postal_expand` argument.
+const EXPAND_COLUMN_ADDRESS: c_int = 1;
+/// Column of the optional `postal_expand` languages.
+const EXPAND_COLUMN_LANGUAGES: c_int = 2;
+
+/// Bits of the index number, set for each argument passed to the cursor.
+const EXPAND_ADDRESS: c_int = 1;
+const EXPAND_LANGUAGES: c_int = 2;
+
+/// The `postal_expand` table-valued function.
+#[repr(C)]
+struct ExpandTab {
+    base: ffi::sqlite3_vtab,
+}
+
+unsafe impl<'vtab> VTab<'vtab> for ExpandTab {
+    type Aux = ();
+    type Cursor = ExpandCursor<'vtab>;
+
+    fn connect(
+        db: &mut VTabConnection,
+        _aux: Option<&()>,
+        _args: &[&[u8]],
+    ) -> Result<(String, Self)> {
+        db.config(VTabConfig::Innocuous)?;
+        let vtab = ExpandTab {
+            base: ffi::sqlite3_vtab::default(),
+        };
+        Ok((
+            String::from("CREATE TABLE x(value, address HIDDEN, languages HIDDEN)"),
+            vtab,
+        ))
+    }
+
+    fn best_index(&self, info: &mut IndexInfo) -> Result<()> {
+        let mut arguments = [None, None];
+        for (index, constraint) in info.constraints().enumerate() {
+            let argument = match constraint.column() {
+                EXPAND_COLUMN_ADDRESS => 0,
+                EXPAND_COLUMN_LANGUAGES => 1,
+                _ => continue,
+            };
+            if constraint.is_usable()
+                && constraint.operator() == IndexConstraintOp::SQLITE_INDEX_CONSTRAINT_EQ
+            {
+                arguments[argument] = Some(index);
+            }
+        }
+        if arguments[0].is_none() {
+            // Rule out plans without the address, e.g. before the left side of a join.
+            return Err(Error::SqliteFailure(
+                ffi::Error::new(ffi::SQLITE_CONSTRAINT),
+                None,
+            ));
+        }
+        let mut idx_num = 0;
+        let mut argv_index = 0;
+        for (&argument, bit) in arguments.iter().zip([EXPAND_ADDRESS, EXPAND_LANGUAGES]) {
+            if let Some(index) = argument {
+                argv_index += 1;
+                idx_num |= bit;
+                let mut usage = info.constraint_usage(index);
+                usage.set_argv_index(argv_index);
+                usage.set_omit(true);
+            }
+        }
+        info.set_idx_num(idx_num);
+        info.set_estimated_cost(1.0);
+        info.set_estimated_rows(8);
+        Ok(())
+    }
+
+    fn open(&mut self) -> Result<ExpandCursor<'_>> {
+        Ok(ExpandCursor {
+            base: ffi::sqlite3_vtab_cursor::default(),
+            address: None,
+            languages: None,
+            expansions: Vec::new(),
+            row: 0,
+            phantom: PhantomData,
+        })
+    }
+}
+
+#[repr(C)]
+struct ExpandCursor<'vtab> {
+    base: ffi::sqlite3_vtab_cursor,
+    address: Option<String>,
+    languages: Option<String>,
+    expansions: Vec<String>,
+    row: usize,
+    phantom: PhantomData<&'vtab ExpandTab>,
+}
+
+unsafe impl VTabCursor for ExpandCursor<'_> {
+    fn filter(&mut self, idx_num: c_int, _idx_str: Option<&str>, args: &Filters<'_>) -> Result<()> {
+        self.address = args.get(0)?;
+        self.languages = if idx_num & EXPAND_LANGUAGES != 0 {
+            args.get(1)?
+        } else {
+            None
+        };
+        self.row = 0;
+        self.expansions = match &self.address {
+            Some(address) => {
+                let languages: Option<Vec<&str>> = self.languages.as_deref().map(|languages| {
+                    languages
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|language| !language.is_empty())
+                        .collect()
+                });
+                let mut options = NormalizeOptions::new(languages.as_ref().map(|l| l.iter()))
+                    .map_err(user_error)?;
+                Vec::from(options.expand(address).map_err(user_error)?)
+            }
+            None => Vec::new(),
+        };
+        Ok(())
+    }
+
+    fn next(&mut self) -> Result<()> {
+        self.row += 1;
+        Ok(())
+    }
+
+    fn eof(&self) -> bool {
+        self.row >= self.expansions.len()
+    }
+
+    fn column(&self, ctx: &mut rusqlite::vtab::Context, i: c_int) -> Result<()> {
+        match i {
+            EXPAND_COLUMN_ADDRESS => ctx.set_result(&self.address),
+            EXPAND_COLUMN_LANGUAGES => ctx.set_result(&self.languages),
+            _ => ctx.set_result(&self.expansions[self.row]),
+        }
+    }
+
+    fn rowid(&self) -> Result<i64> {
+        Ok(self.row as i64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn null_addresses() -> Result<()> {
+        let conn = Connection::open_in_memory()?;
+        register_functions(&conn)?;
+        let (parsed, component): (Option<String>, Option<String>) = conn.query_row(
+            "SELECT postal_parse_json(NULL), postal_component(NULL, 'city')",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        assert_eq!(parsed, None);
+        assert_eq!(component, None);
+        let count: i64 = conn.query_row("SELECT count(*) FROM postal_expand(NULL)", [], |row| {
+            row.get(0)
+        })?;
+        assert_eq!(count, 0);
+        let count: i64 = conn.query_row(
+            "SELECT count(value) FROM (SELECT NULL AS address) AS a, postal_expand(a.address)",
+            [],
+            |row| row.get(0),
+        )?;
+        assert_eq!(count, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_arguments() -> Result<()> {
+        let conn = Connection::open_in_memory()?;
+        register_functions(&conn)?;
+        let unknown_label = conn.query_row("SELECT postal_component('a', 'zip')", [], |row| {
+            row.get::<_, Option<String>>(0)
+        });
+        assert!(unknown_label.is_err());
+        let no_address = conn.query_row("SELECT postal_parse_json()", [], |row| {
+            row.get::<_, Option<String>>(0)
+        });
+        assert!(no_address.is_err());
+        assert!(conn.prepare("SELECT value FROM postal_expand").is_err());
+        Ok(())
+    }
+}
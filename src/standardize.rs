@@ -0,0 +1,4 @@
+//! Standardization of parsed addresses to national postal formats.
+//!
+//! * [`us`]: USPS Publication 28, for US mail.
+pub mod us;
@@ -0,0 +1,805 @@
+//! Standardization of US addresses following USPS Publication 28.
+//!
+//! Components are uppercased and stripped of punctuation, the road is split
+//! into predirectional, name, suffix and postdirectional, and the bundled
+//! tables of Publication 28 abbreviate street suffixes (AVENUE to AVE),
+//! directionals (NORTHWEST to NW) and secondary unit designators (APARTMENT
+//! to APT). States are resolved to their two-letter codes, see
+//! [`country::find_subdivision`], and ZIP codes to ZIP or ZIP+4, see
+//! [`postcode::normalize`]. No online validation takes place.
+//!
+//! # Examples
+//!
+//! ```
+//! use rustpostal::address::{AddressLabel, AddressParserResponse, ParsedAddress};
+//! use rustpostal::standardize::us;
+//!
+//! let response: AddressParserResponse = vec![
+//!     (AddressLabel::HouseNumber, "1600"),
+//!     (AddressLabel::Road, "pennsylvania avenue northwest"),
+//!     (AddressLabel::City, "washington"),
+//!     (AddressLabel::State, "district of columbia"),
+//!     (AddressLabel::Postcode, "20500"),
+//! ]
+//! .into_iter()
+//! .map(|(label, value)| (label, String::from(value)))
+//! .collect();
+//!
+//! let address = us::standardize(&ParsedAddress::from(response));
+//! assert_eq!(address.lines(), ["1600 PENNSYLVANIA AVE NW", "WASHINGTON DC 20500"]);
+//! ```
+use std::fmt;
+
+use crate::address::ParsedAddress;
+use crate::country;
+use crate::postcode;
+use crate::road;
+
+/// Street suffixes and their common variants, with their abbreviations.
+///
+/// From appendix C1 of Publication 28. Abbreviations match themselves.
+const SUFFIXES: &[(&str, &str)] = &[
+    ("ALLEY", "ALY"),
+    ("ALLEE", "ALY"),
+    ("ALLY", "ALY"),
+    ("ANNEX", "ANX"),
+    ("ANEX", "ANX"),
+    ("ARCADE", "ARC"),
+    ("AVENUE", "AVE"),
+    ("AV", "AVE"),
+    ("AVEN", "AVE"),
+    ("AVENU", "AVE"),
+    ("AVN", "AVE"),
+    ("AVNUE", "AVE"),
+    ("BAYOU", "BYU"),
+    ("BAYOO", "BYU"),
+    ("BEACH", "BCH"),
+    ("BEND", "BND"),
+    ("BLUFF", "BLF"),
+    ("BLUFFS", "BLFS"),
+    ("BOTTOM", "BTM"),
+    ("BOT", "BTM"),
+    ("BOTTM", "BTM"),
+    ("BOULEVARD", "BLVD"),
+    ("BOUL", "BLVD"),
+    ("BOULV", "BLVD"),
+    ("BRANCH", "BR"),
+    ("BRNCH", "BR"),
+    ("BRIDGE", "BRG"),
+    ("BRDGE", "BRG"),
+    ("BROOK", "BRK"),
+    ("BROOKS", "BRKS"),
+    ("BURG", "BG"),
+    ("BURGS", "BGS"),
+    ("BYPASS", "BYP"),
+    ("BYPA", "BYP"),
+    ("BYPAS", "BYP"),
+    ("BYPS", "BYP"),
+    ("CAMP", "CP"),
+    ("CMP", "CP"),
+    ("CANYON", "CYN"),
+    ("CANYN", "CYN"),
+    ("CNYN", "CYN"),
+    ("CAPE", "CPE"),
+    ("CAUSEWAY", "CSWY"),
+    ("CAUSWA", "CSWY"),
+    ("CENTER", "CTR"),
+    ("CEN", "CTR"),
+    ("CENT", "CTR"),
+    ("CENTR", "CTR"),
+    ("CENTRE", "CTR"),
+    ("CNTER", "CTR"),
+    ("CNTR", "CTR"),
+    ("CENTERS", "CTRS"),
+    ("CIRCLE", "CIR"),
+    ("CIRC", "CIR"),
+    ("CIRCL", "CIR"),
+    ("CRCL", "CIR"),
+    ("CRCLE", "CIR"),
+    ("CIRCLES", "CIRS"),
+    ("CLIFF", "CLF"),
+    ("CLIFFS", "CLFS"),
+    ("CLUB", "CLB"),
+    ("COMMON", "CMN"),
+    ("COMMONS", "CMNS"),
+    ("CORNER", "COR"),
+    ("CORNERS", "CORS"),
+    ("COURSE", "CRSE"),
+    ("COURT", "CT"),
+    ("CRT", "CT"),
+    ("COURTS", "CTS"),
+    ("COVE", "CV"),
+    ("COVES", "CVS"),
+    ("CREEK", "CRK"),
+    ("CRESCENT", "CRES"),
+    ("CRSENT", "CRES"),
+    ("CRSNT", "CRES"),
+    ("CREST", "CRST"),
+    ("CROSSING", "XING"),
+    ("CRSSNG", "XING"),
+    ("CROSSROAD", "XRD"),
+    ("CROSSROADS", "XRDS"),
+    ("CURVE", "CURV"),
+    ("DALE", "DL"),
+    ("DAM", "DM"),
+    ("DIVIDE", "DV"),
+    ("DIV", "DV"),
+    ("DVD", "DV"),
+    ("DRIVE", "DR"),
+    ("DRIV", "DR"),
+    ("DRV", "DR"),
+    ("DRIVES", "DRS"),
+    ("ESTATE", "EST"),
+    ("ESTATES", "ESTS"),
+    ("EXPRESSWAY", "EXPY"),
+    ("EXP", "EXPY"),
+    ("EXPR", "EXPY"),
+    ("EXPRESS", "EXPY"),
+    ("EXPW", "EXPY"),
+    ("EXTENSION", "EXT"),
+    ("EXTN", "EXT"),
+    ("EXTNSN", "EXT"),
+    ("EXTENSIONS", "EXTS"),
+    ("FALL", "FALL"),
+    ("FALLS", "FLS"),
+    ("FERRY", "FRY"),
+    ("FRRY", "FRY"),
+    ("FIELD", "FLD"),
+    ("FIELDS", "FLDS"),
+    ("FLAT", "FLT"),
+    ("FLATS", "FLTS"),
+    ("FORD", "FRD"),
+    ("FORDS", "FRDS"),
+    ("FOREST", "FRST"),
+    ("FORESTS", "FRST"),
+    ("FORGE", "FRG"),
+    ("FORG", "FRG"),
+    ("FORGES", "FRGS"),
+    ("FORK", "FRK"),
+    ("FORKS", "FRKS"),
+    ("FORT", "FT"),
+    ("FRT", "FT"),
+    ("FREEWAY", "FWY"),
+    ("FREEWY", "FWY"),
+    ("FRWAY", "FWY"),
+    ("FRWY", "FWY"),
+    ("GARDEN", "GDN"),
+    ("GARDN", "GDN"),
+    ("GRDEN", "GDN"),
+    ("GRDN", "GDN"),
+    ("GARDENS", "GDNS"),
+    ("GRDNS", "GDNS"),
+    ("GATEWAY", "GTWY"),
+    ("GATEWY", "GTWY"),
+    ("GATWAY", "GTWY"),
+    ("GTWAY", "GTWY"),
+    ("GLEN", "GLN"),
+    ("GLENS", "GLNS"),
+    ("GREEN", "GRN"),
+    ("GREENS", "GRNS"),
+    ("GROVE", "GRV"),
+    ("GROV", "GRV"),
+    ("GROVES", "GRVS"),
+    ("HARBOR", "HBR"),
+    ("HARB", "HBR"),
+    ("HARBR", "HBR"),
+    ("HRBOR", "HBR"),
+    ("HARBORS", "HBRS"),
+    ("HAVEN", "HVN"),
+    ("HEIGHTS", "HTS"),
+    ("HT", "HTS"),
+    ("HIGHWAY", "HWY"),
+    ("HIGHWY", "HWY"),
+    ("HIWAY", "HWY"),
+    ("HIWY", "HWY"),
+    ("HWAY", "HWY"),
+    ("HILL", "HL"),
+    ("HILLS", "HLS"),
+    ("HOLLOW", "HOLW"),
+    ("HLLW", "HOLW"),
+    ("HOLLOWS", "HOLW"),
+    ("HOLWS", "HOLW"),
+    ("INLET", "INLT"),
+    ("ISLAND", "IS"),
+    ("ISLND", "IS"),
+    ("ISLANDS", "ISS"),
+    ("ISLNDS", "ISS"),
+    ("ISLE", "ISLE"),
+    ("ISLES", "ISLE"),
+    ("JUNCTION", "JCT"),
+    ("JCTION", "JCT"),
+    ("JCTN", "JCT"),
+    ("JUNCTN", "JCT"),
+    ("JUNCTON", "JCT"),
+    ("JUNCTIONS", "JCTS"),
+    ("KEY", "KY"),
+    ("KEYS", "KYS"),
+    ("KNOLL", "KNL"),
+    ("KNOL", "KNL"),
+    ("KNOLLS", "KNLS"),
+    ("LAKE", "LK"),
+    ("LAKES", "LKS"),
+    ("LAND", "LAND"),
+    ("LANDING", "LNDG"),
+    ("LNDNG", "LNDG"),
+    ("LANE", "LN"),
+    ("LIGHT", "LGT"),
+    ("LIGHTS", "LGTS"),
+    ("LOAF", "LF"),
+    ("LOCK", "LCK"),
+    ("LOCKS", "LCKS"),
+    ("LODGE", "LDG"),
+    ("LDGE", "LDG"),
+    ("LODG", "LDG"),
+    ("LOOP", "LOOP"),
+    ("LOOPS", "LOOP"),
+    ("MALL", "MALL"),
+    ("MANOR", "MNR"),
+    ("MANORS", "MNRS"),
+    ("MEADOW", "MDW"),
+    ("MEADOWS", "MDWS"),
+    ("MEDOWS", "MDWS"),
+    ("MEWS", "MEWS"),
+    ("MILL", "ML"),
+    ("MILLS", "MLS"),
+    ("MISSION", "MSN"),
+    ("MISSN", "MSN"),
+    ("MSSN", "MSN"),
+    ("MOTORWAY", "MTWY"),
+    ("MOUNT", "MT"),
+    ("MNT", "MT"),
+    ("MOUNTAIN", "MTN"),
+    ("MNTAIN", "MTN"),
+    ("MNTN", "MTN"),
+    ("MOUNTIN", "MTN"),
+    ("MTIN", "MTN"),
+    ("MOUNTAINS", "MTNS"),
+    ("MNTNS", "MTNS"),
+    ("NECK", "NCK"),
+    ("ORCHARD", "ORCH"),
+    ("ORCHRD", "ORCH"),
+    ("OVAL", "OVAL"),
+    ("OVL", "OVAL"),
+    ("OVERPASS", "OPAS"),
+    ("PARK", "PARK"),
+    ("PRK", "PARK"),
+    ("PARKS", "PARK"),
+    ("PARKWAY", "PKWY"),
+    ("PARKWY", "PKWY"),
+    ("PKWAY", "PKWY"),
+    ("PKY", "PKWY"),
+    ("PARKWAYS", "PKWY"),
+    ("PKWYS", "PKWY"),
+    ("PASS", "PASS"),
+    ("PASSAGE", "PSGE"),
+    ("PATH", "PATH"),
+    ("PATHS", "PATH"),
+    ("PIKE", "PIKE"),
+    ("PIKES", "PIKE"),
+    ("PINE", "PNE"),
+    ("PINES", "PNES"),
+    ("PLACE", "PL"),
+    ("PLAIN", "PLN"),
+    ("PLAINS", "PLNS"),
+    ("PLAZA", "PLZ"),
+    ("PLZA", "PLZ"),
+    ("POINT", "PT"),
+    ("POINTS", "PTS"),
+    ("PORT", "PRT"),
+    ("PORTS", "PRTS"),
+    ("PRAIRIE", "PR"),
+    ("PRR", "PR"),
+    ("RADIAL", "RADL"),
+    ("RAD", "RADL"),
+    ("RADIEL", "RADL"),
+    ("RAMP", "RAMP"),
+    ("RANCH", "RNCH"),
+    ("RANCHES", "RNCH"),
+    ("RNCHS", "RNCH"),
+    ("RAPID", "RPD"),
+    ("RAPIDS", "RPDS"),
+    ("REST", "RST"),
+    ("RIDGE", "RDG"),
+    ("RDGE", "RDG"),
+    ("RIDGES", "RDGS"),
+    ("RIVER", "RIV"),
+    ("RVR", "RIV"),
+    ("RIVR", "RIV"),
+    ("ROAD", "RD"),
+    ("ROADS", "RDS"),
+    ("ROUTE", "RTE"),
+    ("ROW", "ROW"),
+    ("RUE", "RUE"),
+    ("RUN", "RUN"),
+    ("SHOAL", "SHL"),
+    ("SHOALS", "SHLS"),
+    ("SHORE", "SHR"),
+    ("SHOAR", "SHR"),
+    ("SHORES", "SHRS"),
+    ("SHOARS", "SHRS"),
+    ("SKYWAY", "SKWY"),
+    ("SPRING", "SPG"),
+    ("SPNG", "SPG"),
+    ("SPRNG", "SPG"),
+    ("SPRINGS", "SPGS"),
+    ("SPNGS", "SPGS"),
+    ("SPRNGS", "SPGS"),
+    ("SPUR", "SPUR"),
+    ("SPURS", "SPUR"),
+    ("SQUARE", "SQ"),
+    ("SQR", "SQ"),
+    ("SQRE", "SQ"),
+    ("SQU", "SQ"),
+    ("SQUARES", "SQS"),
+    ("SQRS", "SQS"),
+    ("STATION", "STA"),
+    ("STATN", "STA"),
+    ("STN", "STA"),
+    ("STRAVENUE", "STRA"),
+    ("STRAV", "STRA"),
+    ("STRAVEN", "STRA"),
+    ("STRAVN", "STRA"),
+    ("STRVN", "STRA"),
+    ("STRVNUE", "STRA"),
+    ("STREAM", "STRM"),
+    ("STREME", "STRM"),
+    ("STREET", "ST"),
+    ("STRT", "ST"),
+    ("STR", "ST"),
+    ("STREETS", "STS"),
+    ("SUMMIT", "SMT"),
+    ("SUMIT", "SMT"),
+    ("SUMITT", "SMT"),
+    ("TERRACE", "TER"),
+    ("TERR", "TER"),
+    ("THROUGHWAY", "TRWY"),
+    ("TRACE", "TRCE"),
+    ("TRACES", "TRCE"),
+    ("TRACK", "TRAK"),
+    ("TRACKS", "TRAK"),
+    ("TRK", "TRAK"),
+    ("TRKS", "TRAK"),
+    ("TRAFFICWAY", "TRFY"),
+    ("TRAIL", "TRL"),
+    ("TRAILS", "TRL"),
+    ("TRLS", "TRL"),
+    ("TRAILER", "TRLR"),
+    ("TRLRS", "TRLR"),
+    ("TUNNEL", "TUNL"),
+    ("TUNEL", "TUNL"),
+    ("TUNLS", "TUNL"),
+    ("TUNNELS", "TUNL"),
+    ("TUNNL", "TUNL"),
+    ("TURNPIKE", "TPKE"),
+    ("TRNPK", "TPKE"),
+    ("TURNPK", "TPKE"),
+    ("UNDERPASS", "UPAS"),
+    ("UNION", "UN"),
+    ("UNIONS", "UNS"),
+    ("VALLEY", "VLY"),
+    ("VALLY", "VLY"),
+    ("VLLY", "VLY"),
+    ("VALLEYS", "VLYS"),
+    ("VIADUCT", "VIA"),
+    ("VDCT", "VIA"),
+    ("VIADCT", "VIA"),
+    ("VIEW", "VW"),
+    ("VIEWS", "VWS"),
+    ("VILLAGE", "VLG"),
+    ("VILL", "VLG"),
+    ("VILLAG", "VLG"),
+    ("VILLG", "VLG"),
+    ("VILLIAGE", "VLG"),
+    ("VILLAGES", "VLGS"),
+    ("VILLE", "VL"),
+    ("VISTA", "VIS"),
+    ("VIST", "VIS"),
+    ("VST", "VIS"),
+    ("VSTA", "VIS"),
+    ("WALK", "WALK"),
+    ("WALKS", "WALK"),
+    ("WALL", "WALL"),
+    ("WAY", "WAY"),
+    ("WY", "WAY"),
+    ("WAYS", "WAYS"),
+    ("WELL", "WL"),
+    ("WELLS", "WLS"),
+];
+
+/// Directionals, in English and Spanish, with their abbreviations.
+const DIRECTIONALS: &[(&str, &str)] = &[
+    ("NORTH", "N"),
+    ("SOUTH", "S"),
+    ("EAST", "E"),
+    ("WEST", "W"),
+    ("NORTHEAST", "NE"),
+    ("NORTHWEST", "NW"),
+    ("SOUTHEAST", "SE"),
+    ("SOUTHWEST", "SW"),
+    ("NORTE", "N"),
+    ("SUR", "S"),
+    ("ESTE", "E"),
+    ("OESTE", "W"),
+    ("NORESTE", "NE"),
+    ("NOROESTE", "NW"),
+    ("SURESTE", "SE"),
+    ("SUROESTE", "SW"),
+];
+
+/// Secondary unit designators with their abbreviations, and whether they
+/// require a number.
+///
+/// From appendix C2 of Publication 28.
+const UNIT_DESIGNATORS: &[(&str, &str, bool)] = &[
+    ("APARTMENT", "APT", true),
+    ("BASEMENT", "BSMT", false),
+    ("BUILDING", "BLDG", true),
+    ("DEPARTMENT", "DEPT", true),
+    ("FLOOR", "FL", true),
+    ("FRONT", "FRNT", false),
+    ("HANGAR", "HNGR", true),
+    ("KEY", "KEY", true),
+    ("LOBBY", "LBBY", false),
+    ("LOT", "LOT", true),
+    ("LOWER", "LOWR", false),
+    ("OFFICE", "OFC", false),
+    ("PENTHOUSE", "PH", false),
+    ("PIER", "PIER", true),
+    ("REAR", "REAR", false),
+    ("ROOM", "RM", true),
+    ("SIDE", "SIDE", false),
+    ("SLIP", "SLIP", true),
+    ("SPACE", "SPC", true),
+    ("STOP", "STOP", true),
+    ("SUITE", "STE", true),
+    ("TRAILER", "TRLR", true),
+    ("UNIT", "UNIT", true),
+    ("UPPER", "UPPR", false),
+];
+
+/// Find the abbreviation of `token` in a table of names and abbreviations.
+fn abbreviate(table: &[(&str, &'static str)], token: &str) -> Option<&'static str> {
+    table
+        .iter()
+        .find(|(name, abbreviation)| *name == token || *abbreviation == token)
+        .map(|&(_, abbreviation)| abbreviation)
+}
+
+/// Find the designator of `token`, and whether it requires a number.
+fn designator(token: &str) -> Option<(&'static str, bool)> {
+    UNIT_DESIGNATORS
+        .iter()
+        .find(|(name, abbreviation, _)| *name == token || *abbreviation == token)
+        .map(|&(_, abbreviation, numbered)| (abbreviation, numbered))
+}
+
+/// Uppercase the tokens of a component, dropping periods and commas.
+fn tokens(component: &str) -> Vec<String> {
+    component
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .map(|token| token.replace('.', "").to_uppercase())
+        .filter(|token| !token.is_empty())
+        .collect()
+}
+
+fn clean(component: &str) -> Option<String> {
+    let tokens = tokens(component);
+    if tokens.is_empty() {
+        None
+    } else {
+        Some(tokens.join(" "))
+    }
+}
+
+/// A road split into the elements of a USPS delivery line.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Street {
+    pub predirectional: Option<String>,
+    pub name: String,
+    pub suffix: Option<String>,
+    pub postdirectional: Option<String>,
+}
+
+impl Street {
+    /// Split and abbreviate a road, e.g. "North Main Street" into "N",
+    /// "MAIN" and "ST".
+    ///
+    /// A directional or suffix is kept in the name if the name would be
+    /// empty otherwise, e.g. in "North Avenue" or "Avenue E".
+    pub fn parse(road: &str) -> Street {
+        let mut tokens = tokens(road);
+        let mut street = Street::default();
+        if tokens.len() > 1 {
+            if let Some(abbreviation) = abbreviate(DIRECTIONALS, &tokens[tokens.len() - 1]) {
+                street.postdirectional = Some(String::from(abbreviation));
+                tokens.pop();
+            }
+        }
+        if tokens.len() > 1 {
+            if let Some(abbreviation) = abbreviate(SUFFIXES, &tokens[tokens.len() - 1]) {
+                street.suffix = Some(String::from(abbreviation));
+                tokens.pop();
+            }
+        }
+        if tokens.len() > 1 {
+            if let Some(abbreviation) = abbreviate(DIRECTIONALS, &tokens[0]) {
+                street.predirectional = Some(String::from(abbreviation));
+                tokens.remove(0);
+            }
+        }
+        street.name = tokens.join(" ");
+        street
+    }
+}
+
+impl fmt::Display for Street {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let parts = [
+            self.predirectional.as_deref(),
+            Some(self.name.as_str()).filter(|name| !name.is_empty()),
+            self.suffix.as_deref(),
+            self.postdirectional.as_deref(),
+        ];
+        let parts: Vec<&str> = parts.iter().flatten().copied().collect();
+        write!(f, "{}", parts.join(" "))
+    }
+}
+
+/// A secondary unit, e.g. "APT 4B".
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SecondaryUnit {
+    /// The abbreviated designator, or "#" if it is unknown.
+    pub designator: String,
+    pub number: Option<String>,
+}
+
+impl SecondaryUnit {
+    /// Parse a unit or level, e.g. "Apartment 4B", "#4B" or "3rd floor".
+    ///
+    /// Returns `None` if there is neither a designator nor a number.
+    pub fn parse(unit: &str) -> Option<SecondaryUnit> {
+        let mut tokens = tokens(unit);
+        if let Some(first) = tokens.first_mut() {
+            if first.starts_with('#') {
+                first.remove(0);
+                let number = Some(tokens.concat()).filter(|number| !number.is_empty());
+                return Some(SecondaryUnit {
+                    designator: String::from("#"),
+                    number,
+                });
+            }
+        }
+        tokens.retain(|token| token != "NO" && token != "NUMBER");
+        let (designator, number) = match (tokens.first(), tokens.last()) {
+            (Some(first), _) if designator(first).is_some() => {
+                (designator(first), tokens[1..].concat())
+            }
+            (_, Some(last)) if designator(last).is_some() => {
+                (designator(last), tokens[..tokens.len() - 1].concat())
+            }
+            (Some(_), _) => (None, tokens.concat()),
+            (None, _) => return None,
+        };
+        let mut number = number.trim_start_matches('#').to_string();
+        // "3rd floor" is level 3, while the letter of "4A" is a suffix.
+        if let Some(marker) = road::ordinal_marker(&number.to_lowercase()) {
+            let len = number.len() - marker.len();
+            number.truncate(len);
+        }
+        let number = Some(number).filter(|n| !n.is_empty());
+        match designator {
+            Some((designator, numbered)) if number.is_some() || !numbered => Some(SecondaryUnit {
+                designator: String::from(designator),
+                number,
+            }),
+            // A designator requiring a number without one is dropped.
+            Some(_) => None,
+            None => Some(SecondaryUnit {
+                designator: String::from("#"),
+                number,
+            }),
+        }
+    }
+}
+
+impl fmt::Display for SecondaryUnit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.number {
+            Some(number) if self.designator == "#" => write!(f, "# {}", number),
+            Some(number) => write!(f, "{} {}", self.designator, number),
+            None => write!(f, "{}", self.designator),
+        }
+    }
+}
+
+/// An address standardized following USPS Publication 28.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UsAddress {
+    pub primary_number: Option<String>,
+    pub street: Option<Street>,
+    pub secondary_unit: Option<SecondaryUnit>,
+    /// The box number of a PO box.
+    pub po_box: Option<String>,
+    pub city: Option<String>,
+    /// The two-letter code of the state, or the uppercase state if unknown.
+    pub state: Option<String>,
+    /// The ZIP or ZIP+4 code.
+    pub zip: Option<String>,
+}
+
+impl UsAddress {
+    /// Return the street delivery line, e.g. "123 N MAIN ST APT 4".
+    pub fn street_line(&self) -> Option<String> {
+        let street = self.street.as_ref().map(Street::to_string);
+        let unit = self.secondary_unit.as_ref().map(SecondaryUnit::to_string);
+        let parts: Vec<String> = vec![self.primary_number.clone(), street, unit]
+            .into_iter()
+            .flatten()
+            .filter(|part| !part.is_empty())
+            .collect();
+        if parts.is_empty() {
+            None
+        } else {
+            Some(parts.join(" "))
+        }
+    }
+
+    /// Return the PO box line, e.g. "PO BOX 123".
+    pub fn po_box_line(&self) -> Option<String> {
+        self.po_box
+            .as_ref()
+            .map(|number| format!("PO BOX {}", number))
+    }
+
+    /// Return the last line, e.g. "BROOKLYN NY 11216-1234".
+    pub fn last_line(&self) -> Option<String> {
+        let state_zip: Vec<&str> = [self.state.as_deref(), self.zip.as_deref()]
+            .iter()
+            .flatten()
+            .copied()
+            .collect();
+        let state_zip = Some(state_zip.join(" ")).filter(|part| !part.is_empty());
+        match (&self.city, state_zip) {
+            (Some(city), Some(state_zip)) => Some(format!("{} {}", city, state_zip)),
+            (city, state_zip) => city.clone().or(state_zip),
+        }
+    }
+
+    /// Return the lines of the address: the street line, the PO box line,
+    /// which takes precedence for delivery, and the last line.
+    pub fn lines(&self) -> Vec<String> {
+        vec![self.street_line(), self.po_box_line(), self.last_line()]
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+}
+
+impl fmt::Display for UsAddress {
+    /// Format the lines of the address, separated by newlines.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.lines().join("\n"))
+    }
+}
+
+/// Return the box number of a PO box, e.g. "123" for "P.O. Box 123".
+fn po_box_number(po_box: &str) -> Option<String> {
+    let tokens: Vec<String> = tokens(po_box)
+        .into_iter()
+        .filter(|token| {
+            !matches!(
+                token.as_str(),
+                "P" | "O" | "PO" | "POB" | "BOX" | "POST" | "OFFICE"
+            )
+        })
+        .collect();
+    if tokens.is_empty() {
+        None
+    } else {
+        Some(tokens.concat())
+    }
+}
+
+/// Standardize a parsed US address.
+///
+/// The unit, or the level in its absence, is the secondary unit.
+pub fn standardize(address: &ParsedAddress) -> UsAddress {
+    let us = country::find("US");
+    UsAddress {
+        primary_number: address.house_number().as_deref().and_then(clean),
+        street: address
+            .road()
+            .map(|road| Street::parse(&road))
+            .filter(|street| !street.to_string().is_empty()),
+        secondary_unit: address
+            .unit()
+            .or_else(|| address.level())
+            .as_deref()
+            .and_then(SecondaryUnit::parse),
+        po_box: address.po_box().as_deref().and_then(po_box_number),
+        city: address.city().as_deref().and_then(clean),
+        state: address.state().and_then(|state| {
+            country::find_subdivision(us, &state)
+                .map(|subdivision| subdivision.local_code().to_string())
+                .or_else(|| clean(&state))
+        }),
+        zip: address
+            .postcode()
+            .and_then(|code| postcode::normalize("US", &code).or_else(|| clean(&code))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::address::{AddressLabel, AddressParserResponse};
+
+    #[test]
+    fn parse_streets() {
+        let cases = [
+            ("north main street", "N MAIN ST"),
+            ("Pennsylvania Avenue Northwest", "PENNSYLVANIA AVE NW"),
+            ("north avenue", "NORTH AVE"),
+            ("avenue e", "AVENUE E"),
+            ("s. 5th blvd.", "S 5TH BLVD"),
+            ("avenue of the americas", "AVENUE OF THE AMERICAS"),
+            ("calle sur", "CALLE S"),
+        ];
+        for (road, expected) in cases.iter() {
+            assert_eq!(Street::parse(road).to_string(), *expected, "{}", road);
+        }
+        let street = Street::parse("w elm ln s");
+        assert_eq!(street.predirectional.as_deref(), Some("W"));
+        assert_eq!(street.name, "ELM");
+        assert_eq!(street.suffix.as_deref(), Some("LN"));
+        assert_eq!(street.postdirectional.as_deref(), Some("S"));
+    }
+
+    #[test]
+    fn parse_secondary_units() {
+        let unit = |unit| SecondaryUnit::parse(unit).map(|unit| unit.to_string());
+        assert_eq!(unit("apartment 4b").as_deref(), Some("APT 4B"));
+        assert_eq!(unit("#12").as_deref(), Some("# 12"));
+        assert_eq!(unit("suite no. 200").as_deref(), Some("STE 200"));
+        assert_eq!(unit("3rd floor").as_deref(), Some("FL 3"));
+        assert_eq!(unit("Floor 2nd").as_deref(), Some("FL 2"));
+        assert_eq!(unit("3º floor").as_deref(), Some("FL 3"));
+        assert_eq!(unit("Apt 4A").as_deref(), Some("APT 4A"));
+        assert_eq!(unit("penthouse").as_deref(), Some("PH"));
+        assert_eq!(unit("17").as_deref(), Some("# 17"));
+        assert_eq!(unit("apt"), None);
+        assert_eq!(unit(""), None);
+    }
+
+    #[test]
+    fn standardize_address() {
+        let response: AddressParserResponse = vec![
+            (AddressLabel::HouseNumber, "660"),
+            (AddressLabel::Road, "nostrand avenue"),
+            (AddressLabel::Unit, "apartment 3"),
+            (AddressLabel::PoBox, "p.o. box 42"),
+            (AddressLabel::City, "brooklyn"),
+            (AddressLabel::State, "new york"),
+            (AddressLabel::Postcode, "112161234"),
+        ]
+        .into_iter()
+        .map(|(label, value)| (label, String::from(value)))
+        .collect();
+        let address = standardize(&ParsedAddress::from(response));
+        assert_eq!(
+            address.lines(),
+            [
+                "660 NOSTRAND AVE APT 3",
+                "PO BOX 42",
+                "BROOKLYN NY 11216-1234"
+            ]
+        );
+        assert_eq!(
+            standardize(&ParsedAddress::default()).lines(),
+            Vec::<String>::new()
+        );
+    }
+}
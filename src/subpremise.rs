@@ -0,0 +1,445 @@
+//! Structured units, levels and staircases.
+//!
+//! The parser returns units, levels and staircases as raw strings, e.g.
+//! "apt 2f", "#2f", "3rd floor" or "2º izq". They are expanded in the
+//! matching component mode, see [`AddressComponents::UNIT`],
+//! [`AddressComponents::LEVEL`] and [`AddressComponents::STAIRCASE`], so
+//! that abbreviations and ordinals are normalized whatever the language,
+//! and split into a [`Designator`], an identifier and a qualifier.
+//!
+//! Without the models for expansion, the raw string is split as is, which
+//! handles the common English abbreviations.
+//!
+//! # Examples
+//!
+//! ```
+//! use rustpostal::expand::AddressComponents;
+//! use rustpostal::subpremise::{Designator, Subpremise};
+//!
+//! fn main() -> Result<(), std::ffi::NulError> {
+//!     let apartment = Subpremise::parse("apt 2f", AddressComponents::UNIT)?.unwrap();
+//!     assert_eq!(apartment.designator, Some(Designator::Apartment));
+//!     assert_eq!(apartment.identifier.as_deref(), Some("2f"));
+//!
+//!     let unit = Subpremise::parse("#2F", AddressComponents::UNIT)?.unwrap();
+//!     assert_eq!(unit.identifier, apartment.identifier);
+//!
+//!     let floor = Subpremise::parse("3rd floor", AddressComponents::LEVEL)?.unwrap();
+//!     assert_eq!(floor.designator, Some(Designator::Floor));
+//!     assert_eq!(floor.identifier.as_deref(), Some("3"));
+//!     Ok(())
+//! }
+//! ```
+use std::ffi::NulError;
+use std::fmt;
+use std::sync::OnceLock;
+
+use crate::expand::{AddressComponents, NormalizeOptions};
+use crate::models::{self, Models};
+use crate::road;
+
+/// The type of a unit, level or staircase.
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Designator {
+    Apartment,
+    Suite,
+    Unit,
+    Room,
+    Office,
+    Building,
+    Floor,
+    Basement,
+    Ground,
+    Mezzanine,
+    Penthouse,
+    Staircase,
+}
+
+impl Designator {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Designator::Apartment => "apartment",
+            Designator::Suite => "suite",
+            Designator::Unit => "unit",
+            Designator::Room => "room",
+            Designator::Office => "office",
+            Designator::Building => "building",
+            Designator::Floor => "floor",
+            Designator::Basement => "basement",
+            Designator::Ground => "ground",
+            Designator::Mezzanine => "mezzanine",
+            Designator::Penthouse => "penthouse",
+            Designator::Staircase => "staircase",
+        }
+    }
+}
+
+impl fmt::Display for Designator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Designator phrases, expanded and without accents, along with common
+/// abbreviations.
+const DESIGNATORS: &[(&str, Designator)] = &[
+    ("apartment", Designator::Apartment),
+    ("apt", Designator::Apartment),
+    ("apts", Designator::Apartment),
+    ("appt", Designator::Apartment),
+    ("apartamento", Designator::Apartment),
+    ("apto", Designator::Apartment),
+    ("appartement", Designator::Apartment),
+    ("appartamento", Designator::Apartment),
+    ("departamento", Designator::Apartment),
+    ("depto", Designator::Apartment),
+    ("dpto", Designator::Apartment),
+    ("flat", Designator::Apartment),
+    ("wohnung", Designator::Apartment),
+    ("whg", Designator::Apartment),
+    ("suite", Designator::Suite),
+    ("ste", Designator::Suite),
+    ("unit", Designator::Unit),
+    ("unite", Designator::Unit),
+    ("unidad", Designator::Unit),
+    ("unita", Designator::Unit),
+    ("einheit", Designator::Unit),
+    ("room", Designator::Room),
+    ("rm", Designator::Room),
+    ("chambre", Designator::Room),
+    ("habitacion", Designator::Room),
+    ("zimmer", Designator::Room),
+    ("stanza", Designator::Room),
+    ("kamer", Designator::Room),
+    ("office", Designator::Office),
+    ("ofc", Designator::Office),
+    ("bureau", Designator::Office),
+    ("oficina", Designator::Office),
+    ("ufficio", Designator::Office),
+    ("buro", Designator::Office),
+    ("building", Designator::Building),
+    ("bldg", Designator::Building),
+    ("edificio", Designator::Building),
+    ("batiment", Designator::Building),
+    ("bat", Designator::Building),
+    ("gebaude", Designator::Building),
+    ("block", Designator::Building),
+    ("blk", Designator::Building),
+    ("floor", Designator::Floor),
+    ("fl", Designator::Floor),
+    ("flr", Designator::Floor),
+    ("level", Designator::Floor),
+    ("lvl", Designator::Floor),
+    ("storey", Designator::Floor),
+    ("etage", Designator::Floor),
+    ("piso", Designator::Floor),
+    ("planta", Designator::Floor),
+    ("stock", Designator::Floor),
+    ("obergeschoss", Designator::Floor),
+    ("og", Designator::Floor),
+    ("piano", Designator::Floor),
+    ("verdieping", Designator::Floor),
+    ("andar", Designator::Floor),
+    ("basement", Designator::Basement),
+    ("bsmt", Designator::Basement),
+    ("sous sol", Designator::Basement),
+    ("sotano", Designator::Basement),
+    ("souterrain", Designator::Basement),
+    ("untergeschoss", Designator::Basement),
+    ("ug", Designator::Basement),
+    ("kelder", Designator::Basement),
+    ("seminterrato", Designator::Basement),
+    ("ground floor", Designator::Ground),
+    ("ground", Designator::Ground),
+    ("gf", Designator::Ground),
+    ("rez de chaussee", Designator::Ground),
+    ("rdc", Designator::Ground),
+    ("erdgeschoss", Designator::Ground),
+    ("eg", Designator::Ground),
+    ("planta baja", Designator::Ground),
+    ("bajo", Designator::Ground),
+    ("piano terra", Designator::Ground),
+    ("begane grond", Designator::Ground),
+    ("mezzanine", Designator::Mezzanine),
+    ("entresuelo", Designator::Mezzanine),
+    ("entreplanta", Designator::Mezzanine),
+    ("mezzanino", Designator::Mezzanine),
+    ("penthouse", Designator::Penthouse),
+    ("ph", Designator::Penthouse),
+    ("atico", Designator::Penthouse),
+    ("attique", Designator::Penthouse),
+    ("attico", Designator::Penthouse),
+    ("dachgeschoss", Designator::Penthouse),
+    ("staircase", Designator::Staircase),
+    ("stair", Designator::Staircase),
+    ("stairs", Designator::Staircase),
+    ("escalera", Designator::Staircase),
+    ("esc", Designator::Staircase),
+    ("escalier", Designator::Staircase),
+    ("stiege", Designator::Staircase),
+    ("treppe", Designator::Staircase),
+    ("scala", Designator::Staircase),
+];
+
+/// Words marking an identifier without designating anything, e.g. "#".
+const NUMBER_WORDS: &[&str] = &["#", "no", "nr", "number", "numero", "num"];
+
+/// A structured unit, level or staircase.
+#[derive(Clone, Debug, Default, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Subpremise {
+    /// The designator, if any, e.g. `Apartment` for "apt 2f".
+    ///
+    /// Levels and staircases without one default to `Floor` and
+    /// `Staircase`.
+    pub designator: Option<Designator>,
+    /// The lowercase identifier, e.g. "2f", without ordinal markers for
+    /// levels, e.g. "3" for "3rd floor".
+    pub identifier: Option<String>,
+    /// The remaining words, e.g. "izquierda" for "2º izquierda".
+    pub qualifier: Option<String>,
+}
+
+/// Split a lowercase string into words, separating "#" and dropping
+/// punctuation.
+fn words(value: &str) -> Vec<String> {
+    value
+        .replace('#', " # ")
+        .split(|c: char| c.is_whitespace() || c == ',' || c == '-')
+        .map(|word| word.trim_matches('.').to_string())
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+fn is_identifier(word: &str) -> bool {
+    word.chars().any(|c| c.is_numeric())
+        || (word.chars().count() == 1 && word.chars().all(char::is_alphabetic))
+}
+
+/// Drop the ordinal marker of a level, e.g. "3rd" to "3".
+fn strip_ordinal(word: &str) -> &str {
+    match road::ordinal_marker(word) {
+        Some(marker) => &word[..word.len() - marker.len()],
+        None => word,
+    }
+}
+
+/// The words of the designator phrases, longer phrases first, e.g.
+/// "planta baja" before "planta".
+fn phrases() -> &'static [(Vec<&'static str>, Designator)] {
+    static PHRASES: OnceLock<Vec<(Vec<&'static str>, Designator)>> = OnceLock::new();
+    PHRASES.get_or_init(|| {
+        let mut phrases: Vec<(Vec<&str>, Designator)> = DESIGNATORS
+            .iter()
+            .map(|&(phrase, designator)| (phrase.split(' ').collect(), designator))
+            .collect();
+        phrases.sort_by_key(|(phrase, _)| std::cmp::Reverse(phrase.len()));
+        phrases
+    })
+}
+
+/// Split a lowercase string into a subpremise, without default designator.
+///
+/// The identifier is made of the identifier words following the designator,
+/// or else preceding it, e.g. "2" for "bldg 3 apt 2". Other words, including
+/// the identifiers of other designators, are kept in the qualifier.
+fn split(value: &str, component: AddressComponents) -> Subpremise {
+    let mut words = words(value);
+    words.retain(|word| !NUMBER_WORDS.contains(&word.as_str()));
+    let mut designator = None;
+    let mut position = None;
+    for (phrase, phrase_designator) in phrases() {
+        let start = words
+            .windows(phrase.len())
+            .position(|window| window.iter().map(String::as_str).eq(phrase.iter().copied()));
+        if let Some(start) = start {
+            designator = Some(*phrase_designator);
+            position = Some(start);
+            words.drain(start..start + phrase.len());
+            break;
+        }
+    }
+    let end_of_run = |start: usize| {
+        words[start..]
+            .iter()
+            .position(|word| !is_identifier(word))
+            .map_or(words.len(), |len| start + len)
+    };
+    let run = match position {
+        Some(start) if words.get(start).is_some_and(|word| is_identifier(word)) => {
+            start..end_of_run(start)
+        }
+        Some(start) => {
+            let begin = words[..start]
+                .iter()
+                .rposition(|word| !is_identifier(word))
+                .map_or(0, |index| index + 1);
+            begin..start
+        }
+        None => match words.iter().position(|word| is_identifier(word)) {
+            Some(start) => start..end_of_run(start),
+            None => 0..0,
+        },
+    };
+    let is_level = component.contains(AddressComponents::LEVEL);
+    let mut identifier = String::new();
+    let mut qualifier = Vec::new();
+    for (index, word) in words.iter().enumerate() {
+        if run.contains(&index) {
+            let word = if is_level {
+                strip_ordinal(word)
+            } else {
+                word.trim_end_matches(['º', 'ª'])
+            };
+            identifier.push_str(word);
+        } else {
+            qualifier.push(word.as_str());
+        }
+    }
+    Subpremise {
+        designator,
+        identifier: Some(identifier).filter(|identifier| !identifier.is_empty()),
+        qualifier: Some(qualifier.join(" ")).filter(|qualifier| !qualifier.is_empty()),
+    }
+}
+
+/// The designator of the values of `component` without one.
+fn default_designator(component: AddressComponents) -> Option<Designator> {
+    if component.contains(AddressComponents::LEVEL) {
+        Some(Designator::Floor)
+    } else if component.contains(AddressComponents::STAIRCASE) {
+        Some(Designator::Staircase)
+    } else {
+        None
+    }
+}
+
+impl Subpremise {
+    /// Parse a unit, level or staircase, expanded in the mode of
+    /// `component`, e.g. [`AddressComponents::UNIT`].
+    ///
+    /// The expansion takes place if the models for expansion are setup,
+    /// e.g. with [`LibModules::Expand`](crate::LibModules::Expand).
+    /// Returns `None` if there is neither a designator nor an identifier.
+    ///
+    /// # Errors
+    ///
+    /// It will return an error if the value contains an internal null byte.
+    pub fn parse(
+        value: &str,
+        component: AddressComponents,
+    ) -> Result<Option<Subpremise>, NulError> {
+        let mut variations = Vec::new();
+        if models::loaded().contains(Models::BASE | Models::CLASSIFIER) {
+            let mut options = NormalizeOptions::default();
+            options.add_address_component(component);
+            variations = Vec::from(options.expand(value)?);
+        }
+        variations.push(value.to_lowercase());
+        let subpremises: Vec<Subpremise> = variations
+            .iter()
+            .map(|variation| split(variation, component))
+            .collect();
+        // Prefer the first variation with a designator.
+        let mut subpremise = subpremises
+            .iter()
+            .find(|subpremise| subpremise.designator.is_some())
+            .unwrap_or(&subpremises[0])
+            .clone();
+        if subpremise.designator.is_none() && subpremise.identifier.is_none() {
+            return Ok(None);
+        }
+        subpremise.designator = subpremise
+            .designator
+            .or_else(|| default_designator(component));
+        Ok(Some(subpremise))
+    }
+}
+
+impl fmt::Display for Subpremise {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let designator = self.designator.map(|designator| designator.as_str());
+        let parts: Vec<&str> = [
+            designator,
+            self.identifier.as_deref(),
+            self.qualifier.as_deref(),
+        ]
+        .iter()
+        .flatten()
+        .copied()
+        .collect();
+        write!(f, "{}", parts.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_units() {
+        let unit = |value| split(value, AddressComponents::UNIT);
+        let apartment = unit("apt 2f");
+        assert_eq!(apartment.designator, Some(Designator::Apartment));
+        assert_eq!(apartment.identifier.as_deref(), Some("2f"));
+        assert_eq!(unit("#2f").identifier.as_deref(), Some("2f"));
+        assert_eq!(unit("#2f").designator, None);
+        assert_eq!(unit("suite no. 200").to_string(), "suite 200");
+        assert_eq!(unit("unit b").identifier.as_deref(), Some("b"));
+        // Letters of unit identifiers are not ordinal markers.
+        assert_eq!(unit("apartamento 2a").identifier.as_deref(), Some("2a"));
+        // Only the identifier of the matched designator is kept.
+        let apartment = unit("bldg 3 apt 2");
+        assert_eq!(apartment.designator, Some(Designator::Apartment));
+        assert_eq!(apartment.identifier.as_deref(), Some("2"));
+        assert_eq!(apartment.qualifier.as_deref(), Some("bldg 3"));
+    }
+
+    #[test]
+    fn split_levels() {
+        let level = |value| split(value, AddressComponents::LEVEL);
+        assert_eq!(level("3rd floor").to_string(), "floor 3");
+        assert_eq!(level("2º izq").to_string(), "2 izq");
+        assert_eq!(level("planta baja").designator, Some(Designator::Ground));
+        assert_eq!(level("sous-sol 2").to_string(), "basement 2");
+        assert_eq!(level("4").designator, None);
+        // Letters of level identifiers are not ordinal markers either.
+        assert_eq!(level("floor 2a").identifier.as_deref(), Some("2a"));
+    }
+
+    #[test]
+    fn split_staircases() {
+        let staircase = split("escalera b", AddressComponents::STAIRCASE);
+        assert_eq!(staircase.designator, Some(Designator::Staircase));
+        assert_eq!(staircase.identifier.as_deref(), Some("b"));
+    }
+
+    #[test]
+    fn parse_default_designators() -> Result<(), NulError> {
+        let designator = |value, component| {
+            Subpremise::parse(value, component).map(|s| s.and_then(|s| s.designator))
+        };
+        assert_eq!(
+            designator("4", AddressComponents::LEVEL)?,
+            Some(Designator::Floor)
+        );
+        assert_eq!(
+            designator("3", AddressComponents::STAIRCASE)?,
+            Some(Designator::Staircase)
+        );
+        assert_eq!(designator("3", AddressComponents::UNIT)?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn parse_empty() -> Result<(), NulError> {
+        assert_eq!(Subpremise::parse("", AddressComponents::UNIT)?, None);
+        assert_eq!(Subpremise::parse("left", AddressComponents::UNIT)?, None);
+        Ok(())
+    }
+}
@@ -1,3 +1,4 @@
+#![cfg(feature = "parser")]
 extern crate rustpostal;
 use rustpostal::address::ParsedAddress;
 use rustpostal::error::RuntimeError;
@@ -1,3 +1,4 @@
+#![cfg(feature = "expand")]
 extern crate rustpostal;
 
 use rustpostal::error::RuntimeError;
@@ -80,3 +81,34 @@ fn expand_with_options() -> Result<(), RuntimeError> {
     }
     Ok(())
 }
+
+#[test]
+fn expand_root() -> Result<(), RuntimeError> {
+    let postal_module = LibModules::Expand;
+    postal_module.setup()?;
+    let mut options = expand::NormalizeOptions::new(Some(["en"].iter()))?;
+    let roots = options.expand_root("123 Main St")?;
+    assert!(roots.iter().any(|root| root == "123 main"));
+    Ok(())
+}
+
+#[test]
+fn expand_canonical() -> Result<(), RuntimeError> {
+    let postal_module = LibModules::Expand;
+    postal_module.setup()?;
+    for (address, _, lang) in TEST_CASES {
+        let normalized = expand::expand_address_with_options(address, Some([*lang].iter()))?;
+        let canonical = normalized.canonical().unwrap();
+        assert_eq!(normalized.ranked()[0], canonical);
+
+        // Independent of the order of the variations.
+        let mut variations = Vec::from(normalized.clone());
+        variations.reverse();
+        let reversed = expand::NormalizedAddress::from(variations);
+        assert_eq!(reversed.canonical(), Some(canonical));
+    }
+    let mut options = expand::NormalizeOptions::new(Some(["en", "fr"].iter()))?;
+    let canonical = options.expand_canonical("123 Main St")?;
+    assert_eq!(canonical.as_deref(), Some("123 main street"));
+    Ok(())
+}